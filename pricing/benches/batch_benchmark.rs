@@ -0,0 +1,62 @@
+extern crate pricing;
+use pricing::analytic::batch::{BlackScholesMertonBatch, DerivativeParameterBatch};
+use pricing::analytic::black_scholes::{BlackScholesMerton, OptionPrice};
+use pricing::common::models::{DerivativeParameter, ExerciseType};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use ndarray::Array;
+
+criterion_group!(benches, criterion_option_chain);
+criterion_main!(benches);
+
+const NR_CONTRACTS: usize = 100_000;
+
+pub fn criterion_option_chain(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Black-Scholes-Merton option chain");
+    let strikes = Array::linspace(50.0, 150.0, NR_CONTRACTS);
+    let expirations = Array::linspace(0.1, 2.0, NR_CONTRACTS);
+
+    group.bench_function("price the contracts one at a time", |b| {
+        b.iter(|| {
+            strikes
+                .iter()
+                .zip(&expirations)
+                .map(|(strike, expiration)| {
+                    let dp = DerivativeParameter::new(100.0, *strike, *expiration, 0.03, 0.25);
+                    BlackScholesMerton::call(black_box(&dp))
+                })
+                .sum::<f64>()
+        })
+    });
+
+    group.bench_function("price the contracts as a batch", |b| {
+        b.iter(|| {
+            let batch = DerivativeParameterBatch::new(
+                &[100.0][..],
+                &strikes,
+                &expirations,
+                &[0.03][..],
+                &[0.25][..],
+            )
+            .unwrap();
+            BlackScholesMertonBatch::price(black_box(&batch), &ExerciseType::Call).sum()
+        })
+    });
+
+    group.bench_function("value the contracts with all Greeks as a batch", |b| {
+        b.iter(|| {
+            let batch = DerivativeParameterBatch::new(
+                &[100.0][..],
+                &strikes,
+                &expirations,
+                &[0.03][..],
+                &[0.25][..],
+            )
+            .unwrap();
+            BlackScholesMertonBatch::valuation(black_box(&batch), &ExerciseType::Call)
+                .price()
+                .sum()
+        })
+    });
+    group.finish();
+}
@@ -145,7 +145,8 @@ fn basket_stock_price_simulation((nr_paths, nr_steps): (usize, usize)) {
     let dt = 1.0;
 
     let mv_gbm =
-        MultivariateGeometricBrownianMotion::new(initial_values, drifts, cholesky_factor, dt);
+        MultivariateGeometricBrownianMotion::new(initial_values, drifts, cholesky_factor, dt)
+            .unwrap();
 
     let mc_simulator: MonteCarloPathSimulator<_, rand_hc::Hc128Rng, Array2<f64>> =
         MonteCarloPathSimulator::new(mv_gbm, Some(42));
@@ -213,7 +214,7 @@ where
 {
     let mu = arr1(&[0.1, 0.2, 0.3]);
     let cholesky_factor = arr2(&[[1.0, 0.5, 0.1], [0.0, 0.6, 0.7], [0.0, 0.0, 0.8]]);
-    let mv_normal = MultivariateNormalDistribution::new(mu, cholesky_factor);
+    let mv_normal = MultivariateNormalDistribution::new(mu, cholesky_factor).unwrap();
 
     let mc_simulator: MonteCarloPathSimulator<_, SeedRng, Array2<f64>> =
         MonteCarloPathSimulator::new(mv_normal, Some(seed));
@@ -227,7 +228,7 @@ where
 {
     let mu = arr1(&[0.1, 0.2, 0.3]);
     let cholesky_factor = arr2(&[[1.0, 0.5, 0.1], [0.0, 0.6, 0.7], [0.0, 0.0, 0.8]]);
-    let mv_normal = MultivariateNormalDistribution::new(mu, cholesky_factor);
+    let mv_normal = MultivariateNormalDistribution::new(mu, cholesky_factor).unwrap();
 
     let mc_simulator: MonteCarloPathSimulator<_, SeedRng, Vec<_>> =
         MonteCarloPathSimulator::new(mv_normal, Some(seed));
@@ -238,7 +239,7 @@ where
 // fn multivariate_normal_distr_slice_path_allocated((nr_paths, nr_steps, seed): (usize, usize, u64)) {
 //     let mu = arr1(&[0.1, 0.2, 0.3]);
 //     let cholesky_factor = arr2(&[[1.0, 0.5, 0.1], [0.0, 0.6, 0.7], [0.0, 0.0, 0.8]]);
-//     let mv_normal = MultivariateNormalDistribution::new(mu, cholesky_factor);
+//     let mv_normal = MultivariateNormalDistribution::new(mu, cholesky_factor).unwrap();
 
 //     let mc_simulator: MonteCarloPathSimulator<SlicePath> =
 //         MonteCarloPathSimulator::new(nr_paths, nr_steps);
@@ -0,0 +1,567 @@
+use crate::analytic::black_scholes::{BlackScholesMerton, OptionPrice};
+use crate::common::models::{DerivativeParameter, ExerciseType};
+use crate::common::normal::{bivariate_cdf, cdf, pdf};
+
+const NEWTON_TOLERANCE: f64 = 1e-10;
+const MAX_NEWTON_ITERATIONS: usize = 100;
+
+/// Whether early exercise is never optimal, i.e. for calls with $b \ge r$ and puts with $r \le 0$.
+fn is_european(dp: &DerivativeParameter, exercise_type: &ExerciseType) -> bool {
+    match exercise_type {
+        ExerciseType::Call => dp.cost_of_carry() >= dp.rfr,
+        ExerciseType::Put => dp.rfr <= 0.0,
+    }
+}
+
+/// The critical asset price of a European exercise, infinite for calls and zero for puts.
+fn no_exercise_boundary(exercise_type: &ExerciseType) -> f64 {
+    match exercise_type {
+        ExerciseType::Call => f64::INFINITY,
+        ExerciseType::Put => 0.0,
+    }
+}
+
+/// Without volatility or at expiry the asset moves deterministically to the forward, and the
+/// option is exercised today, at expiry or when $S e^{b \tau} = r X / (r - b)$, where the
+/// discounted payoff is stationary.
+fn deterministic_price(dp: &DerivativeParameter, exercise_type: &ExerciseType) -> f64 {
+    let phi = exercise_type.sign();
+    let (s, x, t, r, b) = (
+        dp.asset_price,
+        dp.strike,
+        dp.time_to_expiration,
+        dp.rfr,
+        dp.cost_of_carry(),
+    );
+    let discounted_payoff =
+        |tau: f64| (-r * tau).exp() * (phi * (s * (b * tau).exp() - x)).max(0.0);
+    let stationary = (r * x / ((r - b) * s)).ln() / b;
+    let stationary = if stationary.is_finite() {
+        stationary.clamp(0.0, t)
+    } else {
+        0.0
+    };
+    [0.0, stationary, t]
+        .into_iter()
+        .map(discounted_payoff)
+        .fold(0.0, f64::max)
+}
+
+/// The quadratic approximation of American options, which solves the PDE of the early exercise
+/// premium with the time derivative neglected.
+/// See Haug, The Complete Guide to Option Pricing Formulas, chapter 3.1.2, and Barone-Adesi and
+/// Whaley, Efficient Analytic Approximation of American Option Values (1987).
+pub struct BaroneAdesiWhaley;
+
+impl BaroneAdesiWhaley {
+    /// The exponent $q_2$ for calls or $q_1$ for puts of the early exercise premium.
+    fn exponent(dp: &DerivativeParameter, phi: f64, discount_factor: f64) -> f64 {
+        let sigma2 = dp.vola.powi(2);
+        let n = 2.0 * dp.cost_of_carry() / sigma2;
+        let m = 2.0 * dp.rfr / sigma2;
+        (-(n - 1.0) + phi * ((n - 1.0).powi(2) + 4.0 * m / (1.0 - discount_factor)).sqrt()) / 2.0
+    }
+
+    /// The critical asset price above which a call and below which a put is exercised, solved by
+    /// Newton's method from the seed of Barone-Adesi and Whaley. At expiry the option is
+    /// exercised whenever it is in the money.
+    pub fn exercise_boundary(dp: &DerivativeParameter, exercise_type: &ExerciseType) -> f64 {
+        if is_european(dp, exercise_type) {
+            return no_exercise_boundary(exercise_type);
+        }
+        if dp.time_to_expiration == 0.0 {
+            return dp.strike;
+        }
+        let phi = exercise_type.sign();
+        let (x, t, r, b, sigma) = (
+            dp.strike,
+            dp.time_to_expiration,
+            dp.rfr,
+            dp.cost_of_carry(),
+            dp.vola,
+        );
+        let sigma_sqrt_t = sigma * t.sqrt();
+        let carry_factor = ((b - r) * t).exp();
+        let q = Self::exponent(dp, phi, (-r * t).exp());
+
+        // the boundary of the perpetual option interpolated towards the strike
+        let q_infinity = Self::exponent(dp, phi, 0.0);
+        let perpetual = x / (1.0 - 1.0 / q_infinity);
+        let h = -(b * t + phi * 2.0 * sigma_sqrt_t) * x / (perpetual - x);
+        let mut boundary = x + (perpetual - x) * (1.0 - h.exp());
+
+        for _ in 0..MAX_NEWTON_ITERATIONS {
+            let at_boundary = DerivativeParameter {
+                asset_price: boundary,
+                ..*dp
+            };
+            let d1 = ((boundary / x).ln() + (b + sigma.powi(2) / 2.0) * t) / sigma_sqrt_t;
+            let european = BlackScholesMerton::price(&at_boundary, exercise_type);
+            // value matching phi (S - X) = V(S) + phi (1 - e^{(b - r)T} N(phi d1)) S / q
+            let lhs = phi * (boundary - x);
+            let rhs = european + phi * (1.0 - carry_factor * cdf(phi * d1)) * boundary / q;
+            if (lhs - rhs).abs() < NEWTON_TOLERANCE * x {
+                break;
+            }
+            let slope = phi * carry_factor * cdf(phi * d1) * (1.0 - 1.0 / q)
+                + (phi - carry_factor * pdf(d1) / sigma_sqrt_t) / q;
+            boundary = (phi * x + rhs - slope * boundary) / (phi - slope);
+        }
+        boundary
+    }
+
+    fn baw_price(dp: &DerivativeParameter, exercise_type: &ExerciseType) -> f64 {
+        let european = BlackScholesMerton::price(dp, exercise_type);
+        if is_european(dp, exercise_type) {
+            return european;
+        }
+        if dp.vola * dp.time_to_expiration.sqrt() == 0.0 {
+            return deterministic_price(dp, exercise_type);
+        }
+        let phi = exercise_type.sign();
+        let (s, x, t, b, sigma) = (
+            dp.asset_price,
+            dp.strike,
+            dp.time_to_expiration,
+            dp.cost_of_carry(),
+            dp.vola,
+        );
+        let boundary = Self::exercise_boundary(dp, exercise_type);
+        if phi * (s - boundary) >= 0.0 {
+            return phi * (s - x);
+        }
+        let q = Self::exponent(dp, phi, (-dp.rfr * t).exp());
+        let d1 = ((boundary / x).ln() + (b + sigma.powi(2) / 2.0) * t) / (sigma * t.sqrt());
+        let carry_factor = ((b - dp.rfr) * t).exp();
+        let premium = phi * boundary / q * (1.0 - carry_factor * cdf(phi * d1));
+        european + premium * (s / boundary).powf(q)
+    }
+}
+
+impl OptionPrice for BaroneAdesiWhaley {
+    type Params = DerivativeParameter;
+
+    fn call(dp: &DerivativeParameter) -> f64 {
+        Self::baw_price(dp, &ExerciseType::Call)
+    }
+
+    fn put(dp: &DerivativeParameter) -> f64 {
+        Self::baw_price(dp, &ExerciseType::Put)
+    }
+}
+
+/// Bjerksund and Stensland's approximation of American options by exercise strategies with flat
+/// boundaries on the intervals $[0, t_1]$ and $[t_1, T]$, where $t_1 = (\sqrt{5} - 1) T / 2$.
+/// Puts are valued as calls by the put-call transformation
+/// $P(S, X, T, r, b, \sigma) = C(X, S, T, r - b, -b, \sigma)$. The boundaries fall below the
+/// strike for $b T + 2 \sigma \sqrt{T} < 0$, which makes very long dated calls with negative cost
+/// of carry unreliable.
+/// See Haug, The Complete Guide to Option Pricing Formulas, chapter 3.1.4, and Bjerksund and
+/// Stensland, Closed Form Valuation of American Options (2002).
+pub struct BjerksundStensland;
+
+/// The flat exercise boundaries $I_1$ on $[t_1, T]$ and $I_2$ on $[0, t_1]$ of a call, and the
+/// exponent $\beta$ of the perpetual option.
+struct FlatBoundaries {
+    beta: f64,
+    t1: f64,
+    i1: f64,
+    i2: f64,
+}
+
+impl BjerksundStensland {
+    /// The parameters of the call into which the put-call transformation turns the option.
+    fn as_call(dp: &DerivativeParameter, exercise_type: &ExerciseType) -> DerivativeParameter {
+        match exercise_type {
+            ExerciseType::Call => *dp,
+            ExerciseType::Put => DerivativeParameter {
+                asset_price: dp.strike,
+                strike: dp.asset_price,
+                rfr: dp.dividend_yield,
+                dividend_yield: dp.rfr,
+                ..*dp
+            },
+        }
+    }
+
+    fn flat_boundaries(dp: &DerivativeParameter) -> FlatBoundaries {
+        let (x, t, r, b, sigma2) = (
+            dp.strike,
+            dp.time_to_expiration,
+            dp.rfr,
+            dp.cost_of_carry(),
+            dp.vola.powi(2),
+        );
+        let beta = (0.5 - b / sigma2) + ((b / sigma2 - 0.5).powi(2) + 2.0 * r / sigma2).sqrt();
+        let b_infinity = beta / (beta - 1.0) * x;
+        let b_zero = x.max(r / (r - b) * x);
+        let t1 = (5.0_f64.sqrt() - 1.0) / 2.0 * t;
+        let boundary = |tau: f64| {
+            let h = -(b * tau + 2.0 * dp.vola * tau.sqrt()) * x.powi(2)
+                / ((b_infinity - b_zero) * b_zero);
+            b_zero + (b_infinity - b_zero) * (1.0 - h.exp())
+        };
+        FlatBoundaries {
+            beta,
+            t1,
+            i1: boundary(t1),
+            i2: boundary(t),
+        }
+    }
+
+    /// The critical asset price above which a call and below which a put is exercised, the
+    /// strike at expiry.
+    pub fn exercise_boundary(dp: &DerivativeParameter, exercise_type: &ExerciseType) -> f64 {
+        if is_european(dp, exercise_type) {
+            return no_exercise_boundary(exercise_type);
+        }
+        if dp.time_to_expiration == 0.0 {
+            return dp.strike;
+        }
+        // the boundaries of the transformed call do not depend on the asset price
+        let at_strike = DerivativeParameter {
+            asset_price: dp.strike,
+            ..*dp
+        };
+        let i2 = Self::flat_boundaries(&Self::as_call(&at_strike, exercise_type)).i2;
+        match exercise_type {
+            ExerciseType::Call => i2,
+            // I_2 is proportional to the strike S of the transformed call, which is exercised
+            // for X >= S I_2 / X
+            ExerciseType::Put => dp.strike.powi(2) / i2,
+        }
+    }
+
+    fn bs_price(dp: &DerivativeParameter, exercise_type: &ExerciseType) -> f64 {
+        if is_european(dp, exercise_type) {
+            return BlackScholesMerton::price(dp, exercise_type);
+        }
+        if dp.vola * dp.time_to_expiration.sqrt() == 0.0 {
+            return deterministic_price(dp, exercise_type);
+        }
+        let call = Self::as_call(dp, exercise_type);
+        let (s, x) = (call.asset_price, call.strike);
+        let FlatBoundaries { beta, t1, i1, i2 } = Self::flat_boundaries(&call);
+        if s >= i2 {
+            return s - x;
+        }
+        let alpha1 = (i1 - x) * i1.powf(-beta);
+        let alpha2 = (i2 - x) * i2.powf(-beta);
+        let phi = |gamma, h, i| Self::phi(&call, t1, gamma, h, i);
+        let psi = |gamma, h| Self::psi(&call, gamma, h, i2, i1, t1);
+        alpha2 * s.powf(beta) - alpha2 * phi(beta, i2, i2) + phi(1.0, i2, i2)
+            - phi(1.0, i1, i2)
+            - x * phi(0.0, i2, i2)
+            + x * phi(0.0, i1, i2)
+            + alpha1 * phi(beta, i1, i2)
+            - alpha1 * psi(beta, i1)
+            + psi(1.0, i1)
+            - psi(1.0, x)
+            - x * psi(0.0, i1)
+            + x * psi(0.0, x)
+    }
+
+    /// The value of $S_t^\gamma$ paid at time $t$ unless the asset crosses $I$ before, if
+    /// $S_t \le H$.
+    fn phi(dp: &DerivativeParameter, t: f64, gamma: f64, h: f64, i: f64) -> f64 {
+        let (s, r, b, sigma) = (dp.asset_price, dp.rfr, dp.cost_of_carry(), dp.vola);
+        let sigma_sqrt_t = sigma * t.sqrt();
+        let lambda = (-r + gamma * b + gamma * (gamma - 1.0) * sigma.powi(2) / 2.0) * t;
+        let d = -((s / h).ln() + (b + (gamma - 0.5) * sigma.powi(2)) * t) / sigma_sqrt_t;
+        let kappa = 2.0 * b / sigma.powi(2) + 2.0 * gamma - 1.0;
+        lambda.exp()
+            * s.powf(gamma)
+            * (cdf(d) - (i / s).powf(kappa) * cdf(d - 2.0 * (i / s).ln() / sigma_sqrt_t))
+    }
+
+    /// The two period analogue of [Self::phi] with the boundary $I_2$ until $t_1$ and $I_1$
+    /// until $T$.
+    fn psi(dp: &DerivativeParameter, gamma: f64, h: f64, i2: f64, i1: f64, t1: f64) -> f64 {
+        let (s, t, r, b, sigma) = (
+            dp.asset_price,
+            dp.time_to_expiration,
+            dp.rfr,
+            dp.cost_of_carry(),
+            dp.vola,
+        );
+        let drift = b + (gamma - 0.5) * sigma.powi(2);
+        let (sigma_sqrt_t1, sigma_sqrt_t) = (sigma * t1.sqrt(), sigma * t.sqrt());
+        let e1 = ((s / i1).ln() + drift * t1) / sigma_sqrt_t1;
+        let e2 = ((i2.powi(2) / (s * i1)).ln() + drift * t1) / sigma_sqrt_t1;
+        let e3 = ((s / i1).ln() - drift * t1) / sigma_sqrt_t1;
+        let e4 = ((i2.powi(2) / (s * i1)).ln() - drift * t1) / sigma_sqrt_t1;
+        let f1 = ((s / h).ln() + drift * t) / sigma_sqrt_t;
+        let f2 = ((i2.powi(2) / (s * h)).ln() + drift * t) / sigma_sqrt_t;
+        let f3 = ((i1.powi(2) / (s * h)).ln() + drift * t) / sigma_sqrt_t;
+        let f4 = ((s * i1.powi(2) / (h * i2.powi(2))).ln() + drift * t) / sigma_sqrt_t;
+        let rho = (t1 / t).sqrt();
+        let lambda = -r + gamma * b + gamma * (gamma - 1.0) * sigma.powi(2) / 2.0;
+        let kappa = 2.0 * b / sigma.powi(2) + 2.0 * gamma - 1.0;
+        (lambda * t).exp()
+            * s.powf(gamma)
+            * (bivariate_cdf(-e1, -f1, rho)
+                - (i2 / s).powf(kappa) * bivariate_cdf(-e2, -f2, rho)
+                - (i1 / s).powf(kappa) * bivariate_cdf(-e3, -f3, -rho)
+                + (i1 / i2).powf(kappa) * bivariate_cdf(-e4, -f4, -rho))
+    }
+}
+
+impl OptionPrice for BjerksundStensland {
+    type Params = DerivativeParameter;
+
+    fn call(dp: &DerivativeParameter) -> f64 {
+        Self::bs_price(dp, &ExerciseType::Call)
+    }
+
+    fn put(dp: &DerivativeParameter) -> f64 {
+        Self::bs_price(dp, &ExerciseType::Put)
+    }
+}
+
+/// American options without expiration, whose exercise boundary is flat. The time to
+/// expiration of the option parameters is not used. A call on an asset without dividends is
+/// never exercised and worth the asset itself, as is a put worth the strike for $r \le 0$.
+/// See Haug, The Complete Guide to Option Pricing Formulas, chapter 3.1.1.
+pub struct PerpetualAmerican;
+
+impl PerpetualAmerican {
+    /// The roots $y_1 > 1$ for calls and $y_2 < 0$ for puts of
+    /// $\sigma^2 y (y - 1) / 2 + b y - r = 0$.
+    fn exponent(dp: &DerivativeParameter, exercise_type: &ExerciseType) -> f64 {
+        let sigma2 = dp.vola.powi(2);
+        let b = dp.cost_of_carry();
+        0.5 - b / sigma2
+            + exercise_type.sign() * ((b / sigma2 - 0.5).powi(2) + 2.0 * dp.rfr / sigma2).sqrt()
+    }
+
+    /// The critical asset price $X y / (y - 1)$ above which a call and below which a put is
+    /// exercised.
+    pub fn exercise_boundary(dp: &DerivativeParameter, exercise_type: &ExerciseType) -> f64 {
+        if is_european(dp, exercise_type) {
+            return no_exercise_boundary(exercise_type);
+        }
+        let y = Self::exponent(dp, exercise_type);
+        dp.strike * y / (y - 1.0)
+    }
+
+    fn perpetual_price(dp: &DerivativeParameter, exercise_type: &ExerciseType) -> f64 {
+        let (s, x) = (dp.asset_price, dp.strike);
+        if is_european(dp, exercise_type) {
+            return match exercise_type {
+                ExerciseType::Call => s,
+                ExerciseType::Put => x,
+            };
+        }
+        let phi = exercise_type.sign();
+        let boundary = Self::exercise_boundary(dp, exercise_type);
+        if phi * (s - boundary) >= 0.0 {
+            return phi * (s - x);
+        }
+        let y = Self::exponent(dp, exercise_type);
+        phi * (boundary - x) * (s / boundary).powf(y)
+    }
+}
+
+impl OptionPrice for PerpetualAmerican {
+    type Params = DerivativeParameter;
+
+    fn call(dp: &DerivativeParameter) -> f64 {
+        Self::perpetual_price(dp, &ExerciseType::Call)
+    }
+
+    fn put(dp: &DerivativeParameter) -> f64 {
+        Self::perpetual_price(dp, &ExerciseType::Put)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    /// (option parameters, exercise type, average of binomial trees with 4000 and 4001 steps)
+    fn binomial_tree_references() -> Vec<(DerivativeParameter, ExerciseType, f64)> {
+        let futures =
+            DerivativeParameter::new(100.0, 100.0, 0.1, 0.1, 0.15).with_dividend_yield(0.1);
+        let stock =
+            DerivativeParameter::new(100.0, 110.0, 1.0, 0.05, 0.3).with_dividend_yield(0.03);
+        vec![
+            (futures, ExerciseType::Call, 1.876458),
+            (futures, ExerciseType::Put, 1.876458),
+            (
+                DerivativeParameter {
+                    asset_price: 90.0,
+                    ..futures
+                },
+                ExerciseType::Call,
+                0.020488,
+            ),
+            (
+                DerivativeParameter {
+                    asset_price: 110.0,
+                    ..futures
+                },
+                ExerciseType::Put,
+                0.040779,
+            ),
+            (
+                DerivativeParameter::new(42.0, 40.0, 0.75, 0.04, 0.35).with_dividend_yield(0.08),
+                ExerciseType::Call,
+                5.309182,
+            ),
+            (
+                DerivativeParameter::new(42.0, 40.0, 0.75, 0.04, 0.35).with_dividend_yield(0.08),
+                ExerciseType::Put,
+                4.361555,
+            ),
+            (
+                DerivativeParameter::new(100.0, 100.0, 0.5, 0.06, 0.25),
+                ExerciseType::Put,
+                5.848339,
+            ),
+            (stock, ExerciseType::Call, 8.604159),
+            (stock, ExerciseType::Put, 16.694409),
+        ]
+    }
+
+    #[test]
+    fn barone_adesi_whaley() {
+        for (dp, exercise_type, price) in binomial_tree_references() {
+            assert_approx_eq!(BaroneAdesiWhaley::price(&dp, &exercise_type), price, 0.02);
+        }
+    }
+
+    #[test]
+    fn bjerksund_stensland() {
+        // Haug, The Complete Guide to Option Pricing Formulas, example 3.1.4 of the flat
+        // boundary of 1993 gives 5.2704, the two boundaries of 2002 improve on it
+        let dp = DerivativeParameter::new(42.0, 40.0, 0.75, 0.04, 0.35).with_dividend_yield(0.08);
+        assert!(BjerksundStensland::call(&dp) > 5.2704);
+
+        // the value of an exercise strategy is a lower bound, which is less accurate for puts
+        for (dp, exercise_type, price) in binomial_tree_references() {
+            let approximation = BjerksundStensland::price(&dp, &exercise_type);
+            assert!(approximation < price + 1e-4);
+            assert!((price - approximation) / price < 0.011);
+        }
+    }
+
+    #[test]
+    fn no_early_exercise() {
+        let dp = DerivativeParameter::new(100.0, 95.0, 0.5, 0.05, 0.25);
+        let european = BlackScholesMerton::call(&dp);
+        assert_approx_eq!(BaroneAdesiWhaley::call(&dp), european, 1e-12);
+        assert_approx_eq!(BjerksundStensland::call(&dp), european, 1e-12);
+        assert_eq!(
+            BaroneAdesiWhaley::exercise_boundary(&dp, &ExerciseType::Call),
+            f64::INFINITY
+        );
+        assert_eq!(PerpetualAmerican::call(&dp), dp.asset_price);
+
+        let dp = DerivativeParameter { rfr: 0.0, ..dp };
+        let european = BlackScholesMerton::put(&dp);
+        assert_approx_eq!(BaroneAdesiWhaley::put(&dp), european, 1e-12);
+        assert_approx_eq!(BjerksundStensland::put(&dp), european, 1e-12);
+        assert_eq!(
+            BjerksundStensland::exercise_boundary(&dp, &ExerciseType::Put),
+            0.0
+        );
+    }
+
+    #[test]
+    fn exercise_boundaries() {
+        let dp = DerivativeParameter::new(0.0, 100.0, 0.8, 0.06, 0.3).with_dividend_yield(0.04);
+        let h = 1e-4;
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            let phi = exercise_type.sign();
+            let at = |asset_price: f64| DerivativeParameter { asset_price, ..dp };
+
+            // value matching and smooth pasting of the quadratic approximation
+            let boundary = BaroneAdesiWhaley::exercise_boundary(&dp, &exercise_type);
+            let continuation = BaroneAdesiWhaley::price(&at(boundary - phi * h), &exercise_type);
+            assert_approx_eq!(continuation, phi * (boundary - phi * h - dp.strike), 1e-6);
+
+            // the strategy of Bjerksund and Stensland exercises immediately from the boundary on
+            let boundary = BjerksundStensland::exercise_boundary(&dp, &exercise_type);
+            let exercised = BjerksundStensland::price(&at(boundary), &exercise_type);
+            assert_approx_eq!(exercised, phi * (boundary - dp.strike), 1e-8);
+            let continuation = BjerksundStensland::price(&at(boundary - phi), &exercise_type);
+            assert!(continuation > phi * (boundary - phi - dp.strike));
+        }
+    }
+
+    #[test]
+    fn perpetual() {
+        // the approximations become exact for infinite expiration
+        let dp =
+            DerivativeParameter::new(100.0, 100.0, 1000.0, 0.05, 0.25).with_dividend_yield(0.03);
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            assert_approx_eq!(
+                BaroneAdesiWhaley::price(&dp, &exercise_type),
+                PerpetualAmerican::price(&dp, &exercise_type),
+                1e-8
+            );
+            assert_approx_eq!(
+                BaroneAdesiWhaley::exercise_boundary(&dp, &exercise_type),
+                PerpetualAmerican::exercise_boundary(&dp, &exercise_type),
+                1e-8
+            );
+        }
+        assert_approx_eq!(
+            BjerksundStensland::call(&dp),
+            PerpetualAmerican::call(&dp),
+            1e-8
+        );
+
+        // smooth pasting of the put at its boundary
+        let boundary = PerpetualAmerican::exercise_boundary(&dp, &ExerciseType::Put);
+        let h = 1e-4;
+        let above = DerivativeParameter {
+            asset_price: boundary + h,
+            ..dp
+        };
+        assert_approx_eq!(
+            PerpetualAmerican::put(&above),
+            dp.strike - boundary - h,
+            1e-7
+        );
+    }
+
+    #[test]
+    fn expiry_and_zero_volatility() {
+        for (strike, call, put) in [(95.0, 5.0, 0.0), (100.0, 0.0, 0.0), (105.0, 0.0, 5.0)] {
+            let dp = DerivativeParameter::new(100.0, strike, 0.0, 0.05, 0.25);
+            for (price, intrinsic) in [
+                (BaroneAdesiWhaley::call(&dp), call),
+                (BaroneAdesiWhaley::put(&dp), put),
+                (BjerksundStensland::call(&dp), call),
+                (BjerksundStensland::put(&dp), put),
+            ] {
+                assert_eq!(price, intrinsic);
+            }
+            // at expiry both are exercised in the money, the call on a dividend paying asset
+            let dp = dp.with_dividend_yield(0.1);
+            for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+                assert_eq!(
+                    BaroneAdesiWhaley::exercise_boundary(&dp, &exercise_type),
+                    strike
+                );
+                assert_eq!(
+                    BjerksundStensland::exercise_boundary(&dp, &exercise_type),
+                    strike
+                );
+            }
+        }
+
+        // without volatility the put is exercised once the asset, falling at the cost of carry
+        // b = -0.1, reaches r X / (r - b) = X / 3 at the time 10 ln(1.2)
+        let dp = DerivativeParameter::new(40.0, 100.0, 5.0, 0.05, 0.0).with_dividend_yield(0.15);
+        let exercised = 1.2_f64.powf(-0.5) * 200.0 / 3.0;
+        assert_approx_eq!(BaroneAdesiWhaley::put(&dp), exercised, 1e-10);
+        assert_approx_eq!(BjerksundStensland::put(&dp), exercised, 1e-10);
+        // the call is exercised at once
+        let dp = DerivativeParameter::new(100.0, 90.0, 1.0, 0.05, 0.0).with_dividend_yield(0.1);
+        assert_approx_eq!(BaroneAdesiWhaley::call(&dp), 10.0, 1e-10);
+        assert_approx_eq!(BjerksundStensland::call(&dp), 10.0, 1e-10);
+    }
+}
@@ -0,0 +1,593 @@
+use std::f64::consts::PI;
+
+use crate::analytic::black_scholes::OptionPrice;
+use crate::common::models::{DerivativeParameter, ExerciseType};
+use crate::common::numeric::Numeric;
+use crate::error::PricingError;
+
+/// Number of trapezoidal intervals approximating continuous averaging
+/// in the moment matching approximations.
+const CONTINUOUS_FIXINGS: usize = 500;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AsianType {
+    /// the average replaces the asset price at expiration, e.g. call payoff $(A - K)^+$
+    AveragePrice,
+    /// the average replaces the strike, e.g. call payoff $(S_T - A)^+$
+    AverageStrike,
+}
+
+#[derive(Clone, Debug)]
+pub struct AsianParameter<T: Numeric = f64> {
+    /// the strike is ignored for average strike options
+    pub option_params: DerivativeParameter<T>,
+    pub asian_type: AsianType,
+    /// the sorted fixing times (in years from now) of the equally weighted average,
+    /// or continuous averaging over the whole lifetime of the option if empty
+    pub fixing_times: Vec<T>,
+}
+
+impl<T: Numeric> AsianParameter<T> {
+    pub fn new(
+        option_params: DerivativeParameter<T>,
+        asian_type: AsianType,
+        mut fixing_times: Vec<T>,
+    ) -> Self {
+        fixing_times.sort_by(|a, b| a.to_f64().total_cmp(&b.to_f64()));
+        Self {
+            option_params,
+            asian_type,
+            fixing_times,
+        }
+    }
+
+    /// As [AsianParameter::new], but rejects fixing times outside the lifetime of the option.
+    pub fn try_new(
+        option_params: DerivativeParameter<T>,
+        asian_type: AsianType,
+        fixing_times: Vec<T>,
+    ) -> Result<Self, PricingError> {
+        let ap = Self::new(option_params, asian_type, fixing_times);
+        ap.validate()?;
+        Ok(ap)
+    }
+
+    pub fn continuous(option_params: DerivativeParameter<T>, asian_type: AsianType) -> Self {
+        Self::new(option_params, asian_type, Vec::new())
+    }
+
+    pub fn validate(&self) -> Result<(), PricingError> {
+        self.option_params.validate()?;
+        let expiration = self.option_params.time_to_expiration.to_f64();
+        self.fixing_times.iter().try_for_each(|time| {
+            let time = time.to_f64();
+            PricingError::check_parameter("fixing_time", time, (0.0..=expiration).contains(&time))
+        })
+    }
+
+    pub fn is_continuous(&self) -> bool {
+        self.fixing_times.is_empty()
+    }
+
+    /// The fixing times together with their weights in the average.
+    pub(crate) fn weighted_fixings(&self) -> Vec<(T, T)> {
+        if self.is_continuous() {
+            let dt = self.option_params.time_to_expiration / T::from_f64(CONTINUOUS_FIXINGS as f64);
+            (0..=CONTINUOUS_FIXINGS)
+                .map(|idx| {
+                    let weight = if idx == 0 || idx == CONTINUOUS_FIXINGS {
+                        0.5
+                    } else {
+                        1.0
+                    };
+                    (
+                        T::from_f64(idx as f64) * dt,
+                        T::from_f64(weight / CONTINUOUS_FIXINGS as f64),
+                    )
+                })
+                .collect()
+        } else {
+            let weight = T::from_f64(1.0 / self.fixing_times.len() as f64);
+            self.fixing_times.iter().map(|t| (*t, weight)).collect()
+        }
+    }
+
+    /// Returns $\sum_i w_i t_i$ and $\sum_{i, j} w_i w_j \min(t_i, t_j)$, i.e. the mean time and the
+    /// variance (in units of time) of the weighted Brownian motion over the fixings.
+    fn geometric_sums(&self) -> (T, T) {
+        if self.is_continuous() {
+            let t = self.option_params.time_to_expiration;
+            return (t / T::from_f64(2.0), t / T::from_f64(3.0));
+        }
+        let fixings = self.weighted_fixings();
+        let mean_time = fixings
+            .iter()
+            .fold(T::zero(), |mean_time, (t, w)| mean_time + *w * *t);
+        let mut variance_time = T::zero();
+        let mut weight_after = T::zero();
+        for (t, w) in fixings.iter().rev() {
+            variance_time = variance_time + *w * *t * (*w + T::from_f64(2.0) * weight_after);
+            weight_after = weight_after + *w;
+        }
+        (mean_time, variance_time)
+    }
+
+    /// Raw moments (up to the given order) of the arithmetic average $A$ under the risk-neutral
+    /// measure for average price options, or of $A / S_T$ under the share measure for average
+    /// strike options.
+    fn arithmetic_moments(&self, order: usize) -> Vec<T> {
+        let dp = &self.option_params;
+        let (b, sigma) = (dp.cost_of_carry(), dp.vola);
+        let half_variance = sigma.powi(2) / T::from_f64(2.0);
+        let fixings = self.weighted_fixings();
+
+        match self.asian_type {
+            AsianType::AveragePrice => {
+                // A / S_0 = X_1 (w_1 + X_2 (w_2 + ...)) with X_i = S_{t_i} / S_{t_{i-1}}
+                let mut previous_time = T::zero();
+                let mut factors = Vec::with_capacity(fixings.len());
+                for (t, w) in fixings {
+                    let dt = t - previous_time;
+                    factors.push((w, (b - half_variance) * dt, sigma.powi(2) * dt));
+                    previous_time = t;
+                }
+                nested_sum_moments(&factors, order)
+                    .iter()
+                    .enumerate()
+                    .map(|(k, m)| *m * dp.asset_price.powi(k as i32))
+                    .collect()
+            }
+            AsianType::AverageStrike => {
+                // A / S_T = Z_1 (w_n + Z_2 (w_{n-1} + ...)) with Z_i = S_{t_{n-i}} / S_{t_{n-i+1}}
+                // where log S_t has drift b + sigma^2 / 2 under the share measure
+                let mut next_time = dp.time_to_expiration;
+                let mut factors = Vec::with_capacity(fixings.len());
+                for (t, w) in fixings.into_iter().rev() {
+                    let dt = next_time - t;
+                    factors.push((w, -(b + half_variance) * dt, sigma.powi(2) * dt));
+                    next_time = t;
+                }
+                nested_sum_moments(&factors, order)
+            }
+        }
+    }
+}
+
+/// Raw moments $E[U^k]$ for $k = 0, ..., order$ of $U = X_1 (w_1 + X_2 (w_2 + ... + X_n w_n))$
+/// for independent lognormal factors $X_i$, each given by (w_i, mean and variance of log X_i).
+fn nested_sum_moments<T: Numeric>(factors: &[(T, T, T)], order: usize) -> Vec<T> {
+    let lognormal_moment = |k: usize, log_mean: T, log_variance: T| {
+        let k = T::from_f64(k as f64);
+        (k * log_mean + k.powi(2) * log_variance / T::from_f64(2.0)).exp()
+    };
+
+    // the innermost sum is zero
+    let mut moments: Vec<T> = (0..=order)
+        .map(|k| if k == 0 { T::one() } else { T::zero() })
+        .collect();
+    for (weight, log_mean, log_variance) in factors.iter().rev() {
+        moments = (0..=order)
+            .map(|k| {
+                let binomial_sum = (0..=k).fold(T::zero(), |sum, m| {
+                    sum + T::from_f64(binomial(k, m)) * weight.powi((k - m) as i32) * moments[m]
+                });
+                lognormal_moment(k, *log_mean, *log_variance) * binomial_sum
+            })
+            .collect();
+    }
+    moments
+}
+
+fn binomial(n: usize, k: usize) -> f64 {
+    (0..k).fold(1.0, |acc, i| acc * (n - i) as f64 / (i + 1) as f64)
+}
+
+/// Undiscounted $E[(\phi(X - K))^+]$ for a lognormal random variable $X$
+/// with the mean $m_1$ and the second moment $m_2$.
+fn lognormal_payoff<T: Numeric>(m1: T, m2: T, strike: T, exercise_type: &ExerciseType) -> T {
+    let log_variance = (m2 / m1.powi(2)).ln().max(T::zero());
+    if log_variance == T::zero() {
+        // without variance, e.g. at expiry or without volatility, X is its mean
+        return match exercise_type {
+            ExerciseType::Call => (m1 - strike).max(T::zero()),
+            ExerciseType::Put => (strike - m1).max(T::zero()),
+        };
+    }
+    let s = log_variance.sqrt();
+    let d1 = ((m1 / strike).ln() + s.powi(2) / T::from_f64(2.0)) / s;
+    let d2 = d1 - s;
+    match exercise_type {
+        ExerciseType::Call => m1 * d1.norm_cdf() - strike * d2.norm_cdf(),
+        ExerciseType::Put => strike * (-d2).norm_cdf() - m1 * (-d1).norm_cdf(),
+    }
+}
+
+/// The Edgeworth correction of the lognormal approximation of $E[(\phi(X - K))^+]$
+/// by the excess third and fourth cumulants, see Jarrow and Rudd,
+/// Approximate Option Valuation for Arbitrary Stochastic Processes (1982).
+/// Equal for puts and calls, since the first moment is matched.
+fn edgeworth_correction<T: Numeric>(moments: &[T], strike: T) -> T {
+    let c = T::from_f64;
+    // normalize by the mean for numerical stability
+    let m1 = moments[1];
+    let scaled: Vec<T> = moments
+        .iter()
+        .enumerate()
+        .map(|(k, m)| *m / m1.powi(k as i32))
+        .collect();
+    let k = strike / m1;
+
+    let s2 = scaled[2].ln();
+    if s2 <= T::zero() {
+        // a degenerate average has no higher cumulants
+        return T::zero();
+    }
+    let lognormal_moment = |n: i32| (c(n as f64 * (n as f64 - 1.0)) * s2 / c(2.0)).exp();
+    let cumulants = |m: [T; 4]| {
+        let kappa3 = m[2] - c(3.0) * m[1] * m[0] + c(2.0) * m[0].powi(3);
+        let kappa4 = m[3] - c(4.0) * m[2] * m[0] - c(3.0) * m[1].powi(2)
+            + c(12.0) * m[1] * m[0].powi(2)
+            - c(6.0) * m[0].powi(4);
+        (kappa3, kappa4)
+    };
+    let (kappa3, kappa4) = cumulants([scaled[1], scaled[2], scaled[3], scaled[4]]);
+    let (kappa3_ln, kappa4_ln) = cumulants([
+        T::one(),
+        lognormal_moment(2),
+        lognormal_moment(3),
+        lognormal_moment(4),
+    ]);
+
+    // lognormal density and its first two derivatives at the strike
+    let m = -s2 / c(2.0);
+    let u = (k.ln() - m) / s2;
+    let density = (-(k.ln() - m).powi(2) / (c(2.0) * s2)).exp() / (k * (c(2.0 * PI) * s2).sqrt());
+    let d_density = -density * (T::one() + u) / k;
+    let d2_density =
+        density / k.powi(2) * ((T::one() + u).powi(2) + (T::one() + u) - T::one() / s2);
+
+    // rescale the derivatives from the normalized variable back to X
+    m1 * (-(kappa3 - kappa3_ln) / c(6.0) * d_density + (kappa4 - kappa4_ln) / c(24.0) * d2_density)
+}
+
+/// Prices the Asian option from the distribution of the average, see [AsianParameter::arithmetic_moments].
+/// The payoff expectation is given for puts and calls on the average (price options),
+/// or on $A / S_T$ with unit strike under the share measure (strike options).
+fn asian_price<T: Numeric>(
+    ap: &AsianParameter<T>,
+    exercise_type: &ExerciseType,
+    expected_payoff: impl Fn(T, &ExerciseType) -> T,
+) -> T {
+    let dp = &ap.option_params;
+    match ap.asian_type {
+        AsianType::AveragePrice => {
+            (-dp.rfr * dp.time_to_expiration).exp() * expected_payoff(dp.strike, exercise_type)
+        }
+        AsianType::AverageStrike => {
+            // (S_T - A)^+ = S_T (1 - A / S_T)^+
+            let ratio_exercise_type = match exercise_type {
+                ExerciseType::Call => ExerciseType::Put,
+                ExerciseType::Put => ExerciseType::Call,
+            };
+            let carry_factor = ((dp.cost_of_carry() - dp.rfr) * dp.time_to_expiration).exp();
+            dp.asset_price * carry_factor * expected_payoff(T::one(), &ratio_exercise_type)
+        }
+    }
+}
+
+/// Geometric average Asian options, for which the average is lognormal.
+/// See Kemna and Vorst, A Pricing Method for Options Based on Average Asset Values (1990).
+pub struct KemnaVorst;
+
+impl KemnaVorst {
+    fn geometric_price<T: Numeric>(ap: &AsianParameter<T>, exercise_type: &ExerciseType) -> T {
+        let dp = &ap.option_params;
+        let (b, sigma, t) = (dp.cost_of_carry(), dp.vola, dp.time_to_expiration);
+        let (mean_time, variance_time) = ap.geometric_sums();
+        let two = T::from_f64(2.0);
+
+        // mean and variance of the log of G (price options) or of G / S_T (strike options)
+        let (log_mean, log_variance) = match ap.asian_type {
+            AsianType::AveragePrice => (
+                dp.asset_price.ln() + (b - sigma.powi(2) / two) * mean_time,
+                sigma.powi(2) * variance_time,
+            ),
+            AsianType::AverageStrike => (
+                (b + sigma.powi(2) / two) * (mean_time - t),
+                sigma.powi(2) * (variance_time - two * mean_time + t),
+            ),
+        };
+        let m1 = (log_mean + log_variance / two).exp();
+        let m2 = (two * log_mean + two * log_variance).exp();
+        asian_price(ap, exercise_type, |strike, exercise_type| {
+            lognormal_payoff(m1, m2, strike, exercise_type)
+        })
+    }
+}
+
+impl<T: Numeric> OptionPrice<T> for KemnaVorst {
+    type Params = AsianParameter<T>;
+
+    fn call(ap: &AsianParameter<T>) -> T {
+        Self::geometric_price(ap, &ExerciseType::Call)
+    }
+
+    fn put(ap: &AsianParameter<T>) -> T {
+        Self::geometric_price(ap, &ExerciseType::Put)
+    }
+}
+
+/// Arithmetic average Asian options, approximating the distribution of the average by the
+/// lognormal distribution with the same first two moments.
+/// See Levy, Pricing European Average Rate Currency Options (1992).
+pub struct Levy;
+
+impl Levy {
+    fn arithmetic_price<T: Numeric>(ap: &AsianParameter<T>, exercise_type: &ExerciseType) -> T {
+        let moments = ap.arithmetic_moments(2);
+        asian_price(ap, exercise_type, |strike, exercise_type| {
+            lognormal_payoff(moments[1], moments[2], strike, exercise_type)
+        })
+    }
+}
+
+impl<T: Numeric> OptionPrice<T> for Levy {
+    type Params = AsianParameter<T>;
+
+    fn call(ap: &AsianParameter<T>) -> T {
+        Self::arithmetic_price(ap, &ExerciseType::Call)
+    }
+
+    fn put(ap: &AsianParameter<T>) -> T {
+        Self::arithmetic_price(ap, &ExerciseType::Put)
+    }
+}
+
+/// Arithmetic average Asian options, approximating the distribution of the average by the
+/// lognormal distribution with the same first two moments, corrected by an Edgeworth expansion
+/// in the third and fourth cumulants.
+/// See Turnbull and Wakeman, A Quick Algorithm for Pricing European Average Options (1991).
+pub struct TurnbullWakeman;
+
+impl TurnbullWakeman {
+    fn arithmetic_price<T: Numeric>(ap: &AsianParameter<T>, exercise_type: &ExerciseType) -> T {
+        let moments = ap.arithmetic_moments(4);
+        asian_price(ap, exercise_type, |strike, exercise_type| {
+            lognormal_payoff(moments[1], moments[2], strike, exercise_type)
+                + edgeworth_correction(&moments, strike)
+        })
+    }
+}
+
+impl<T: Numeric> OptionPrice<T> for TurnbullWakeman {
+    type Params = AsianParameter<T>;
+
+    fn call(ap: &AsianParameter<T>) -> T {
+        Self::arithmetic_price(ap, &ExerciseType::Call)
+    }
+
+    fn put(ap: &AsianParameter<T>) -> T {
+        Self::arithmetic_price(ap, &ExerciseType::Put)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::black_scholes::tests::assert_dual_derivatives_match_finite_differences;
+    use crate::analytic::black_scholes::BlackScholesMerton;
+    use crate::common::numeric::Dual;
+    use crate::simulation::monte_carlo::MonteCarloEstimate;
+    use crate::simulation::sde::gbm::GeometricBrownianMotion;
+    use assert_approx_eq::assert_approx_eq;
+    use rand::{Rng, SeedableRng};
+    use rand_distr::StandardNormal;
+
+    const TOLERANCE: f64 = 1e-4;
+
+    #[test]
+    fn fixing_times() {
+        let dp = DerivativeParameter::new(100.0, 100.0, 1.0, 0.05, 0.3);
+        let ap = AsianParameter::try_new(dp, AsianType::AveragePrice, vec![1.0, 0.0, 0.5]);
+        assert_eq!(ap.unwrap().fixing_times, vec![0.0, 0.5, 1.0]);
+        for time in [-0.1, 1.5, f64::NAN] {
+            let ap = AsianParameter::try_new(dp, AsianType::AveragePrice, vec![0.5, time]);
+            assert!(matches!(
+                ap,
+                Err(PricingError::InvalidParameter {
+                    name: "fixing_time",
+                    ..
+                })
+            ));
+        }
+    }
+
+    #[test]
+    fn geometric_average_price() {
+        // Haug, The Complete Guide to Option Pricing Formulas, example 4.20.1
+        let dp = DerivativeParameter::new(80.0, 85.0, 0.25, 0.05, 0.2).with_dividend_yield(-0.03);
+        let ap = AsianParameter::continuous(dp, AsianType::AveragePrice);
+        assert_approx_eq!(KemnaVorst::put(&ap), 4.6922, TOLERANCE);
+    }
+
+    #[test]
+    fn single_fixing_equals_vanilla() {
+        let dp = DerivativeParameter::new(100.0, 105.0, 1.0, 0.04, 0.3).with_dividend_yield(0.01);
+        // averaging the price at expiration only
+        let price_ap = AsianParameter::new(dp, AsianType::AveragePrice, vec![1.0]);
+        // averaging today's price only, i.e. an at-the-money option
+        let strike_ap = AsianParameter::new(dp, AsianType::AverageStrike, vec![0.0]);
+        let atm_dp = DerivativeParameter {
+            strike: dp.asset_price,
+            ..dp
+        };
+
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            let vanilla = BlackScholesMerton::price(&dp, &exercise_type);
+            assert_approx_eq!(KemnaVorst::price(&price_ap, &exercise_type), vanilla, 1e-10);
+            assert_approx_eq!(Levy::price(&price_ap, &exercise_type), vanilla, 1e-10);
+            assert_approx_eq!(
+                TurnbullWakeman::price(&price_ap, &exercise_type),
+                vanilla,
+                1e-10
+            );
+
+            let atm_vanilla = BlackScholesMerton::price(&atm_dp, &exercise_type);
+            assert_approx_eq!(
+                KemnaVorst::price(&strike_ap, &exercise_type),
+                atm_vanilla,
+                1e-10
+            );
+            assert_approx_eq!(Levy::price(&strike_ap, &exercise_type), atm_vanilla, 1e-10);
+            assert_approx_eq!(
+                TurnbullWakeman::price(&strike_ap, &exercise_type),
+                atm_vanilla,
+                1e-10
+            );
+        }
+    }
+
+    #[test]
+    fn continuous_limit_of_discrete_fixings() {
+        let dp = DerivativeParameter::new(100.0, 95.0, 2.0, 0.03, 0.25);
+        let fixing_times: Vec<f64> = (0..=2000).map(|idx| idx as f64 / 1000.0).collect();
+        for asian_type in [AsianType::AveragePrice, AsianType::AverageStrike] {
+            let discrete = AsianParameter::new(dp, asian_type, fixing_times.clone());
+            let continuous = AsianParameter::continuous(dp, asian_type);
+            assert_approx_eq!(
+                KemnaVorst::call(&discrete),
+                KemnaVorst::call(&continuous),
+                1e-2
+            );
+            assert_approx_eq!(Levy::call(&discrete), Levy::call(&continuous), 1e-2);
+        }
+    }
+
+    #[test]
+    fn arithmetic_average_reference() {
+        // seeded simulation of exact lognormal steps between monthly fixings
+        let (s0, strike, tte, rfr, vola) = (100.0, 100.0, 1.0, 0.05, 0.3);
+        let nr_fixings = 12;
+        let dp = DerivativeParameter::new(s0, strike, tte, rfr, vola);
+        let fixing_times: Vec<f64> = (1..=nr_fixings)
+            .map(|idx| idx as f64 * tte / nr_fixings as f64)
+            .collect();
+
+        let stock_gbm = GeometricBrownianMotion::new(s0, rfr, vola, tte / nr_fixings as f64);
+        let mut rng = rand_hc::Hc128Rng::seed_from_u64(42);
+        let disc_factor = (-rfr * tte).exp();
+        let (mut average_price, mut average_strike) = (Vec::new(), Vec::new());
+        for _ in 0..100_000 {
+            let mut spot = s0;
+            let mut sum = 0.0;
+            for _ in 0..nr_fixings {
+                spot = stock_gbm.step_analytic(spot, rng.sample(StandardNormal));
+                sum += spot;
+            }
+            let average = sum / nr_fixings as f64;
+            average_price.push(disc_factor * (average - strike).max(0.0));
+            average_strike.push(disc_factor * (spot - average).max(0.0));
+        }
+
+        // with seed 42: 8.4754 (average price) and 7.5599 (average strike), standard errors 0.04
+        let mc = MonteCarloEstimate::from_samples(&average_price).unwrap();
+        assert!(mc.standard_error < 0.05);
+        let ap = AsianParameter::new(dp, AsianType::AveragePrice, fixing_times.clone());
+        assert_approx_eq!(
+            TurnbullWakeman::call(&ap),
+            mc.price,
+            3.0 * mc.standard_error
+        );
+        assert_approx_eq!(Levy::call(&ap), mc.price, 3.0 * mc.standard_error);
+        assert!(KemnaVorst::call(&ap) < TurnbullWakeman::call(&ap));
+
+        let mc = MonteCarloEstimate::from_samples(&average_strike).unwrap();
+        assert!(mc.standard_error < 0.05);
+        let ap = AsianParameter::new(dp, AsianType::AverageStrike, fixing_times);
+        assert_approx_eq!(
+            TurnbullWakeman::call(&ap),
+            mc.price,
+            3.0 * mc.standard_error
+        );
+        assert_approx_eq!(Levy::call(&ap), mc.price, 3.0 * mc.standard_error);
+    }
+
+    #[test]
+    fn expiry_and_zero_volatility() {
+        let dp = DerivativeParameter::new(100.0, 95.0, 0.0, 0.05, 0.3);
+        let ap = AsianParameter::continuous(dp, AsianType::AveragePrice);
+        for (call, put) in [
+            (Levy::call(&ap), Levy::put(&ap)),
+            (TurnbullWakeman::call(&ap), TurnbullWakeman::put(&ap)),
+            (KemnaVorst::call(&ap), KemnaVorst::put(&ap)),
+        ] {
+            assert_approx_eq!(call, 5.0, 1e-12);
+            assert_approx_eq!(put, 0.0, 1e-12);
+        }
+
+        // the average of the asset growing deterministically at the cost of carry
+        let dp = DerivativeParameter::new(100.0, 95.0, 1.0, 0.05, 0.0).with_dividend_yield(0.02);
+        let fixing_times: Vec<f64> = (1..=4).map(|idx| idx as f64 / 4.0).collect();
+        let average = fixing_times
+            .iter()
+            .map(|t| 100.0 * (0.03 * t).exp())
+            .sum::<f64>()
+            / 4.0;
+        let ap = AsianParameter::new(dp, AsianType::AveragePrice, fixing_times.clone());
+        let discount_factor = (-0.05_f64).exp();
+        assert_approx_eq!(Levy::call(&ap), (average - 95.0) * discount_factor, 1e-10);
+        assert_approx_eq!(TurnbullWakeman::call(&ap), Levy::call(&ap), 1e-10);
+        assert_approx_eq!(Levy::put(&ap), 0.0, 1e-10);
+
+        let ap = AsianParameter::new(dp, AsianType::AverageStrike, fixing_times);
+        let forward = 100.0 * 0.03_f64.exp();
+        assert_approx_eq!(
+            Levy::call(&ap),
+            (forward - average) * discount_factor,
+            1e-10
+        );
+        assert_approx_eq!(TurnbullWakeman::call(&ap), Levy::call(&ap), 1e-10);
+    }
+
+    #[test]
+    fn automatic_differentiation() {
+        let dp = DerivativeParameter::new(100.0, 95.0, 1.0, 0.05, 0.3).with_dividend_yield(0.02);
+        let fixing_times = [0.0, 0.25, 0.5, 0.75, 1.0];
+        for asian_type in [AsianType::AveragePrice, AsianType::AverageStrike] {
+            let continuous = |dp| AsianParameter::continuous(dp, asian_type);
+            let dual_continuous = |dp| AsianParameter::continuous(dp, asian_type);
+            let discrete = |dp| AsianParameter::new(dp, asian_type, fixing_times.to_vec());
+            let dual_discrete = |dp| {
+                let fixing_times = fixing_times.iter().map(|t| Dual::constant(*t)).collect();
+                AsianParameter::new(dp, asian_type, fixing_times)
+            };
+            for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+                let et = &exercise_type;
+                assert_dual_derivatives_match_finite_differences::<KemnaVorst>(
+                    &dp,
+                    et,
+                    continuous,
+                    dual_continuous,
+                );
+                assert_dual_derivatives_match_finite_differences::<Levy>(
+                    &dp,
+                    et,
+                    continuous,
+                    dual_continuous,
+                );
+                assert_dual_derivatives_match_finite_differences::<KemnaVorst>(
+                    &dp,
+                    et,
+                    discrete,
+                    dual_discrete,
+                );
+                assert_dual_derivatives_match_finite_differences::<TurnbullWakeman>(
+                    &dp,
+                    et,
+                    discrete,
+                    dual_discrete,
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,272 @@
+use std::f64::consts::PI;
+
+use crate::analytic::black_scholes::{OptionGreeks, OptionPrice};
+use crate::analytic::implied_volatility::ImpliedVolatility;
+use crate::common::models::{DerivativeParameter, ExerciseType};
+use crate::common::normal::{cdf, pdf};
+use crate::common::numeric::Numeric;
+
+/// European Put and Call option prices for forwards following an arithmetic Brownian motion
+/// $dF_t = \sigma dW_t$. The asset price is the forward price and the volatility is the
+/// absolute (normal) volatility, hence zero and negative forwards and strikes are supported.
+/// https://en.wikipedia.org/wiki/Bachelier_model
+pub struct Bachelier;
+
+struct BachelierTerms<T: Numeric = f64> {
+    /// F - K
+    moneyness: T,
+    /// sigma * sqrt(T)
+    sigma_exp: T,
+    d: T,
+    discount_factor: T,
+}
+
+impl Bachelier {
+    fn terms<T: Numeric>(dp: &DerivativeParameter<T>) -> BachelierTerms<T> {
+        let moneyness = dp.asset_price - dp.strike;
+        let sigma_exp = dp.vola * dp.time_to_expiration.sqrt();
+        let d = if sigma_exp.to_f64().is_nan() {
+            T::from_f64(f64::NAN)
+        } else if sigma_exp > T::zero() {
+            moneyness / sigma_exp
+        } else {
+            // the intrinsic value at expiry or without volatility
+            T::from_f64(if moneyness > T::zero() {
+                f64::INFINITY
+            } else {
+                f64::NEG_INFINITY
+            })
+        };
+        BachelierTerms {
+            moneyness,
+            sigma_exp,
+            d,
+            discount_factor: (-dp.rfr * dp.time_to_expiration).exp(),
+        }
+    }
+}
+
+impl BachelierTerms {
+    /// At expiry or without volatility d is infinite and the terms with the density vanish;
+    /// at the money the density is a Dirac delta, which is taken as zero as well.
+    fn is_deterministic(&self) -> bool {
+        self.d.is_infinite()
+    }
+}
+
+impl<T: Numeric> OptionPrice<T> for Bachelier {
+    type Params = DerivativeParameter<T>;
+
+    fn call(dp: &DerivativeParameter<T>) -> T {
+        let t = Self::terms(dp);
+        t.discount_factor * (t.moneyness * t.d.norm_cdf() + t.sigma_exp * t.d.norm_pdf())
+    }
+
+    fn put(dp: &DerivativeParameter<T>) -> T {
+        let t = Self::terms(dp);
+        t.discount_factor * (-t.moneyness * (-t.d).norm_cdf() + t.sigma_exp * t.d.norm_pdf())
+    }
+}
+
+impl OptionGreeks for Bachelier {
+    fn delta(dp: &DerivativeParameter, exercise_type: &ExerciseType) -> f64 {
+        let t = Self::terms(dp);
+        match exercise_type {
+            ExerciseType::Call => t.discount_factor * cdf(t.d),
+            ExerciseType::Put => -t.discount_factor * cdf(-t.d),
+        }
+    }
+
+    fn gamma(dp: &DerivativeParameter, _exercise_type: &ExerciseType) -> f64 {
+        let t = Self::terms(dp);
+        if t.is_deterministic() {
+            return 0.0;
+        }
+        t.discount_factor * pdf(t.d) / t.sigma_exp
+    }
+
+    fn vega(dp: &DerivativeParameter, _exercise_type: &ExerciseType) -> f64 {
+        let t = Self::terms(dp);
+        t.discount_factor * dp.time_to_expiration.sqrt() * pdf(t.d)
+    }
+
+    fn theta(dp: &DerivativeParameter, exercise_type: &ExerciseType) -> f64 {
+        let t = Self::terms(dp);
+        let time_decay = if t.is_deterministic() {
+            0.0
+        } else {
+            t.discount_factor * dp.vola * pdf(t.d) / (2.0 * dp.time_to_expiration.sqrt())
+        };
+        dp.rfr * Self::price(dp, exercise_type) - time_decay
+    }
+
+    /// The forward price does not depend on the interest rate, only the discounting does.
+    fn rho(dp: &DerivativeParameter, exercise_type: &ExerciseType) -> f64 {
+        -dp.time_to_expiration * Self::price(dp, exercise_type)
+    }
+
+    fn vanna(dp: &DerivativeParameter, _exercise_type: &ExerciseType) -> f64 {
+        let t = Self::terms(dp);
+        if t.is_deterministic() {
+            return 0.0;
+        }
+        -t.discount_factor * pdf(t.d) * t.d / dp.vola
+    }
+
+    fn volga(dp: &DerivativeParameter, exercise_type: &ExerciseType) -> f64 {
+        let t = Self::terms(dp);
+        if t.is_deterministic() {
+            return 0.0;
+        }
+        Self::vega(dp, exercise_type) * t.d.powi(2) / dp.vola
+    }
+
+    fn charm(dp: &DerivativeParameter, exercise_type: &ExerciseType) -> f64 {
+        let t = Self::terms(dp);
+        let density_term = if t.is_deterministic() {
+            0.0
+        } else {
+            t.discount_factor * pdf(t.d) * t.d / (2.0 * dp.time_to_expiration)
+        };
+        match exercise_type {
+            ExerciseType::Call => dp.rfr * t.discount_factor * cdf(t.d) + density_term,
+            ExerciseType::Put => -dp.rfr * t.discount_factor * cdf(-t.d) + density_term,
+        }
+    }
+}
+
+/// The implied normal volatility; prices are unbounded from above in the Bachelier model.
+impl ImpliedVolatility for Bachelier {
+    fn price_bounds(dp: &DerivativeParameter, exercise_type: &ExerciseType) -> (f64, f64) {
+        let t = Self::terms(dp);
+        let intrinsic_value = match exercise_type {
+            ExerciseType::Call => t.moneyness.max(0.0),
+            ExerciseType::Put => (-t.moneyness).max(0.0),
+        };
+        (t.discount_factor * intrinsic_value, f64::INFINITY)
+    }
+
+    /// Inverts the at-the-money price $\sigma \sqrt{T / 2\pi}$ for the time value of the option.
+    fn initial_guess(price: f64, dp: &DerivativeParameter, exercise_type: &ExerciseType) -> f64 {
+        let (intrinsic_value, _) = Self::price_bounds(dp, exercise_type);
+        let time_value = (price - intrinsic_value) * (dp.rfr * dp.time_to_expiration).exp();
+        time_value * (2.0 * PI / dp.time_to_expiration).sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::black_scholes::tests::assert_greeks_match_finite_differences;
+    use assert_approx_eq::assert_approx_eq;
+
+    const TOLERANCE: f64 = 1e-10;
+
+    #[test]
+    fn at_the_money() {
+        let dp = DerivativeParameter::new(0.02, 0.02, 2.0, 0.01, 0.008);
+        let atm_price = (-0.02_f64).exp() * 0.008 * (2.0 / (2.0 * PI)).sqrt();
+        assert_approx_eq!(Bachelier::call(&dp), atm_price, TOLERANCE);
+        assert_approx_eq!(Bachelier::put(&dp), atm_price, TOLERANCE);
+    }
+
+    #[test]
+    fn nan_volatility() {
+        let dp = DerivativeParameter::new(0.015, 0.01, 1.0, 0.01, f64::NAN);
+        assert!(Bachelier::call(&dp).is_nan());
+        assert!(Bachelier::put(&dp).is_nan());
+        assert!(Bachelier::gamma(&dp, &ExerciseType::Call).is_nan());
+    }
+
+    #[test]
+    fn expiry_and_zero_volatility() {
+        let discount_factor = (-0.01_f64).exp();
+        for (forward, call, put) in [(0.015, 0.005, 0.0), (0.01, 0.0, 0.0), (-0.005, 0.0, 0.015)] {
+            let expired = DerivativeParameter::new(forward, 0.01, 0.0, 0.01, 0.008);
+            assert_approx_eq!(Bachelier::call(&expired), call, TOLERANCE);
+            assert_approx_eq!(Bachelier::put(&expired), put, TOLERANCE);
+
+            let no_vola = DerivativeParameter::new(forward, 0.01, 1.0, 0.01, 0.0);
+            assert_approx_eq!(Bachelier::call(&no_vola), discount_factor * call, TOLERANCE);
+            assert_approx_eq!(Bachelier::put(&no_vola), discount_factor * put, TOLERANCE);
+        }
+    }
+
+    #[test]
+    fn greeks_at_expiry_and_zero_volatility() {
+        for tte in [0.0, 1.0] {
+            let vola = 0.008 * (1.0 - tte);
+            for strike in [0.005, 0.01, 0.015] {
+                let dp = DerivativeParameter::new(0.01, strike, tte, 0.02, vola);
+                for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+                    assert_eq!(Bachelier::gamma(&dp, &exercise_type), 0.0);
+                    assert_eq!(Bachelier::vega(&dp, &exercise_type), 0.0);
+                    assert_eq!(Bachelier::vanna(&dp, &exercise_type), 0.0);
+                    assert_eq!(Bachelier::volga(&dp, &exercise_type), 0.0);
+                    assert!(Bachelier::theta(&dp, &exercise_type).is_finite());
+                    assert!(Bachelier::charm(&dp, &exercise_type).is_finite());
+                }
+            }
+        }
+
+        // without volatility the value in the money is only discounted
+        let dp = DerivativeParameter::new(0.015, 0.01, 2.0, 0.02, 0.0);
+        let call = ExerciseType::Call;
+        let discount_factor = (-0.04_f64).exp();
+        assert_approx_eq!(
+            Bachelier::theta(&dp, &call),
+            0.02 * discount_factor * 0.005,
+            TOLERANCE
+        );
+        assert_approx_eq!(
+            Bachelier::charm(&dp, &call),
+            0.02 * discount_factor,
+            TOLERANCE
+        );
+        let small_vola = DerivativeParameter { vola: 1e-6, ..dp };
+        assert_approx_eq!(
+            Bachelier::theta(&small_vola, &call),
+            Bachelier::theta(&dp, &call),
+            TOLERANCE
+        );
+    }
+
+    #[test]
+    fn negative_forward_and_strike() {
+        let dp = DerivativeParameter::new(-0.005, -0.002, 1.0, -0.004, 0.006);
+        let call = Bachelier::call(&dp);
+        let put = Bachelier::put(&dp);
+        assert!(call > 0.0);
+        assert!(put > -dp.asset_price + dp.strike);
+
+        let discount_factor = (-dp.rfr * dp.time_to_expiration).exp();
+        assert_approx_eq!(
+            call - put,
+            discount_factor * (dp.asset_price - dp.strike),
+            TOLERANCE
+        );
+    }
+
+    #[test]
+    fn greeks_finite_differences() {
+        let dp = DerivativeParameter::new(100.0, 105.0, 1.5, 0.03, 20.0);
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            assert_greeks_match_finite_differences::<Bachelier>(&dp, &exercise_type);
+        }
+    }
+
+    #[test]
+    fn implied_normal_volatility() {
+        for strike in [-0.01, -0.002, 0.0, 0.003, 0.015] {
+            for vola in [0.004, 0.01, 0.02] {
+                let dp = DerivativeParameter::new(0.001, strike, 3.0, 0.02, vola);
+                for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+                    let price = Bachelier::price(&dp, &exercise_type);
+                    let implied_vola =
+                        Bachelier::implied_volatility(price, &dp, &exercise_type).unwrap();
+                    assert_approx_eq!(implied_vola, vola, 1e-8);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,356 @@
+use crate::analytic::black_scholes::{BlackScholesMerton, OptionPrice};
+use crate::common::models::{DerivativeParameter, ExerciseType};
+use crate::common::numeric::Numeric;
+
+/// Direction of the barrier relative to the asset price and whether hitting it
+/// activates (knock-in) or extinguishes (knock-out) the option.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BarrierType {
+    DownAndIn,
+    DownAndOut,
+    UpAndIn,
+    UpAndOut,
+}
+
+impl BarrierType {
+    fn is_down(&self) -> bool {
+        matches!(self, BarrierType::DownAndIn | BarrierType::DownAndOut)
+    }
+
+    fn is_knock_in(&self) -> bool {
+        matches!(self, BarrierType::DownAndIn | BarrierType::UpAndIn)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BarrierParameter<T: Numeric = f64> {
+    pub option_params: DerivativeParameter<T>,
+    pub barrier_type: BarrierType,
+    /// the level which is monitored continuously until expiration
+    pub barrier: T,
+    /// the cash amount paid at expiration if a knock-in option was never activated,
+    /// or at the hitting time if a knock-out option is extinguished
+    pub rebate: T,
+}
+
+impl<T: Numeric> BarrierParameter<T> {
+    pub fn new(
+        option_params: DerivativeParameter<T>,
+        barrier_type: BarrierType,
+        barrier: T,
+        rebate: T,
+    ) -> Self {
+        Self {
+            option_params,
+            barrier_type,
+            barrier,
+            rebate,
+        }
+    }
+
+    /// Whether the barrier has already been hit by the current asset price.
+    pub fn is_breached(&self) -> bool {
+        if self.barrier_type.is_down() {
+            self.option_params.asset_price <= self.barrier
+        } else {
+            self.option_params.asset_price >= self.barrier
+        }
+    }
+}
+
+/// European single barrier options with continuous monitoring.
+/// See Reiner and Rubinstein, Breaking Down the Barriers (1991), and
+/// Haug, The Complete Guide to Option Pricing Formulas, chapter 4.17.1.
+pub struct ReinerRubinstein;
+
+/// The building blocks A to F of the closed form solutions.
+struct BarrierTerms<T: Numeric> {
+    a: T,
+    b: T,
+    c: T,
+    d: T,
+    e: T,
+    f: T,
+}
+
+impl ReinerRubinstein {
+    fn terms<T: Numeric>(
+        bp: &BarrierParameter<T>,
+        exercise_type: &ExerciseType,
+    ) -> BarrierTerms<T> {
+        let dp = &bp.option_params;
+        let (s, x, h, k) = (dp.asset_price, dp.strike, bp.barrier, bp.rebate);
+        let (t, r, b, sigma) = (dp.time_to_expiration, dp.rfr, dp.cost_of_carry(), dp.vola);
+
+        let phi = T::from_f64(exercise_type.sign());
+        let eta = T::from_f64(if bp.barrier_type.is_down() { 1.0 } else { -1.0 });
+        let (one, two) = (T::one(), T::from_f64(2.0));
+
+        let sigma_exp = sigma * t.sqrt();
+        let mu = (b - sigma.powi(2) / two) / sigma.powi(2);
+        let lambda = (mu.powi(2) + two * r / sigma.powi(2)).sqrt();
+        let carry_factor = ((b - r) * t).exp();
+        let discount_factor = (-r * t).exp();
+
+        let x1 = (s / x).ln() / sigma_exp + (one + mu) * sigma_exp;
+        let x2 = (s / h).ln() / sigma_exp + (one + mu) * sigma_exp;
+        let y1 = (h.powi(2) / (s * x)).ln() / sigma_exp + (one + mu) * sigma_exp;
+        let y2 = (h / s).ln() / sigma_exp + (one + mu) * sigma_exp;
+        let z = (h / s).ln() / sigma_exp + lambda * sigma_exp;
+
+        let h_s = h / s;
+        let vanilla_term = |d: T| {
+            phi * s * carry_factor * (phi * d).norm_cdf()
+                - phi * x * discount_factor * (phi * d - phi * sigma_exp).norm_cdf()
+        };
+        let reflected_term = |d: T| {
+            phi * s * carry_factor * h_s.powf(two * (mu + one)) * (eta * d).norm_cdf()
+                - phi
+                    * x
+                    * discount_factor
+                    * h_s.powf(two * mu)
+                    * (eta * d - eta * sigma_exp).norm_cdf()
+        };
+
+        BarrierTerms {
+            a: vanilla_term(x1),
+            b: vanilla_term(x2),
+            c: reflected_term(y1),
+            d: reflected_term(y2),
+            e: k * discount_factor
+                * ((eta * x2 - eta * sigma_exp).norm_cdf()
+                    - h_s.powf(two * mu) * (eta * y2 - eta * sigma_exp).norm_cdf()),
+            f: k * (h_s.powf(mu + lambda) * (eta * z).norm_cdf()
+                + h_s.powf(mu - lambda) * (eta * z - two * eta * lambda * sigma_exp).norm_cdf()),
+        }
+    }
+
+    /// Without volatility or at expiry the asset moves deterministically to the forward and hits
+    /// the barrier at $\tau = \ln(H / S) / b$ if it lies in between.
+    fn deterministic_price<T: Numeric>(
+        bp: &BarrierParameter<T>,
+        exercise_type: &ExerciseType,
+    ) -> T {
+        let dp = &bp.option_params;
+        let (s, h, r, b) = (dp.asset_price, bp.barrier, dp.rfr, dp.cost_of_carry());
+        let forward = s * (b * dp.time_to_expiration).exp();
+        let is_hit = if bp.barrier_type.is_down() {
+            forward <= h
+        } else {
+            forward >= h
+        };
+        match (bp.barrier_type.is_knock_in(), is_hit) {
+            (true, true) | (false, false) => BlackScholesMerton::price(dp, exercise_type),
+            (true, false) => bp.rebate * (-r * dp.time_to_expiration).exp(),
+            (false, true) => bp.rebate * (-r * (h / s).ln() / b).exp(),
+        }
+    }
+
+    fn barrier_price<T: Numeric>(bp: &BarrierParameter<T>, exercise_type: &ExerciseType) -> T {
+        let dp = &bp.option_params;
+        if bp.is_breached() {
+            return if bp.barrier_type.is_knock_in() {
+                BlackScholesMerton::price(dp, exercise_type)
+            } else {
+                bp.rebate
+            };
+        }
+
+        if dp.vola * dp.time_to_expiration.sqrt() == T::zero() {
+            return Self::deterministic_price(bp, exercise_type);
+        }
+
+        let t = Self::terms(bp, exercise_type);
+        let strike_above_barrier = dp.strike >= bp.barrier;
+        match (bp.barrier_type, exercise_type, strike_above_barrier) {
+            (BarrierType::DownAndIn, ExerciseType::Call, true) => t.c + t.e,
+            (BarrierType::DownAndIn, ExerciseType::Call, false) => t.a - t.b + t.d + t.e,
+            (BarrierType::UpAndIn, ExerciseType::Call, true) => t.a + t.e,
+            (BarrierType::UpAndIn, ExerciseType::Call, false) => t.b - t.c + t.d + t.e,
+            (BarrierType::DownAndIn, ExerciseType::Put, true) => t.b - t.c + t.d + t.e,
+            (BarrierType::DownAndIn, ExerciseType::Put, false) => t.a + t.e,
+            (BarrierType::UpAndIn, ExerciseType::Put, true) => t.a - t.b + t.d + t.e,
+            (BarrierType::UpAndIn, ExerciseType::Put, false) => t.c + t.e,
+            (BarrierType::DownAndOut, ExerciseType::Call, true) => t.a - t.c + t.f,
+            (BarrierType::DownAndOut, ExerciseType::Call, false) => t.b - t.d + t.f,
+            (BarrierType::UpAndOut, ExerciseType::Call, true) => t.f,
+            (BarrierType::UpAndOut, ExerciseType::Call, false) => t.a - t.b + t.c - t.d + t.f,
+            (BarrierType::DownAndOut, ExerciseType::Put, true) => t.a - t.b + t.c - t.d + t.f,
+            (BarrierType::DownAndOut, ExerciseType::Put, false) => t.f,
+            (BarrierType::UpAndOut, ExerciseType::Put, true) => t.b - t.d + t.f,
+            (BarrierType::UpAndOut, ExerciseType::Put, false) => t.a - t.c + t.f,
+        }
+    }
+}
+
+impl<T: Numeric> OptionPrice<T> for ReinerRubinstein {
+    type Params = BarrierParameter<T>;
+
+    fn call(bp: &BarrierParameter<T>) -> T {
+        Self::barrier_price(bp, &ExerciseType::Call)
+    }
+
+    fn put(bp: &BarrierParameter<T>) -> T {
+        Self::barrier_price(bp, &ExerciseType::Put)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::black_scholes::tests::assert_dual_derivatives_match_finite_differences;
+    use crate::common::numeric::Dual;
+    use assert_approx_eq::assert_approx_eq;
+
+    const TOLERANCE: f64 = 1e-4;
+
+    /// Haug, The Complete Guide to Option Pricing Formulas, table 4-13, with
+    /// S = 100, T = 0.5, r = 0.08, b = 0.04, rebate = 3 and sigma = 0.25.
+    fn reference_parameter(
+        barrier_type: BarrierType,
+        strike: f64,
+        barrier: f64,
+    ) -> BarrierParameter {
+        let dp = DerivativeParameter::new(100.0, strike, 0.5, 0.08, 0.25).with_dividend_yield(0.04);
+        BarrierParameter::new(dp, barrier_type, barrier, 3.0)
+    }
+
+    #[test]
+    fn barrier_calls() {
+        let references = [
+            (BarrierType::DownAndOut, 95.0, [9.0246, 6.7924, 4.8759]),
+            (BarrierType::DownAndIn, 95.0, [7.7627, 4.0109, 2.0576]),
+            (BarrierType::UpAndIn, 105.0, [14.1112, 8.4482, 4.5910]),
+            (BarrierType::UpAndOut, 105.0, [2.6789, 2.3580, 2.3453]),
+        ];
+        for (barrier_type, barrier, prices) in references {
+            for (strike, price) in [90.0, 100.0, 110.0].iter().zip(prices) {
+                let bp = reference_parameter(barrier_type, *strike, barrier);
+                assert_approx_eq!(ReinerRubinstein::call(&bp), price, TOLERANCE);
+            }
+        }
+    }
+
+    #[test]
+    fn barrier_puts() {
+        let references = [
+            (BarrierType::DownAndOut, 95.0, [2.2798, 2.2947, 2.6252]),
+            (BarrierType::DownAndIn, 95.0, [2.9586, 6.5677, 11.9752]),
+            (BarrierType::UpAndIn, 105.0, [1.4653, 3.3721, 7.0846]),
+            (BarrierType::UpAndOut, 105.0, [3.7760, 5.4932, 7.5187]),
+        ];
+        for (barrier_type, barrier, prices) in references {
+            for (strike, price) in [90.0, 100.0, 110.0].iter().zip(prices) {
+                let bp = reference_parameter(barrier_type, *strike, barrier);
+                assert_approx_eq!(ReinerRubinstein::put(&bp), price, TOLERANCE);
+            }
+        }
+    }
+
+    #[test]
+    fn in_out_parity() {
+        let dp = DerivativeParameter::new(100.0, 102.0, 1.2, 0.03, 0.3).with_dividend_yield(0.01);
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            let vanilla = BlackScholesMerton::price(&dp, &exercise_type);
+            for (knock_in, knock_out, barrier) in [
+                (BarrierType::DownAndIn, BarrierType::DownAndOut, 85.0),
+                (BarrierType::UpAndIn, BarrierType::UpAndOut, 120.0),
+            ] {
+                let knock_in = BarrierParameter::new(dp, knock_in, barrier, 0.0);
+                let knock_out = BarrierParameter::new(dp, knock_out, barrier, 0.0);
+                assert_approx_eq!(
+                    ReinerRubinstein::price(&knock_in, &exercise_type)
+                        + ReinerRubinstein::price(&knock_out, &exercise_type),
+                    vanilla,
+                    1e-10
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn breached_barrier() {
+        let bp = reference_parameter(BarrierType::DownAndOut, 90.0, 100.0);
+        assert!(bp.is_breached());
+        assert_eq!(ReinerRubinstein::call(&bp), 3.0);
+
+        let bp = reference_parameter(BarrierType::UpAndIn, 90.0, 100.0);
+        assert_eq!(
+            ReinerRubinstein::call(&bp),
+            BlackScholesMerton::call(&bp.option_params)
+        );
+    }
+
+    #[test]
+    fn expiry_and_zero_volatility() {
+        // the intrinsic value at expiry, or the rebate of a knock-in option
+        let expired = |barrier_type| {
+            let dp = DerivativeParameter::new(100.0, 95.0, 0.0, 0.05, 0.25);
+            BarrierParameter::new(dp, barrier_type, 90.0, 3.0)
+        };
+        assert_eq!(
+            ReinerRubinstein::call(&expired(BarrierType::DownAndOut)),
+            5.0
+        );
+        assert_eq!(
+            ReinerRubinstein::put(&expired(BarrierType::DownAndOut)),
+            0.0
+        );
+        assert_eq!(
+            ReinerRubinstein::call(&expired(BarrierType::DownAndIn)),
+            3.0
+        );
+
+        // the asset rises to the forward above the barrier
+        let dp = DerivativeParameter::new(100.0, 95.0, 1.0, 0.05, 0.0).with_dividend_yield(0.02);
+        let bp = BarrierParameter::new(dp, BarrierType::DownAndOut, 90.0, 3.0);
+        assert_approx_eq!(
+            ReinerRubinstein::call(&bp),
+            BlackScholesMerton::call(&dp),
+            1e-12
+        );
+        let small_vola = BarrierParameter {
+            option_params: DerivativeParameter { vola: 1e-6, ..dp },
+            ..bp
+        };
+        assert_approx_eq!(
+            ReinerRubinstein::call(&small_vola),
+            ReinerRubinstein::call(&bp),
+            1e-8
+        );
+
+        // the asset falls to the forward below the barrier, which it hits at ln(0.95) / -0.08
+        let dp = DerivativeParameter::new(100.0, 95.0, 1.0, 0.02, 0.0).with_dividend_yield(0.1);
+        let bp = BarrierParameter::new(dp, BarrierType::DownAndOut, 95.0, 3.0);
+        let rebate = 3.0 * (0.02 * 0.95_f64.ln() / 0.08).exp();
+        assert_approx_eq!(ReinerRubinstein::call(&bp), rebate, 1e-12);
+        let bp = BarrierParameter::new(dp, BarrierType::DownAndIn, 95.0, 3.0);
+        assert_eq!(ReinerRubinstein::call(&bp), 0.0);
+    }
+
+    #[test]
+    fn automatic_differentiation() {
+        let dp = DerivativeParameter::new(100.0, 0.0, 0.5, 0.08, 0.25).with_dividend_yield(0.04);
+        for (barrier_type, barrier) in [
+            (BarrierType::DownAndIn, 95.0),
+            (BarrierType::DownAndOut, 95.0),
+            (BarrierType::UpAndIn, 105.0),
+            (BarrierType::UpAndOut, 105.0),
+        ] {
+            for strike in [90.0, 110.0] {
+                let dp = DerivativeParameter { strike, ..dp };
+                for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+                    assert_dual_derivatives_match_finite_differences::<ReinerRubinstein>(
+                        &dp,
+                        &exercise_type,
+                        |dp| BarrierParameter::new(dp, barrier_type, barrier, 3.0),
+                        |dp| {
+                            let (barrier, rebate) = (Dual::constant(barrier), Dual::constant(3.0));
+                            BarrierParameter::new(dp, barrier_type, barrier, rebate)
+                        },
+                    );
+                }
+            }
+        }
+    }
+}
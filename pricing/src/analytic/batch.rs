@@ -0,0 +1,582 @@
+use std::f64::consts::PI;
+
+use ndarray::{Array1, Array2, ArrayView1, ArrayViewMut1, Ix1, NdProducer, Zip};
+
+use crate::analytic::black_scholes::{BlackScholesMerton, GeneralizedBlackScholes, OptionPrice};
+use crate::common::models::{DerivativeParameter, ExerciseType};
+use crate::common::normal::cdf_with_gaussian;
+use crate::common::numeric::Numeric;
+use crate::error::PricingError;
+
+/// Option parameters in columns with one entry per contract, e.g. an option chain. Columns with
+/// a single entry are shared by all contracts.
+#[derive(Clone, Debug)]
+pub struct DerivativeParameterBatch<'a, T: Numeric = f64> {
+    asset_price: ArrayView1<'a, T>,
+    strike: ArrayView1<'a, T>,
+    time_to_expiration: ArrayView1<'a, T>,
+    rfr: ArrayView1<'a, T>,
+    vola: ArrayView1<'a, T>,
+    dividend_yield: Option<ArrayView1<'a, T>>,
+    len: usize,
+}
+
+/// The entry of a column for a contract, broadcasting columns with a single entry.
+fn entry<T: Numeric>(column: &ArrayView1<T>, idx: usize) -> T {
+    if column.len() == 1 {
+        column[0]
+    } else {
+        column[idx]
+    }
+}
+
+fn check_length(column: &'static str, length: usize, expected: usize) -> Result<(), PricingError> {
+    if length == expected || length == 1 {
+        Ok(())
+    } else {
+        Err(PricingError::ColumnLengthMismatch {
+            column,
+            length,
+            expected,
+        })
+    }
+}
+
+impl<'a, T: Numeric> DerivativeParameterBatch<'a, T> {
+    /// The columns are slices, arrays or array views of the same length or of length one.
+    pub fn new(
+        asset_price: impl Into<ArrayView1<'a, T>>,
+        strike: impl Into<ArrayView1<'a, T>>,
+        time_to_expiration: impl Into<ArrayView1<'a, T>>,
+        rfr: impl Into<ArrayView1<'a, T>>,
+        vola: impl Into<ArrayView1<'a, T>>,
+    ) -> Result<Self, PricingError> {
+        let columns = [
+            ("asset_price", asset_price.into()),
+            ("strike", strike.into()),
+            ("time_to_expiration", time_to_expiration.into()),
+            ("rfr", rfr.into()),
+            ("vola", vola.into()),
+        ];
+        let len = columns.iter().map(|(_, c)| c.len()).max().unwrap_or(0);
+        for (name, column) in &columns {
+            check_length(name, column.len(), len)?;
+        }
+        let [asset_price, strike, time_to_expiration, rfr, vola] = columns.map(|(_, c)| c);
+        Ok(Self {
+            asset_price,
+            strike,
+            time_to_expiration,
+            rfr,
+            vola,
+            dividend_yield: None,
+            len,
+        })
+    }
+
+    pub fn with_dividend_yield(
+        self,
+        dividend_yield: impl Into<ArrayView1<'a, T>>,
+    ) -> Result<Self, PricingError> {
+        let dividend_yield = dividend_yield.into();
+        check_length("dividend_yield", dividend_yield.len(), self.len)?;
+        Ok(Self {
+            dividend_yield: Some(dividend_yield),
+            ..self
+        })
+    }
+
+    /// The number of contracts.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The option parameters of a single contract.
+    pub fn contract(&self, idx: usize) -> DerivativeParameter<T> {
+        let dp = DerivativeParameter::new(
+            entry(&self.asset_price, idx),
+            entry(&self.strike, idx),
+            entry(&self.time_to_expiration, idx),
+            entry(&self.rfr, idx),
+            entry(&self.vola, idx),
+        );
+        match &self.dividend_yield {
+            Some(dividend_yield) => dp.with_dividend_yield(entry(dividend_yield, idx)),
+            None => dp,
+        }
+    }
+
+    /// Prices every contract with a pricer generic over the numeric type, e.g. in f32 or in
+    /// [crate::common::numeric::Dual] numbers for the derivatives of the whole batch.
+    pub fn price<P>(&self, exercise_type: &ExerciseType) -> Array1<T>
+    where
+        P: OptionPrice<T, Params = DerivativeParameter<T>>,
+        T: Send + Sync,
+    {
+        let mut prices = Array1::from_elem(self.len, T::zero());
+        for_each_contract(prices.view_mut(), |idx, price| {
+            *price = P::price(&self.contract(idx), exercise_type);
+        });
+        prices
+    }
+}
+
+/// The price and the Greeks in the columns of a [BatchValuation].
+const NR_VALUES: usize = 9;
+const PRICE: usize = 0;
+const DELTA: usize = 1;
+const GAMMA: usize = 2;
+const VEGA: usize = 3;
+const THETA: usize = 4;
+const RHO: usize = 5;
+const VANNA: usize = 6;
+const VOLGA: usize = 7;
+const CHARM: usize = 8;
+
+/// The prices and Greeks of a batch with one row per contract, with the conventions of
+/// [crate::analytic::black_scholes::OptionGreeks].
+#[derive(Clone, Debug)]
+pub struct BatchValuation {
+    values: Array2<f64>,
+}
+
+impl BatchValuation {
+    pub fn price(&self) -> ArrayView1<'_, f64> {
+        self.values.column(PRICE)
+    }
+
+    pub fn delta(&self) -> ArrayView1<'_, f64> {
+        self.values.column(DELTA)
+    }
+
+    pub fn gamma(&self) -> ArrayView1<'_, f64> {
+        self.values.column(GAMMA)
+    }
+
+    pub fn vega(&self) -> ArrayView1<'_, f64> {
+        self.values.column(VEGA)
+    }
+
+    pub fn theta(&self) -> ArrayView1<'_, f64> {
+        self.values.column(THETA)
+    }
+
+    pub fn rho(&self) -> ArrayView1<'_, f64> {
+        self.values.column(RHO)
+    }
+
+    pub fn vanna(&self) -> ArrayView1<'_, f64> {
+        self.values.column(VANNA)
+    }
+
+    pub fn volga(&self) -> ArrayView1<'_, f64> {
+        self.values.column(VOLGA)
+    }
+
+    pub fn charm(&self) -> ArrayView1<'_, f64> {
+        self.values.column(CHARM)
+    }
+}
+
+/// Applies the function to the index and the output of every contract, on the rayon thread
+/// pool with the `rayon` feature.
+fn for_each_contract<P>(outputs: P, f: impl Fn(usize, P::Item) + Sync + Send)
+where
+    P: NdProducer<Dim = Ix1> + Send,
+    P::Item: Send,
+{
+    let contracts = Zip::indexed(outputs);
+    #[cfg(feature = "rayon")]
+    contracts.par_for_each(f);
+    #[cfg(not(feature = "rayon"))]
+    contracts.for_each(f);
+}
+
+/// The quantities of the generalized Black-Scholes formula shared by the price and the Greeks.
+struct Contract {
+    exercise_type: ExerciseType,
+    phi: f64,
+    gbs: GeneralizedBlackScholes,
+    dp: DerivativeParameter,
+    sqrt_t: f64,
+    carry_factor: f64,
+    discount_factor: f64,
+    /// $N(\phi d_1)$ and $N(\phi d_2)$
+    n1: f64,
+    n2: f64,
+    /// the density $n(d_1)$
+    density: f64,
+}
+
+impl Contract {
+    /// Uses $n(d_2) = n(d_1) F / K$ to evaluate a single exponential for both distributions,
+    /// unless the strike or the forward vanish and the product is undefined.
+    fn new(dp: DerivativeParameter, exercise_type: &ExerciseType) -> Self {
+        let phi = exercise_type.sign();
+        let (s, k, t, r, q) = (
+            dp.asset_price,
+            dp.strike,
+            dp.time_to_expiration,
+            dp.rfr,
+            dp.dividend_yield,
+        );
+        let gbs = BlackScholesMerton::generalized(&dp);
+        let (d1, d2) = (gbs.d1(), gbs.d2());
+        let discount_factor = (-r * t).exp();
+        let carry_factor = (-q * t).exp();
+        let gaussian = (-d1 * d1 / 2.0).exp();
+        let forward_moneyness = s * carry_factor / (k * discount_factor);
+        let gaussian2 = match gaussian * forward_moneyness {
+            gaussian2 if gaussian2.is_nan() => (-d2 * d2 / 2.0).exp(),
+            gaussian2 => gaussian2,
+        };
+        Self {
+            exercise_type: *exercise_type,
+            phi,
+            gbs,
+            dp,
+            sqrt_t: t.sqrt(),
+            carry_factor,
+            discount_factor,
+            n1: cdf_with_gaussian(phi * d1, gaussian),
+            n2: cdf_with_gaussian(phi * d2, gaussian2),
+            density: gaussian / (2.0 * PI).sqrt(),
+        }
+    }
+
+    fn price(&self) -> f64 {
+        let dp = &self.dp;
+        self.phi
+            * (dp.asset_price * self.carry_factor * self.n1
+                - dp.strike * self.discount_factor * self.n2)
+    }
+
+    fn write_valuation(&self, mut row: ArrayViewMut1<f64>) {
+        let Self {
+            exercise_type,
+            phi,
+            ref gbs,
+            dp,
+            sqrt_t,
+            carry_factor,
+            discount_factor,
+            n1,
+            n2,
+            density,
+        } = *self;
+        row[PRICE] = self.price();
+        if gbs.is_deterministic() {
+            // the limits at expiry or without volatility
+            row[DELTA] = gbs.delta(&exercise_type);
+            row[GAMMA] = gbs.gamma();
+            row[VEGA] = gbs.vega();
+            row[THETA] = gbs.theta(&exercise_type);
+            row[RHO] = gbs.rho(&exercise_type);
+            row[VANNA] = gbs.vanna();
+            row[VOLGA] = gbs.volga();
+            row[CHARM] = gbs.charm(&exercise_type);
+            return;
+        }
+        let (s, k, t, r, b, sigma) = (
+            dp.asset_price,
+            dp.strike,
+            dp.time_to_expiration,
+            dp.rfr,
+            dp.cost_of_carry(),
+            dp.vola,
+        );
+        let (d1, d2) = (gbs.d1(), gbs.d2());
+        let sigma_sqrt_t = sigma * sqrt_t;
+        let vega = s * carry_factor * density * sqrt_t;
+        row[DELTA] = phi * carry_factor * n1;
+        row[GAMMA] = carry_factor * density / (s * sigma_sqrt_t);
+        row[VEGA] = vega;
+        row[THETA] = -s * carry_factor * density * sigma / (2.0 * sqrt_t)
+            - phi * (b - r) * s * carry_factor * n1
+            - phi * r * k * discount_factor * n2;
+        row[RHO] = phi * t * k * discount_factor * n2;
+        row[VANNA] = -carry_factor * density * d2 / sigma;
+        row[VOLGA] = vega * d1 * d2 / sigma;
+        row[CHARM] =
+            -carry_factor * (density * (b / sigma_sqrt_t - d2 / (2.0 * t)) + phi * (b - r) * n1);
+    }
+}
+
+/// European options of [crate::analytic::black_scholes::BlackScholesMerton] priced in batches.
+/// Each contract evaluates three exponentials and a logarithm for its price and all Greeks.
+/// Futures options as in Black76 have a dividend yield equal to the interest rate.
+pub struct BlackScholesMertonBatch;
+
+impl BlackScholesMertonBatch {
+    pub fn price(batch: &DerivativeParameterBatch, exercise_type: &ExerciseType) -> Array1<f64> {
+        let mut prices = Array1::zeros(batch.len());
+        for_each_contract(prices.view_mut(), |idx, price| {
+            *price = Contract::new(batch.contract(idx), exercise_type).price();
+        });
+        prices
+    }
+
+    pub fn valuation(
+        batch: &DerivativeParameterBatch,
+        exercise_type: &ExerciseType,
+    ) -> BatchValuation {
+        let mut values = Array2::zeros((batch.len(), NR_VALUES));
+        for_each_contract(values.rows_mut(), |idx, row| {
+            Contract::new(batch.contract(idx), exercise_type).write_valuation(row);
+        });
+        BatchValuation { values }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::black_scholes::{BlackScholesMerton, OptionGreeks, OptionPrice};
+    use crate::common::numeric::Dual;
+    use assert_approx_eq::assert_approx_eq;
+    use ndarray::{arr1, Array};
+
+    #[test]
+    fn option_chain() {
+        let strikes = Array::linspace(60.0, 140.0, 81);
+        let batch = DerivativeParameterBatch::new(
+            &[100.0][..],
+            &strikes,
+            &[0.75][..],
+            &[0.03][..],
+            &[0.25][..],
+        )
+        .and_then(|batch| batch.with_dividend_yield(&[0.01][..]))
+        .unwrap();
+        assert_eq!(batch.len(), strikes.len());
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            let prices = BlackScholesMertonBatch::price(&batch, &exercise_type);
+            let valuation = BlackScholesMertonBatch::valuation(&batch, &exercise_type);
+            for (idx, price) in prices.iter().enumerate() {
+                let dp = batch.contract(idx);
+                assert_eq!(dp.strike, strikes[idx]);
+                assert_approx_eq!(
+                    *price,
+                    BlackScholesMerton::price(&dp, &exercise_type),
+                    1e-12
+                );
+                assert_approx_eq!(valuation.price()[idx], *price, 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn greeks() {
+        let asset_price = arr1(&[90.0, 100.0, 110.0, 250.0]);
+        let time_to_expiration = arr1(&[0.1, 0.5, 2.0, 3.5]);
+        let dividend_yield = arr1(&[0.0, 0.02, 0.05, 0.01]);
+        let batch = DerivativeParameterBatch::new(
+            &asset_price,
+            &[100.0, 95.0, 120.0, 310.0][..],
+            &time_to_expiration,
+            &[0.05][..],
+            &[0.2, 0.3, 0.15, 0.25][..],
+        )
+        .and_then(|batch| batch.with_dividend_yield(&dividend_yield))
+        .unwrap();
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            let valuation = BlackScholesMertonBatch::valuation(&batch, &exercise_type);
+            for idx in 0..batch.len() {
+                let dp = batch.contract(idx);
+                let et = &exercise_type;
+                assert_approx_eq!(
+                    valuation.delta()[idx],
+                    BlackScholesMerton::delta(&dp, et),
+                    1e-12
+                );
+                assert_approx_eq!(
+                    valuation.gamma()[idx],
+                    BlackScholesMerton::gamma(&dp, et),
+                    1e-12
+                );
+                assert_approx_eq!(
+                    valuation.vega()[idx],
+                    BlackScholesMerton::vega(&dp, et),
+                    1e-10
+                );
+                assert_approx_eq!(
+                    valuation.theta()[idx],
+                    BlackScholesMerton::theta(&dp, et),
+                    1e-10
+                );
+                assert_approx_eq!(
+                    valuation.rho()[idx],
+                    BlackScholesMerton::rho(&dp, et),
+                    1e-10
+                );
+                assert_approx_eq!(
+                    valuation.vanna()[idx],
+                    BlackScholesMerton::vanna(&dp, et),
+                    1e-12
+                );
+                assert_approx_eq!(
+                    valuation.volga()[idx],
+                    BlackScholesMerton::volga(&dp, et),
+                    1e-10
+                );
+                assert_approx_eq!(
+                    valuation.charm()[idx],
+                    BlackScholesMerton::charm(&dp, et),
+                    1e-12
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn expiry_and_zero_volatility() {
+        // expired and zero volatility rows in and out of the money and at the money
+        let batch = DerivativeParameterBatch::new(
+            &[100.0][..],
+            &[95.0, 100.0, 105.0, 95.0, 100.0, 105.0][..],
+            &[0.0, 0.0, 0.0, 1.5, 1.5, 1.5][..],
+            &[0.05][..],
+            &[0.2, 0.2, 0.2, 0.0, 0.0, 0.0][..],
+        )
+        .and_then(|batch| batch.with_dividend_yield(&[0.02][..]))
+        .unwrap();
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            let prices = BlackScholesMertonBatch::price(&batch, &exercise_type);
+            let valuation = BlackScholesMertonBatch::valuation(&batch, &exercise_type);
+            for idx in 0..batch.len() {
+                let dp = batch.contract(idx);
+                let et = &exercise_type;
+                let expected = [
+                    (prices[idx], BlackScholesMerton::price(&dp, et)),
+                    (valuation.price()[idx], BlackScholesMerton::price(&dp, et)),
+                    (valuation.delta()[idx], BlackScholesMerton::delta(&dp, et)),
+                    (valuation.gamma()[idx], BlackScholesMerton::gamma(&dp, et)),
+                    (valuation.vega()[idx], BlackScholesMerton::vega(&dp, et)),
+                    (valuation.theta()[idx], BlackScholesMerton::theta(&dp, et)),
+                    (valuation.rho()[idx], BlackScholesMerton::rho(&dp, et)),
+                    (valuation.vanna()[idx], BlackScholesMerton::vanna(&dp, et)),
+                    (valuation.volga()[idx], BlackScholesMerton::volga(&dp, et)),
+                    (valuation.charm()[idx], BlackScholesMerton::charm(&dp, et)),
+                ];
+                for (value, reference) in expected {
+                    assert!(value.is_finite());
+                    assert_approx_eq!(value, reference, 1e-12);
+                }
+            }
+            assert_eq!(valuation.gamma(), Array1::<f64>::zeros(batch.len()));
+        }
+    }
+
+    #[test]
+    fn zero_strike() {
+        // the call is the discounted forward and the put worthless
+        let batch = DerivativeParameterBatch::new(
+            &[100.0][..],
+            &[0.0][..],
+            &[0.0, 1.5][..],
+            &[0.05][..],
+            &[0.2][..],
+        )
+        .and_then(|batch| batch.with_dividend_yield(&[0.02][..]))
+        .unwrap();
+        let calls = BlackScholesMertonBatch::valuation(&batch, &ExerciseType::Call);
+        let puts = BlackScholesMertonBatch::valuation(&batch, &ExerciseType::Put);
+        for idx in 0..batch.len() {
+            let dp = batch.contract(idx);
+            let forward = 100.0 * (-0.02 * dp.time_to_expiration).exp();
+            assert_approx_eq!(calls.price()[idx], forward, 1e-12);
+            assert_approx_eq!(calls.delta()[idx], forward / 100.0, 1e-12);
+            assert_eq!(puts.price()[idx], 0.0);
+            assert_eq!(puts.delta()[idx], 0.0);
+        }
+    }
+
+    #[test]
+    fn generic_numbers() {
+        let strikes = Array::linspace(80.0, 120.0, 9);
+        let batch = DerivativeParameterBatch::new(
+            &[100.0][..],
+            &strikes,
+            &[0.5][..],
+            &[0.02][..],
+            &[0.3][..],
+        )
+        .unwrap();
+        let prices = BlackScholesMertonBatch::price(&batch, &ExerciseType::Put);
+
+        let strikes_f32 = strikes.mapv(|strike| strike as f32);
+        let batch_f32 = DerivativeParameterBatch::new(
+            &[100.0_f32][..],
+            &strikes_f32,
+            &[0.5][..],
+            &[0.02][..],
+            &[0.3][..],
+        )
+        .unwrap();
+        let prices_f32 = batch_f32.price::<BlackScholesMerton>(&ExerciseType::Put);
+        for (price_f32, price) in prices_f32.iter().zip(&prices) {
+            assert_approx_eq!(*price_f32 as f64, price, 1e-4);
+        }
+
+        // the asset price as variable gives the deltas of the chain
+        let strikes_dual = strikes.mapv(Dual::constant);
+        let [asset_price, time_to_expiration, rfr, vola] = [
+            [Dual::variable(100.0)],
+            [Dual::constant(0.5)],
+            [Dual::constant(0.02)],
+            [Dual::constant(0.3)],
+        ];
+        let batch_dual = DerivativeParameterBatch::new(
+            &asset_price[..],
+            &strikes_dual,
+            &time_to_expiration[..],
+            &rfr[..],
+            &vola[..],
+        )
+        .unwrap();
+        let prices_dual = batch_dual.price::<BlackScholesMerton>(&ExerciseType::Put);
+        for (idx, price) in prices_dual.iter().enumerate() {
+            assert_approx_eq!(price.value, prices[idx], 1e-12);
+            assert_approx_eq!(
+                price.derivative,
+                BlackScholesMerton::delta(&batch.contract(idx), &ExerciseType::Put),
+                1e-12
+            );
+        }
+    }
+
+    #[test]
+    fn column_lengths() {
+        let batch = DerivativeParameterBatch::new(
+            &[100.0, 101.0][..],
+            &[100.0, 100.0, 100.0][..],
+            &[1.0][..],
+            &[0.02][..],
+            &[0.2][..],
+        );
+        assert_eq!(
+            batch.unwrap_err(),
+            PricingError::ColumnLengthMismatch {
+                column: "asset_price",
+                length: 2,
+                expected: 3
+            }
+        );
+
+        let empty: [f64; 0] = [];
+        let batch = DerivativeParameterBatch::new(
+            &empty[..],
+            &empty[..],
+            &empty[..],
+            &empty[..],
+            &empty[..],
+        )
+        .unwrap();
+        assert!(batch.is_empty());
+        assert!(BlackScholesMertonBatch::price(&batch, &ExerciseType::Call).is_empty());
+    }
+}
@@ -1,81 +1,352 @@
-use crate::common::models::DerivativeParameter;
-use probability::distribution::{Distribution, Gaussian};
+use crate::common::models::{DerivativeParameter, ExerciseType};
+use crate::common::normal::{cdf, pdf};
+use crate::common::numeric::Numeric;
 
-pub(crate) fn cdf(d: f64) -> f64 {
-    let normal = Gaussian::new(0.0, 1.0);
-    normal.distribution(d)
+/// Prices in f64, or in any [Numeric] for the pricers generic over it, e.g. in
+/// [crate::common::numeric::Dual] numbers for their derivatives of any order.
+pub trait OptionPrice<T: Numeric = f64> {
+    type Params;
+    fn put(params: &Self::Params) -> T;
+    fn call(params: &Self::Params) -> T;
+
+    fn price(params: &Self::Params, exercise_type: &ExerciseType) -> T {
+        match exercise_type {
+            ExerciseType::Call => Self::call(params),
+            ExerciseType::Put => Self::put(params),
+        }
+    }
 }
 
-pub trait OptionPrice {
-    type Params;
-    fn put(params: &Self::Params) -> f64;
-    fn call(params: &Self::Params) -> f64;
+/// Closed-form sensitivities of the theoretical value.
+/// Theta and charm are taken with respect to the passage of calendar time (in years),
+/// i.e. they are the negative derivatives with respect to the time to expiration.
+pub trait OptionGreeks: OptionPrice {
+    /// dV/dS
+    fn delta(params: &Self::Params, exercise_type: &ExerciseType) -> f64;
+    /// d^2V/dS^2
+    fn gamma(params: &Self::Params, exercise_type: &ExerciseType) -> f64;
+    /// dV/dsigma
+    fn vega(params: &Self::Params, exercise_type: &ExerciseType) -> f64;
+    /// dV/dt
+    fn theta(params: &Self::Params, exercise_type: &ExerciseType) -> f64;
+    /// dV/dr
+    fn rho(params: &Self::Params, exercise_type: &ExerciseType) -> f64;
+    /// d^2V/dSdsigma
+    fn vanna(params: &Self::Params, exercise_type: &ExerciseType) -> f64;
+    /// d^2V/dsigma^2
+    fn volga(params: &Self::Params, exercise_type: &ExerciseType) -> f64;
+    /// d^2V/dSdt
+    fn charm(params: &Self::Params, exercise_type: &ExerciseType) -> f64;
+}
+
+/// The generalized Black-Scholes formula with cost of carry $b$:
+/// $b = r$ gives Black-Scholes for stocks and $b = 0$ gives Black76 for futures.
+/// See Haug, The Complete Guide to Option Pricing Formulas, chapter 1 and 2.
+pub(crate) struct GeneralizedBlackScholes<T: Numeric = f64> {
+    s: T,
+    k: T,
+    t: T,
+    r: T,
+    b: T,
+    sigma: T,
+    d1: T,
+    d2: T,
 }
 
-/// European Put and Call option prices for stocks.
+impl<T: Numeric> GeneralizedBlackScholes<T> {
+    pub(crate) fn new(dp: &DerivativeParameter<T>, cost_of_carry: T) -> Self {
+        let sigma_exp = dp.vola * dp.time_to_expiration.sqrt();
+        let log_moneyness = (dp.asset_price / dp.strike).ln();
+        let (d1, d2) = if sigma_exp.to_f64().is_nan() {
+            let d = T::from_f64(f64::NAN);
+            (d, d)
+        } else if sigma_exp > T::zero() {
+            let d1 = (log_moneyness
+                + (cost_of_carry + dp.vola.powi(2) / T::from_f64(2.0)) * dp.time_to_expiration)
+                / sigma_exp;
+            (d1, d1 - sigma_exp)
+        } else {
+            // at expiry or without volatility the option is exercised iff the forward is in the
+            // money, which gives the intrinsic value of the forward
+            let forward_moneyness = log_moneyness + cost_of_carry * dp.time_to_expiration;
+            let d = T::from_f64(if forward_moneyness > T::zero() {
+                f64::INFINITY
+            } else {
+                f64::NEG_INFINITY
+            });
+            (d, d)
+        };
+        Self {
+            s: dp.asset_price,
+            k: dp.strike,
+            t: dp.time_to_expiration,
+            r: dp.rfr,
+            b: cost_of_carry,
+            sigma: dp.vola,
+            d1,
+            d2,
+        }
+    }
+
+    pub(crate) fn d1(&self) -> T {
+        self.d1
+    }
+
+    pub(crate) fn d2(&self) -> T {
+        self.d2
+    }
+
+    /// e^{(b - r)T}, the factor converting the spot into the discounted forward
+    fn carry_factor(&self) -> T {
+        ((self.b - self.r) * self.t).exp()
+    }
+
+    fn discount_factor(&self) -> T {
+        (-self.r * self.t).exp()
+    }
+
+    pub(crate) fn call(&self) -> T {
+        self.d1.norm_cdf() * self.s * self.carry_factor()
+            - self.d2.norm_cdf() * self.k * self.discount_factor()
+    }
+
+    pub(crate) fn put(&self) -> T {
+        (-self.d2).norm_cdf() * self.k * self.discount_factor()
+            - (-self.d1).norm_cdf() * self.s * self.carry_factor()
+    }
+
+    pub(crate) fn price(&self, exercise_type: &ExerciseType) -> T {
+        match exercise_type {
+            ExerciseType::Call => self.call(),
+            ExerciseType::Put => self.put(),
+        }
+    }
+}
+
+impl GeneralizedBlackScholes {
+    /// At expiry or without volatility d1 and d2 are infinite and the terms with the density
+    /// vanish; at the money the density is a Dirac delta, which is taken as zero as well.
+    pub(crate) fn is_deterministic(&self) -> bool {
+        self.d1.is_infinite()
+    }
+
+    /// Model-free bounds (lower, upper) of the price, attained for zero and infinite volatility.
+    pub(crate) fn price_bounds(&self, exercise_type: &ExerciseType) -> (f64, f64) {
+        let forward = self.s * self.carry_factor();
+        let strike = self.k * self.discount_factor();
+        match exercise_type {
+            ExerciseType::Call => ((forward - strike).max(0.0), forward),
+            ExerciseType::Put => ((strike - forward).max(0.0), strike),
+        }
+    }
+
+    pub(crate) fn delta(&self, exercise_type: &ExerciseType) -> f64 {
+        match exercise_type {
+            ExerciseType::Call => self.carry_factor() * cdf(self.d1),
+            ExerciseType::Put => self.carry_factor() * (cdf(self.d1) - 1.0),
+        }
+    }
+
+    pub(crate) fn gamma(&self) -> f64 {
+        if self.is_deterministic() {
+            return 0.0;
+        }
+        self.carry_factor() * pdf(self.d1) / (self.s * self.sigma * self.t.sqrt())
+    }
+
+    pub(crate) fn vega(&self) -> f64 {
+        self.s * self.carry_factor() * pdf(self.d1) * self.t.sqrt()
+    }
+
+    pub(crate) fn theta(&self, exercise_type: &ExerciseType) -> f64 {
+        let time_decay = if self.is_deterministic() {
+            0.0
+        } else {
+            -self.s * self.carry_factor() * pdf(self.d1) * self.sigma / (2.0 * self.t.sqrt())
+        };
+        let carry = (self.b - self.r) * self.s * self.carry_factor();
+        let interest = self.r * self.k * self.discount_factor();
+        match exercise_type {
+            ExerciseType::Call => time_decay - carry * cdf(self.d1) - interest * cdf(self.d2),
+            ExerciseType::Put => time_decay + carry * cdf(-self.d1) + interest * cdf(-self.d2),
+        }
+    }
+
+    /// Sensitivity to the interest rate if the cost of carry moves along with it (b = r - q).
+    pub(crate) fn rho(&self, exercise_type: &ExerciseType) -> f64 {
+        let discounted_strike = self.t * self.k * self.discount_factor();
+        match exercise_type {
+            ExerciseType::Call => discounted_strike * cdf(self.d2),
+            ExerciseType::Put => -discounted_strike * cdf(-self.d2),
+        }
+    }
+
+    /// Sensitivity to the interest rate if the cost of carry is kept fixed (e.g. b = 0 for futures).
+    pub(crate) fn rho_fixed_carry(&self, exercise_type: &ExerciseType) -> f64 {
+        -self.t * self.price(exercise_type)
+    }
+
+    pub(crate) fn vanna(&self) -> f64 {
+        if self.is_deterministic() {
+            return 0.0;
+        }
+        -self.carry_factor() * pdf(self.d1) * self.d2 / self.sigma
+    }
+
+    pub(crate) fn volga(&self) -> f64 {
+        if self.is_deterministic() {
+            return 0.0;
+        }
+        self.vega() * self.d1 * self.d2 / self.sigma
+    }
+
+    pub(crate) fn charm(&self, exercise_type: &ExerciseType) -> f64 {
+        let density_term = if self.is_deterministic() {
+            0.0
+        } else {
+            let sigma_exp = self.sigma * self.t.sqrt();
+            pdf(self.d1) * (self.b / sigma_exp - self.d2 / (2.0 * self.t))
+        };
+        let carry = self.b - self.r;
+        match exercise_type {
+            ExerciseType::Call => -self.carry_factor() * (density_term + carry * cdf(self.d1)),
+            ExerciseType::Put => -self.carry_factor() * (density_term - carry * cdf(-self.d1)),
+        }
+    }
+}
+
+/// European Put and Call option prices for stocks paying a continuous dividend yield.
 /// https://en.wikipedia.org/wiki/Black-Scholes_model
 pub struct BlackScholesMerton;
 
-impl OptionPrice for BlackScholesMerton {
-    type Params = DerivativeParameter;
+/// European Put and Call option prices for FX rates, where the dividend yield
+/// is the foreign risk-free rate.
+/// https://en.wikipedia.org/wiki/Foreign_exchange_option#Garman%E2%80%93Kohlhagen_model
+pub type GarmanKohlhagen = BlackScholesMerton;
 
-    fn call(dp: &DerivativeParameter) -> f64 {
-        let sigma_exp = dp.vola * dp.time_to_expiration.sqrt();
-        let d1 = ((dp.asset_price / dp.strike).ln()
-            + (dp.rfr + dp.vola.powi(2) / 2.0) * dp.time_to_expiration)
-            / sigma_exp;
-        let d2 = d1 - sigma_exp;
-        cdf(d1) * dp.asset_price - cdf(d2) * dp.strike * (-dp.rfr * dp.time_to_expiration).exp()
+impl BlackScholesMerton {
+    pub(crate) fn generalized<T: Numeric>(
+        dp: &DerivativeParameter<T>,
+    ) -> GeneralizedBlackScholes<T> {
+        GeneralizedBlackScholes::new(dp, dp.cost_of_carry())
     }
+}
 
-    fn put(dp: &DerivativeParameter) -> f64 {
-        let sigma_exp = dp.vola * dp.time_to_expiration.sqrt();
-        let d1 = ((dp.asset_price / dp.strike).ln()
-            + (dp.rfr + dp.vola.powi(2) / 2.0) * dp.time_to_expiration)
-            / sigma_exp;
-        let d2 = d1 - sigma_exp;
-        cdf(-d2) * dp.strike * (-dp.rfr * dp.time_to_expiration).exp() - cdf(-d1) * dp.asset_price
+impl<T: Numeric> OptionPrice<T> for BlackScholesMerton {
+    type Params = DerivativeParameter<T>;
+
+    fn call(dp: &DerivativeParameter<T>) -> T {
+        Self::generalized(dp).call()
+    }
+
+    fn put(dp: &DerivativeParameter<T>) -> T {
+        Self::generalized(dp).put()
+    }
+}
+
+impl OptionGreeks for BlackScholesMerton {
+    fn delta(dp: &DerivativeParameter, exercise_type: &ExerciseType) -> f64 {
+        Self::generalized(dp).delta(exercise_type)
+    }
+
+    fn gamma(dp: &DerivativeParameter, _exercise_type: &ExerciseType) -> f64 {
+        Self::generalized(dp).gamma()
+    }
+
+    fn vega(dp: &DerivativeParameter, _exercise_type: &ExerciseType) -> f64 {
+        Self::generalized(dp).vega()
+    }
+
+    fn theta(dp: &DerivativeParameter, exercise_type: &ExerciseType) -> f64 {
+        Self::generalized(dp).theta(exercise_type)
+    }
+
+    fn rho(dp: &DerivativeParameter, exercise_type: &ExerciseType) -> f64 {
+        Self::generalized(dp).rho(exercise_type)
+    }
+
+    fn vanna(dp: &DerivativeParameter, _exercise_type: &ExerciseType) -> f64 {
+        Self::generalized(dp).vanna()
+    }
+
+    fn volga(dp: &DerivativeParameter, _exercise_type: &ExerciseType) -> f64 {
+        Self::generalized(dp).volga()
+    }
+
+    fn charm(dp: &DerivativeParameter, exercise_type: &ExerciseType) -> f64 {
+        Self::generalized(dp).charm(exercise_type)
     }
 }
 
 /// European Put and Call option prices for futures.
+/// The asset price is the futures (forward) price, hence the dividend yield is not used.
 /// https://en.wikipedia.org/wiki/Black_model
 pub struct Black76;
 
-impl OptionPrice for Black76 {
-    type Params = DerivativeParameter;
+impl Black76 {
+    pub(crate) fn generalized<T: Numeric>(
+        dp: &DerivativeParameter<T>,
+    ) -> GeneralizedBlackScholes<T> {
+        GeneralizedBlackScholes::new(dp, T::zero())
+    }
+}
+
+impl<T: Numeric> OptionPrice<T> for Black76 {
+    type Params = DerivativeParameter<T>;
 
-    fn call(dp: &DerivativeParameter) -> f64 {
-        let sigma_exp = dp.vola * dp.time_to_expiration.sqrt();
-        let d1 = ((dp.asset_price / dp.strike).ln() + (dp.vola.powi(2) / 2.0)) / sigma_exp;
-        let d2 = d1 - sigma_exp;
-        (-dp.rfr * dp.time_to_expiration).exp() * (cdf(d1) * dp.asset_price - cdf(d2) * dp.strike)
+    fn call(dp: &DerivativeParameter<T>) -> T {
+        Self::generalized(dp).call()
     }
 
-    fn put(dp: &DerivativeParameter) -> f64 {
-        let sigma_exp = dp.vola * dp.time_to_expiration.sqrt();
-        let d1 = ((dp.asset_price / dp.strike).ln() + (dp.vola.powi(2) / 2.0)) / sigma_exp;
-        let d2 = d1 - sigma_exp;
-        (-dp.rfr * dp.time_to_expiration).exp() * (cdf(-d2) * dp.strike - cdf(-d1) * dp.asset_price)
+    fn put(dp: &DerivativeParameter<T>) -> T {
+        Self::generalized(dp).put()
+    }
+}
+
+impl OptionGreeks for Black76 {
+    fn delta(dp: &DerivativeParameter, exercise_type: &ExerciseType) -> f64 {
+        Self::generalized(dp).delta(exercise_type)
+    }
+
+    fn gamma(dp: &DerivativeParameter, _exercise_type: &ExerciseType) -> f64 {
+        Self::generalized(dp).gamma()
+    }
+
+    fn vega(dp: &DerivativeParameter, _exercise_type: &ExerciseType) -> f64 {
+        Self::generalized(dp).vega()
+    }
+
+    fn theta(dp: &DerivativeParameter, exercise_type: &ExerciseType) -> f64 {
+        Self::generalized(dp).theta(exercise_type)
+    }
+
+    /// The futures price does not depend on the interest rate, only the discounting does.
+    fn rho(dp: &DerivativeParameter, exercise_type: &ExerciseType) -> f64 {
+        Self::generalized(dp).rho_fixed_carry(exercise_type)
+    }
+
+    fn vanna(dp: &DerivativeParameter, _exercise_type: &ExerciseType) -> f64 {
+        Self::generalized(dp).vanna()
+    }
+
+    fn volga(dp: &DerivativeParameter, _exercise_type: &ExerciseType) -> f64 {
+        Self::generalized(dp).volga()
+    }
+
+    fn charm(dp: &DerivativeParameter, exercise_type: &ExerciseType) -> f64 {
+        Self::generalized(dp).charm(exercise_type)
     }
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
+    use crate::common::numeric::Dual;
+    use crate::error::PricingError;
     use assert_approx_eq::assert_approx_eq;
 
     const TOLERANCE: f64 = 1e-4;
 
-    #[test]
-    fn normal_cdf() {
-        let center_value = cdf(0.0);
-        assert_eq!(center_value, 0.5);
-
-        let sigma_top = cdf(1.0); // mu + 1 sigma
-        assert_approx_eq!(sigma_top, 0.8413, 0.0001); // table value for 1.0
-    }
-
     #[test]
     fn european_call() {
         let dp = DerivativeParameter::new(300.0, 250.0, 1.0, 0.03, 0.15);
@@ -98,9 +369,347 @@ mod tests {
     fn european_put_call_parity() {
         let dp = DerivativeParameter::new(300.0, 250.0, 1.0, 0.03, 0.15);
         let put_call_parity = BlackScholesMerton::call(&dp) - BlackScholesMerton::put(&dp);
-        assert_eq!(
+        assert_approx_eq!(
             put_call_parity,
-            dp.asset_price - dp.strike * (-dp.rfr * dp.time_to_expiration).exp()
+            dp.asset_price - dp.strike * (-dp.rfr * dp.time_to_expiration).exp(),
+            1e-12
+        );
+    }
+
+    #[test]
+    fn futures_option() {
+        // Haug, The Complete Guide to Option Pricing Formulas, example 1.1.3
+        let dp = DerivativeParameter::new(19.0, 19.0, 0.75, 0.10, 0.28);
+        assert_approx_eq!(Black76::call(&dp), 1.7011, TOLERANCE);
+        assert_approx_eq!(Black76::put(&dp), 1.7011, TOLERANCE);
+    }
+
+    #[test]
+    fn european_call_greeks() {
+        // Hull, Options, Futures and Other Derivatives, chapter 19
+        let dp = DerivativeParameter::new(49.0, 50.0, 0.3846, 0.05, 0.2);
+        let call = ExerciseType::Call;
+        assert_approx_eq!(BlackScholesMerton::price(&dp, &call), 2.4004, TOLERANCE);
+        assert_approx_eq!(BlackScholesMerton::delta(&dp, &call), 0.5216, TOLERANCE);
+        assert_approx_eq!(BlackScholesMerton::gamma(&dp, &call), 0.0655, TOLERANCE);
+        assert_approx_eq!(BlackScholesMerton::vega(&dp, &call), 12.1052, TOLERANCE);
+        assert_approx_eq!(BlackScholesMerton::theta(&dp, &call), -4.3054, TOLERANCE);
+        assert_approx_eq!(BlackScholesMerton::rho(&dp, &call), 8.9066, TOLERANCE);
+    }
+
+    #[test]
+    fn automatic_differentiation() {
+        let dp = DerivativeParameter::new(100.0, 110.0, 0.75, 0.02, 0.3).with_dividend_yield(0.06);
+        let constant = dp.map(Dual::constant);
+        let second_order = dp.map(|x| Dual::constant(Dual::constant(x)));
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            let et = &exercise_type;
+            let price = |dp: &DerivativeParameter<Dual>| BlackScholesMerton::price(dp, et);
+            let delta = price(&DerivativeParameter {
+                asset_price: Dual::variable(dp.asset_price),
+                ..constant
+            });
+            assert_approx_eq!(delta.value, BlackScholesMerton::price(&dp, et), 1e-12);
+            assert_approx_eq!(delta.derivative, BlackScholesMerton::delta(&dp, et), 1e-12);
+            let vega = price(&DerivativeParameter {
+                vola: Dual::variable(dp.vola),
+                ..constant
+            });
+            assert_approx_eq!(vega.derivative, BlackScholesMerton::vega(&dp, et), 1e-12);
+            // the rate moves the cost of carry along
+            let rho = price(&DerivativeParameter {
+                rfr: Dual::variable(dp.rfr),
+                ..constant
+            });
+            assert_approx_eq!(rho.derivative, BlackScholesMerton::rho(&dp, et), 1e-12);
+            let theta = price(&DerivativeParameter {
+                time_to_expiration: Dual::variable(dp.time_to_expiration),
+                ..constant
+            });
+            assert_approx_eq!(-theta.derivative, BlackScholesMerton::theta(&dp, et), 1e-12);
+
+            let gamma = BlackScholesMerton::price(
+                &DerivativeParameter {
+                    asset_price: Dual::variable(Dual::variable(dp.asset_price)),
+                    ..second_order
+                },
+                et,
+            );
+            assert_approx_eq!(
+                gamma.derivative.derivative,
+                BlackScholesMerton::gamma(&dp, et),
+                1e-12
+            );
+            let vanna = BlackScholesMerton::price(
+                &DerivativeParameter {
+                    asset_price: Dual::variable(Dual::constant(dp.asset_price)),
+                    vola: Dual::constant(Dual::variable(dp.vola)),
+                    ..second_order
+                },
+                et,
+            );
+            assert_approx_eq!(
+                vanna.derivative.derivative,
+                BlackScholesMerton::vanna(&dp, et),
+                1e-12
+            );
+        }
+    }
+
+    #[test]
+    fn single_precision() {
+        let dp = DerivativeParameter::new(19.0_f32, 19.0, 0.75, 0.10, 0.28);
+        assert_approx_eq!(Black76::call(&dp), 1.7011, 1e-4);
+        assert_approx_eq!(Black76::put(&dp), 1.7011, 1e-4);
+    }
+
+    /// Compare the closed forms against central finite differences of the prices.
+    pub(crate) fn assert_greeks_match_finite_differences<P>(
+        dp: &DerivativeParameter,
+        exercise_type: &ExerciseType,
+    ) where
+        P: OptionGreeks<Params = DerivativeParameter>,
+    {
+        assert_greeks_match_finite_differences_with::<P>(dp, exercise_type, |dp| dp);
+    }
+
+    /// Compare the closed forms against central finite differences of the prices for pricers
+    /// whose parameters wrap the option parameters.
+    pub(crate) fn assert_greeks_match_finite_differences_with<P: OptionGreeks>(
+        dp: &DerivativeParameter,
+        exercise_type: &ExerciseType,
+        params: impl Fn(DerivativeParameter) -> P::Params,
+    ) {
+        let h = 1e-4;
+        let shifted = |ds: f64, dt: f64, dr: f64, dv: f64| {
+            let shifted_dp = DerivativeParameter {
+                asset_price: dp.asset_price + ds,
+                time_to_expiration: dp.time_to_expiration + dt,
+                rfr: dp.rfr + dr,
+                vola: dp.vola + dv,
+                ..*dp
+            };
+            P::price(&params(shifted_dp), exercise_type)
+        };
+        let price = shifted(0.0, 0.0, 0.0, 0.0);
+        let params = params(*dp);
+
+        let delta = (shifted(h, 0.0, 0.0, 0.0) - shifted(-h, 0.0, 0.0, 0.0)) / (2.0 * h);
+        assert_approx_eq!(P::delta(&params, exercise_type), delta, TOLERANCE);
+
+        let gamma =
+            (shifted(h, 0.0, 0.0, 0.0) - 2.0 * price + shifted(-h, 0.0, 0.0, 0.0)) / h.powi(2);
+        assert_approx_eq!(P::gamma(&params, exercise_type), gamma, TOLERANCE);
+
+        let vega = (shifted(0.0, 0.0, 0.0, h) - shifted(0.0, 0.0, 0.0, -h)) / (2.0 * h);
+        assert_approx_eq!(P::vega(&params, exercise_type), vega, TOLERANCE);
+
+        let theta = -(shifted(0.0, h, 0.0, 0.0) - shifted(0.0, -h, 0.0, 0.0)) / (2.0 * h);
+        assert_approx_eq!(P::theta(&params, exercise_type), theta, TOLERANCE);
+
+        let rho = (shifted(0.0, 0.0, h, 0.0) - shifted(0.0, 0.0, -h, 0.0)) / (2.0 * h);
+        assert_approx_eq!(P::rho(&params, exercise_type), rho, TOLERANCE);
+
+        let vanna = (shifted(h, 0.0, 0.0, h) - shifted(h, 0.0, 0.0, -h) - shifted(-h, 0.0, 0.0, h)
+            + shifted(-h, 0.0, 0.0, -h))
+            / (4.0 * h.powi(2));
+        assert_approx_eq!(P::vanna(&params, exercise_type), vanna, TOLERANCE);
+
+        let volga =
+            (shifted(0.0, 0.0, 0.0, h) - 2.0 * price + shifted(0.0, 0.0, 0.0, -h)) / h.powi(2);
+        assert_approx_eq!(P::volga(&params, exercise_type), volga, 1e-2);
+
+        let charm =
+            -(shifted(h, h, 0.0, 0.0) - shifted(h, -h, 0.0, 0.0) - shifted(-h, h, 0.0, 0.0)
+                + shifted(-h, -h, 0.0, 0.0))
+                / (4.0 * h.powi(2));
+        assert_approx_eq!(P::charm(&params, exercise_type), charm, TOLERANCE);
+    }
+
+    /// Compare the derivatives of the prices in [Dual] numbers with respect to each of the option
+    /// parameters against central finite differences, for pricers generic over [Numeric] whose
+    /// parameters are built alike from the option parameters in f64 and in dual numbers.
+    pub(crate) fn assert_dual_derivatives_match_finite_differences<P>(
+        dp: &DerivativeParameter,
+        exercise_type: &ExerciseType,
+        params: impl Fn(DerivativeParameter) -> <P as OptionPrice>::Params,
+        dual_params: impl Fn(DerivativeParameter<Dual>) -> <P as OptionPrice<Dual>>::Params,
+    ) where
+        P: OptionPrice + OptionPrice<Dual>,
+    {
+        let h = 1e-5;
+        let constant = dp.map(Dual::constant);
+        let variables = [
+            DerivativeParameter {
+                asset_price: Dual::variable(dp.asset_price),
+                ..constant
+            },
+            DerivativeParameter {
+                strike: Dual::variable(dp.strike),
+                ..constant
+            },
+            DerivativeParameter {
+                time_to_expiration: Dual::variable(dp.time_to_expiration),
+                ..constant
+            },
+            DerivativeParameter {
+                rfr: Dual::variable(dp.rfr),
+                ..constant
+            },
+            DerivativeParameter {
+                vola: Dual::variable(dp.vola),
+                ..constant
+            },
+            DerivativeParameter {
+                dividend_yield: Dual::variable(dp.dividend_yield),
+                ..constant
+            },
+        ];
+        for variable in variables {
+            let price = <P as OptionPrice<Dual>>::price(&dual_params(variable), exercise_type);
+            let shifted = |shift: f64| {
+                let shifted_dp = variable.map(|x| x.value + shift * x.derivative);
+                <P as OptionPrice>::price(&params(shifted_dp), exercise_type)
+            };
+            assert_approx_eq!(price.value, shifted(0.0), 1e-12);
+            let derivative = (shifted(h) - shifted(-h)) / (2.0 * h);
+            assert_approx_eq!(price.derivative, derivative, TOLERANCE);
+        }
+    }
+
+    #[test]
+    fn greeks_finite_differences() {
+        let dp = DerivativeParameter::new(310.0, 250.0, 3.5, 0.05, 0.25);
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            assert_greeks_match_finite_differences::<BlackScholesMerton>(&dp, &exercise_type);
+            assert_greeks_match_finite_differences::<Black76>(&dp, &exercise_type);
+        }
+
+        let dp = DerivativeParameter::new(100.0, 110.0, 0.75, 0.02, 0.3).with_dividend_yield(0.06);
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            assert_greeks_match_finite_differences::<BlackScholesMerton>(&dp, &exercise_type);
+        }
+    }
+
+    #[test]
+    fn dividend_paying_stock() {
+        // Haug, The Complete Guide to Option Pricing Formulas, example 1.1.2
+        let dp = DerivativeParameter::new(100.0, 95.0, 0.5, 0.10, 0.20).with_dividend_yield(0.05);
+        assert_approx_eq!(BlackScholesMerton::put(&dp), 2.4648, TOLERANCE);
+
+        let put_call_parity = BlackScholesMerton::call(&dp) - BlackScholesMerton::put(&dp);
+        assert_approx_eq!(
+            put_call_parity,
+            dp.asset_price * (-dp.dividend_yield * dp.time_to_expiration).exp()
+                - dp.strike * (-dp.rfr * dp.time_to_expiration).exp(),
+            1e-10
+        );
+    }
+
+    #[test]
+    fn currency_option() {
+        // Haug, The Complete Guide to Option Pricing Formulas, example 1.1.6
+        let dp = DerivativeParameter::new(1.56, 1.60, 0.5, 0.06, 0.12).with_dividend_yield(0.08);
+        assert_approx_eq!(GarmanKohlhagen::call(&dp), 0.0291, TOLERANCE);
+    }
+
+    #[test]
+    fn nan_volatility() {
+        // not mistaken for the limit without volatility
+        let dp = DerivativeParameter::new(100.0, 95.0, 1.0, 0.05, f64::NAN);
+        assert!(BlackScholesMerton::call(&dp).is_nan());
+        assert!(Black76::put(&dp).is_nan());
+        assert!(BlackScholesMerton::delta(&dp, &ExerciseType::Call).is_nan());
+    }
+
+    #[test]
+    fn expiry_and_zero_volatility() {
+        // the intrinsic value at expiry
+        for (strike, call, put) in [(95.0, 5.0, 0.0), (100.0, 0.0, 0.0), (105.0, 0.0, 5.0)] {
+            let dp = DerivativeParameter::new(100.0, strike, 0.0, 0.05, 0.2);
+            assert_eq!(BlackScholesMerton::call(&dp), call);
+            assert_eq!(BlackScholesMerton::put(&dp), put);
+        }
+
+        // the discounted intrinsic value of the forward without volatility
+        let dp = DerivativeParameter::new(100.0, 100.0, 2.0, 0.05, 0.0).with_dividend_yield(0.02);
+        let forward = 100.0 * (-0.02_f64 * 2.0).exp() - 100.0 * (-0.05_f64 * 2.0).exp();
+        assert_approx_eq!(BlackScholesMerton::call(&dp), forward, 1e-12);
+        assert_eq!(BlackScholesMerton::put(&dp), 0.0);
+        assert_eq!(Black76::call(&dp), 0.0);
+        assert_eq!(Black76::put(&dp), 0.0);
+
+        // the limits are continuous
+        let small_vola = DerivativeParameter { vola: 1e-8, ..dp };
+        assert_approx_eq!(BlackScholesMerton::call(&small_vola), forward, 1e-12);
+        assert_approx_eq!(
+            BlackScholesMerton::delta(&dp, &ExerciseType::Call),
+            (-0.02_f64 * 2.0).exp(),
+            1e-12
+        );
+    }
+
+    #[test]
+    fn greeks_at_expiry_and_zero_volatility() {
+        let call = ExerciseType::Call;
+        for tte in [0.0, 1.0] {
+            let dp = DerivativeParameter::new(100.0, 95.0, tte, 0.05, 0.2 * (1.0 - tte))
+                .with_dividend_yield(0.02);
+            for strike in [95.0, 100.0, 105.0] {
+                let dp = DerivativeParameter { strike, ..dp };
+                assert_eq!(BlackScholesMerton::gamma(&dp, &call), 0.0);
+                assert_eq!(BlackScholesMerton::vega(&dp, &call), 0.0);
+                assert_eq!(BlackScholesMerton::vanna(&dp, &call), 0.0);
+                assert_eq!(BlackScholesMerton::volga(&dp, &call), 0.0);
+                assert_eq!(Black76::gamma(&dp, &call), 0.0);
+                assert_eq!(Black76::vanna(&dp, &call), 0.0);
+            }
+        }
+
+        // at expiry the value in the money only changes with the interest on the strike
+        let dp = DerivativeParameter::new(100.0, 95.0, 0.0, 0.05, 0.2);
+        assert_approx_eq!(BlackScholesMerton::theta(&dp, &call), -0.05 * 95.0, 1e-12);
+        assert_approx_eq!(
+            BlackScholesMerton::theta(&dp, &ExerciseType::Put),
+            0.0,
+            1e-12
+        );
+
+        // without volatility the delta of the call in the money is e^{-qT}, which grows at q
+        let dp = DerivativeParameter::new(100.0, 95.0, 2.0, 0.05, 0.0).with_dividend_yield(0.02);
+        let charm = 0.02 * (-0.02_f64 * 2.0).exp();
+        assert_approx_eq!(BlackScholesMerton::charm(&dp, &call), charm, 1e-12);
+        let small_vola = DerivativeParameter { vola: 1e-4, ..dp };
+        assert_approx_eq!(BlackScholesMerton::charm(&small_vola, &call), charm, 1e-12);
+        assert_approx_eq!(
+            BlackScholesMerton::theta(&small_vola, &call),
+            BlackScholesMerton::theta(&dp, &call),
+            1e-12
+        );
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        assert!(DerivativeParameter::try_new(100.0, 95.0, 0.0, 0.05, 0.0).is_ok());
+        assert_eq!(
+            DerivativeParameter::try_new(100.0, 95.0, -1.0, 0.05, 0.2).unwrap_err(),
+            PricingError::InvalidParameter {
+                name: "time_to_expiration",
+                value: -1.0
+            }
+        );
+        assert_eq!(
+            DerivativeParameter::try_new(100.0, 95.0, 1.0, 0.05, -0.2).unwrap_err(),
+            PricingError::InvalidParameter {
+                name: "vola",
+                value: -0.2
+            }
         );
+        assert!(DerivativeParameter::try_new(f64::NAN, 95.0, 1.0, 0.05, 0.2).is_err());
+        assert!(DerivativeParameter::try_new(100.0, f64::INFINITY, 1.0, 0.05, 0.2).is_err());
+        assert!(DerivativeParameter::new(100.0, 95.0, 1.0, 0.05, 0.2)
+            .with_dividend_yield(f64::NAN)
+            .validate()
+            .is_err());
     }
 }
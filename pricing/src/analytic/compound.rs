@@ -0,0 +1,653 @@
+use crate::analytic::black_scholes::{BlackScholesMerton, OptionGreeks, OptionPrice};
+use crate::common::models::{DerivativeParameter, ExerciseType};
+use crate::common::normal::{bivariate_cdf, cdf};
+use crate::error::PricingError;
+
+const CRITICAL_PRICE_TOLERANCE: f64 = 1e-12;
+const MAX_CRITICAL_PRICE_ITERATIONS: usize = 200;
+
+/// The root of an increasing or decreasing function of the asset price, given its value and
+/// its derivative, by Newton's method in the log price safeguarded with bisection. The root is
+/// bracketed around the seed, None if there is no sign change within 1e-30 and 1e30 of it.
+fn critical_price(seed: f64, f: impl Fn(f64) -> (f64, f64)) -> Option<f64> {
+    let value = |x: f64| f(x.exp()).0;
+    let (mut lower, mut upper) = (seed.ln() - 1.0, seed.ln() + 1.0);
+    let increasing = value(upper) > value(lower);
+    let above = |x: f64| (value(x) > 0.0) == increasing;
+    while above(lower) {
+        lower -= 2.0 * (upper - lower);
+        if lower < -69.0 {
+            return None;
+        }
+    }
+    while !above(upper) {
+        upper += 2.0 * (upper - lower);
+        if upper > 69.0 {
+            return None;
+        }
+    }
+
+    let mut x = (lower + upper) / 2.0;
+    for _ in 0..MAX_CRITICAL_PRICE_ITERATIONS {
+        let (fx, derivative) = f(x.exp());
+        if (fx > 0.0) == increasing {
+            upper = x;
+        } else {
+            lower = x;
+        }
+        // the derivative with respect to the log price
+        let newton = x - fx / (derivative * x.exp());
+        let next = if newton > lower && newton < upper {
+            newton
+        } else {
+            (lower + upper) / 2.0
+        };
+        if (next - x).abs() < CRITICAL_PRICE_TOLERANCE {
+            return Some(next.exp());
+        }
+        x = next;
+    }
+    Some(x.exp())
+}
+
+/// Checks that the time is not negative and before the expiration.
+fn check_time_before(name: &'static str, time: f64, expiration: f64) -> Result<(), PricingError> {
+    PricingError::check_parameter(name, time, time >= 0.0 && time < expiration)
+}
+
+/// At the first expiration without time or without volatility, the asset is at its forward
+/// $S e^{b t_1}$ and the payoff at that time for this asset price is discounted.
+fn deterministic_value(dp: &DerivativeParameter, t1: f64, payoff: impl Fn(f64) -> f64) -> f64 {
+    let forward = dp.asset_price * (dp.cost_of_carry() * t1).exp();
+    (-dp.rfr * t1).exp() * payoff(forward)
+}
+
+/// An option expiring at the compound expiration to buy (call) or sell (put) the underlying
+/// option for the compound strike. The option parameters are the ones of the underlying option.
+#[derive(Clone, Copy, Debug)]
+pub struct CompoundParameter {
+    pub option_params: DerivativeParameter,
+    pub underlying_type: ExerciseType,
+    /// the price paid or received for the underlying option
+    pub compound_strike: f64,
+    /// the time in years until the compound option expires, before the underlying option
+    pub compound_expiration: f64,
+}
+
+impl CompoundParameter {
+    pub fn new(
+        option_params: DerivativeParameter,
+        underlying_type: ExerciseType,
+        compound_strike: f64,
+        compound_expiration: f64,
+    ) -> Self {
+        Self {
+            option_params,
+            underlying_type,
+            compound_strike,
+            compound_expiration,
+        }
+    }
+
+    /// As [CompoundParameter::new], but rejects a compound expiration which is not before the
+    /// expiration of the underlying option.
+    pub fn try_new(
+        option_params: DerivativeParameter,
+        underlying_type: ExerciseType,
+        compound_strike: f64,
+        compound_expiration: f64,
+    ) -> Result<Self, PricingError> {
+        let cp = Self::new(
+            option_params,
+            underlying_type,
+            compound_strike,
+            compound_expiration,
+        );
+        cp.validate()?;
+        Ok(cp)
+    }
+
+    pub fn validate(&self) -> Result<(), PricingError> {
+        self.option_params.validate()?;
+        PricingError::check_parameter(
+            "compound_strike",
+            self.compound_strike,
+            self.compound_strike.is_finite(),
+        )?;
+        check_time_before(
+            "compound_expiration",
+            self.compound_expiration,
+            self.option_params.time_to_expiration,
+        )
+    }
+
+    /// The underlying option at the compound expiration for the given asset price.
+    fn underlying_at_expiration(&self, asset_price: f64) -> DerivativeParameter {
+        DerivativeParameter {
+            asset_price,
+            time_to_expiration: self.option_params.time_to_expiration - self.compound_expiration,
+            ..self.option_params
+        }
+    }
+}
+
+/// Options on European options, e.g. a call on a call. The call and put are the compound
+/// options, the underlying type decides on the underlying option.
+/// See Geske, The Valuation of Compound Options (1979), and
+/// Haug, The Complete Guide to Option Pricing Formulas, chapter 4.12.
+pub struct Geske;
+
+impl Geske {
+    /// The asset price at the compound expiration at which the underlying option is worth the
+    /// compound strike, None if it is always worth less.
+    pub fn critical_price(cp: &CompoundParameter) -> Option<f64> {
+        let underlying_type = &cp.underlying_type;
+        critical_price(cp.option_params.strike, |asset_price| {
+            let dp = cp.underlying_at_expiration(asset_price);
+            (
+                BlackScholesMerton::price(&dp, underlying_type) - cp.compound_strike,
+                BlackScholesMerton::delta(&dp, underlying_type),
+            )
+        })
+    }
+
+    fn compound_price(cp: &CompoundParameter, exercise_type: &ExerciseType) -> f64 {
+        let dp = &cp.option_params;
+        let (s, x2, x1) = (dp.asset_price, dp.strike, cp.compound_strike);
+        let (t1, t2, r, b, sigma) = (
+            cp.compound_expiration,
+            dp.time_to_expiration,
+            dp.rfr,
+            dp.cost_of_carry(),
+            dp.vola,
+        );
+        let compound_discount_factor = (-r * t1).exp();
+
+        if sigma * t1.sqrt() == 0.0 {
+            let eta = exercise_type.sign();
+            return deterministic_value(dp, t1, |asset_price| {
+                let underlying = cp.underlying_at_expiration(asset_price);
+                (eta * (BlackScholesMerton::price(&underlying, &cp.underlying_type) - x1)).max(0.0)
+            });
+        }
+
+        let critical = match Self::critical_price(cp) {
+            Some(critical) => critical,
+            // only puts on puts are ever exercised, and always
+            None => {
+                return match exercise_type {
+                    ExerciseType::Call => 0.0,
+                    ExerciseType::Put => {
+                        x1 * compound_discount_factor - BlackScholesMerton::put(dp)
+                    }
+                }
+            }
+        };
+
+        let y1 = ((s / critical).ln() + (b + sigma.powi(2) / 2.0) * t1) / (sigma * t1.sqrt());
+        let y2 = y1 - sigma * t1.sqrt();
+        let z1 = ((s / x2).ln() + (b + sigma.powi(2) / 2.0) * t2) / (sigma * t2.sqrt());
+        let z2 = z1 - sigma * t2.sqrt();
+        let rho = (t1 / t2).sqrt();
+
+        // phi for the underlying option, eta for the compound option
+        let phi = cp.underlying_type.sign();
+        let eta = exercise_type.sign();
+        let forward = s * ((b - r) * t2).exp();
+        let strike = x2 * (-r * t2).exp();
+        phi * eta
+            * (forward * bivariate_cdf(phi * z1, phi * eta * y1, eta * rho)
+                - strike * bivariate_cdf(phi * z2, phi * eta * y2, eta * rho))
+            - eta * x1 * compound_discount_factor * cdf(phi * eta * y2)
+    }
+}
+
+impl OptionPrice for Geske {
+    type Params = CompoundParameter;
+
+    fn call(cp: &CompoundParameter) -> f64 {
+        Self::compound_price(cp, &ExerciseType::Call)
+    }
+
+    fn put(cp: &CompoundParameter) -> f64 {
+        Self::compound_price(cp, &ExerciseType::Put)
+    }
+}
+
+/// The holder chooses at the choice time whether the option is a call or a put with the strike
+/// and the expiration of the option parameters.
+#[derive(Clone, Copy, Debug)]
+pub struct ChooserParameter {
+    pub option_params: DerivativeParameter,
+    /// the time in years until the choice
+    pub choice_time: f64,
+}
+
+impl ChooserParameter {
+    pub fn new(option_params: DerivativeParameter, choice_time: f64) -> Self {
+        Self {
+            option_params,
+            choice_time,
+        }
+    }
+
+    /// As [ChooserParameter::new], but rejects a choice time which is not before the
+    /// expiration.
+    pub fn try_new(
+        option_params: DerivativeParameter,
+        choice_time: f64,
+    ) -> Result<Self, PricingError> {
+        let cp = Self::new(option_params, choice_time);
+        cp.validate()?;
+        Ok(cp)
+    }
+
+    pub fn validate(&self) -> Result<(), PricingError> {
+        self.option_params.validate()?;
+        check_time_before(
+            "choice_time",
+            self.choice_time,
+            self.option_params.time_to_expiration,
+        )
+    }
+}
+
+/// Simple chooser options, also called as-you-like-it options.
+/// See Rubinstein, Options for the Undecided (1991), and
+/// Haug, The Complete Guide to Option Pricing Formulas, chapter 4.11.1.
+pub struct SimpleChooser;
+
+impl SimpleChooser {
+    pub fn price(cp: &ChooserParameter) -> f64 {
+        let dp = &cp.option_params;
+        let (s, x, t1, t, r, b, sigma) = (
+            dp.asset_price,
+            dp.strike,
+            cp.choice_time,
+            dp.time_to_expiration,
+            dp.rfr,
+            dp.cost_of_carry(),
+            dp.vola,
+        );
+        if sigma * t1.sqrt() == 0.0 {
+            return deterministic_value(dp, t1, |asset_price| {
+                let option = DerivativeParameter {
+                    asset_price,
+                    time_to_expiration: t - t1,
+                    ..*dp
+                };
+                BlackScholesMerton::call(&option).max(BlackScholesMerton::put(&option))
+            });
+        }
+        let d = ((s / x).ln() + (b + sigma.powi(2) / 2.0) * t) / (sigma * t.sqrt());
+        let y = ((s / x).ln() + b * t + sigma.powi(2) * t1 / 2.0) / (sigma * t1.sqrt());
+        let forward = s * ((b - r) * t).exp();
+        let strike = x * (-r * t).exp();
+        forward * cdf(d) - strike * cdf(d - sigma * t.sqrt()) - forward * cdf(-y)
+            + strike * cdf(-y + sigma * t1.sqrt())
+    }
+}
+
+/// The holder chooses at the choice time between a call and a put with their own strikes and
+/// expirations. The option parameters are the ones of the call.
+#[derive(Clone, Copy, Debug)]
+pub struct ComplexChooserParameter {
+    pub option_params: DerivativeParameter,
+    pub put_strike: f64,
+    /// the time in years until the put expires
+    pub put_expiration: f64,
+    /// the time in years until the choice, before both options expire
+    pub choice_time: f64,
+}
+
+impl ComplexChooserParameter {
+    pub fn new(
+        option_params: DerivativeParameter,
+        put_strike: f64,
+        put_expiration: f64,
+        choice_time: f64,
+    ) -> Self {
+        Self {
+            option_params,
+            put_strike,
+            put_expiration,
+            choice_time,
+        }
+    }
+
+    /// As [ComplexChooserParameter::new], but rejects a choice time which is not before both
+    /// expirations.
+    pub fn try_new(
+        option_params: DerivativeParameter,
+        put_strike: f64,
+        put_expiration: f64,
+        choice_time: f64,
+    ) -> Result<Self, PricingError> {
+        let cp = Self::new(option_params, put_strike, put_expiration, choice_time);
+        cp.validate()?;
+        Ok(cp)
+    }
+
+    pub fn validate(&self) -> Result<(), PricingError> {
+        self.option_params.validate()?;
+        PricingError::check_parameter("put_strike", self.put_strike, self.put_strike.is_finite())?;
+        check_time_before(
+            "choice_time",
+            self.choice_time,
+            self.option_params.time_to_expiration,
+        )?;
+        check_time_before("choice_time", self.choice_time, self.put_expiration)
+    }
+
+    /// The call and the put at the choice time for the given asset price.
+    fn options_at_choice(&self, asset_price: f64) -> (DerivativeParameter, DerivativeParameter) {
+        let call = DerivativeParameter {
+            asset_price,
+            time_to_expiration: self.option_params.time_to_expiration - self.choice_time,
+            ..self.option_params
+        };
+        let put = DerivativeParameter {
+            strike: self.put_strike,
+            time_to_expiration: self.put_expiration - self.choice_time,
+            ..call
+        };
+        (call, put)
+    }
+}
+
+/// Complex chooser options.
+/// See Rubinstein, Options for the Undecided (1991), and
+/// Haug, The Complete Guide to Option Pricing Formulas, chapter 4.11.2.
+pub struct ComplexChooser;
+
+impl ComplexChooser {
+    /// The asset price at the choice time at which the call and the put are worth the same, an
+    /// error if there is none within 1e-30 and 1e30 times the strike.
+    pub fn critical_price(cp: &ComplexChooserParameter) -> Result<f64, PricingError> {
+        let seed = cp.option_params.strike;
+        let difference = |asset_price| {
+            let (call, put) = cp.options_at_choice(asset_price);
+            (
+                BlackScholesMerton::call(&call) - BlackScholesMerton::put(&put),
+                BlackScholesMerton::delta(&call, &ExerciseType::Call)
+                    - BlackScholesMerton::delta(&put, &ExerciseType::Put),
+            )
+        };
+        // the difference increases from minus the discounted put strike to infinity
+        critical_price(seed, difference).ok_or(PricingError::NoConvergence {
+            iterations: MAX_CRITICAL_PRICE_ITERATIONS,
+        })
+    }
+
+    /// NaN if the critical price is not found.
+    pub fn price(cp: &ComplexChooserParameter) -> f64 {
+        let dp = &cp.option_params;
+        let (s, xc, xp, t, tc, tp, r, b, sigma) = (
+            dp.asset_price,
+            dp.strike,
+            cp.put_strike,
+            cp.choice_time,
+            dp.time_to_expiration,
+            cp.put_expiration,
+            dp.rfr,
+            dp.cost_of_carry(),
+            dp.vola,
+        );
+        if sigma * t.sqrt() == 0.0 {
+            return deterministic_value(dp, t, |asset_price| {
+                let (call, put) = cp.options_at_choice(asset_price);
+                BlackScholesMerton::call(&call).max(BlackScholesMerton::put(&put))
+            });
+        }
+        let critical = match Self::critical_price(cp) {
+            Ok(critical) => critical,
+            Err(_) => return f64::NAN,
+        };
+        let d1 = ((s / critical).ln() + (b + sigma.powi(2) / 2.0) * t) / (sigma * t.sqrt());
+        let d2 = d1 - sigma * t.sqrt();
+        let y1 = ((s / xc).ln() + (b + sigma.powi(2) / 2.0) * tc) / (sigma * tc.sqrt());
+        let y2 = ((s / xp).ln() + (b + sigma.powi(2) / 2.0) * tp) / (sigma * tp.sqrt());
+        let rho1 = (t / tc).sqrt();
+        let rho2 = (t / tp).sqrt();
+        s * ((b - r) * tc).exp() * bivariate_cdf(d1, y1, rho1)
+            - xc * (-r * tc).exp() * bivariate_cdf(d2, y1 - sigma * tc.sqrt(), rho1)
+            - s * ((b - r) * tp).exp() * bivariate_cdf(-d1, -y2, rho2)
+            + xp * (-r * tp).exp() * bivariate_cdf(-d2, -y2 + sigma * tp.sqrt(), rho2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::normal::pdf;
+    use crate::common::quadrature::gauss_legendre;
+    use assert_approx_eq::assert_approx_eq;
+
+    /// The discounted expectation of the payoff at time t1 for the asset price at t1, by
+    /// integrating over the standard normal variable of the log price piecewise between the kinks.
+    fn expectation(
+        dp: &DerivativeParameter,
+        t1: f64,
+        kinks: &[f64],
+        payoff: impl Fn(f64) -> f64,
+    ) -> f64 {
+        let (s, r, b, sigma) = (dp.asset_price, dp.rfr, dp.cost_of_carry(), dp.vola);
+        let drift = (b - sigma.powi(2) / 2.0) * t1;
+        let z = |asset_price: f64| ((asset_price / s).ln() - drift) / (sigma * t1.sqrt());
+        let mut bounds = vec![-10.0, 10.0];
+        bounds.extend(kinks.iter().map(|kink| z(*kink)));
+        bounds.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let rule = gauss_legendre(64);
+        let integral: f64 = bounds
+            .windows(2)
+            .map(|bound| {
+                let (lower, upper) = (bound[0], bound[1]);
+                rule.nodes
+                    .iter()
+                    .zip(&rule.weights)
+                    .map(|(node, weight)| {
+                        let x = lower + (upper - lower) * (node + 1.0) / 2.0;
+                        let asset_price = s * (drift + sigma * t1.sqrt() * x).exp();
+                        (upper - lower) / 2.0 * weight * pdf(x) * payoff(asset_price)
+                    })
+                    .sum::<f64>()
+            })
+            .sum();
+        (-r * t1).exp() * integral
+    }
+
+    #[test]
+    fn compound_options() {
+        // Haug, The Complete Guide to Option Pricing Formulas, example 4.12, put on call, whose
+        // bivariate normal distribution is less accurate
+        let dp = DerivativeParameter::new(500.0, 520.0, 0.5, 0.08, 0.35).with_dividend_yield(0.03);
+        let cp = CompoundParameter::new(dp, ExerciseType::Call, 50.0, 0.25);
+        assert_approx_eq!(Geske::put(&cp), 21.1965, 2e-4);
+
+        for underlying_type in [ExerciseType::Call, ExerciseType::Put] {
+            let cp = CompoundParameter {
+                underlying_type,
+                ..cp
+            };
+            let critical = Geske::critical_price(&cp).unwrap();
+            let underlying = |asset_price| {
+                BlackScholesMerton::price(
+                    &cp.underlying_at_expiration(asset_price),
+                    &underlying_type,
+                )
+            };
+            assert_approx_eq!(underlying(critical), cp.compound_strike, 1e-8);
+            for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+                let phi = exercise_type.sign();
+                let price = expectation(&dp, 0.25, &[critical], |asset_price| {
+                    (phi * (underlying(asset_price) - cp.compound_strike)).max(0.0)
+                });
+                assert_approx_eq!(Geske::price(&cp, &exercise_type), price, 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn compound_put_call_parity() {
+        let dp = DerivativeParameter::new(95.0, 100.0, 1.5, 0.04, 0.25).with_dividend_yield(0.01);
+        for underlying_type in [ExerciseType::Call, ExerciseType::Put] {
+            let cp = CompoundParameter::new(dp, underlying_type, 6.0, 0.5);
+            assert_approx_eq!(
+                Geske::call(&cp) - Geske::put(&cp),
+                BlackScholesMerton::price(&dp, &underlying_type) - 6.0 * (-0.04_f64 * 0.5).exp(),
+                1e-10
+            );
+        }
+    }
+
+    #[test]
+    fn worthless_underlying_put() {
+        // the put can never be worth more than the discounted strike
+        let dp = DerivativeParameter::new(100.0, 100.0, 1.0, 0.05, 0.2);
+        let cp = CompoundParameter::new(dp, ExerciseType::Put, 100.0, 0.5);
+        assert!(Geske::critical_price(&cp).is_none());
+        assert_eq!(Geske::call(&cp), 0.0);
+        assert_approx_eq!(
+            Geske::put(&cp),
+            100.0 * (-0.025_f64).exp() - BlackScholesMerton::put(&dp),
+            1e-12
+        );
+    }
+
+    #[test]
+    fn simple_chooser() {
+        // Haug, The Complete Guide to Option Pricing Formulas, example 4.11.1
+        let dp = DerivativeParameter::new(50.0, 50.0, 0.5, 0.08, 0.25);
+        let cp = ChooserParameter::new(dp, 0.25);
+        assert_approx_eq!(SimpleChooser::price(&cp), 6.1071, 1e-4);
+
+        // a call and a put with the discounted strike expiring at the choice time
+        let dp = dp.with_dividend_yield(0.03);
+        let cp = ChooserParameter::new(dp, 0.25);
+        let put = DerivativeParameter {
+            strike: dp.strike * (-0.05_f64 * 0.25).exp(),
+            time_to_expiration: 0.25,
+            ..dp
+        };
+        assert_approx_eq!(
+            SimpleChooser::price(&cp),
+            BlackScholesMerton::call(&dp)
+                + (-0.03_f64 * 0.25).exp() * BlackScholesMerton::put(&put),
+            1e-12
+        );
+    }
+
+    #[test]
+    fn complex_chooser() {
+        // Haug, The Complete Guide to Option Pricing Formulas, example 4.11.2
+        let dp = DerivativeParameter::new(50.0, 55.0, 0.5, 0.1, 0.35).with_dividend_yield(0.05);
+        let cp = ComplexChooserParameter::new(dp, 48.0, 0.5833, 0.25);
+        assert_approx_eq!(ComplexChooser::price(&cp), 6.0508, 1e-4);
+
+        let critical = ComplexChooser::critical_price(&cp).unwrap();
+        let price = expectation(&dp, 0.25, &[critical], |asset_price| {
+            let (call, put) = cp.options_at_choice(asset_price);
+            BlackScholesMerton::call(&call).max(BlackScholesMerton::put(&put))
+        });
+        assert_approx_eq!(ComplexChooser::price(&cp), price, 1e-9);
+
+        // with equal strikes and expirations it is a simple chooser
+        let cp = ComplexChooserParameter::new(dp, 55.0, 0.5, 0.25);
+        assert_approx_eq!(
+            ComplexChooser::price(&cp),
+            SimpleChooser::price(&ChooserParameter::new(dp, 0.25)),
+            1e-10
+        );
+
+        // the put is worth more than the call at any asset price up to 1e30 times the strike
+        let cp = ComplexChooserParameter::new(dp, 1e40, 0.5833, 0.25);
+        assert_eq!(
+            ComplexChooser::critical_price(&cp),
+            Err(PricingError::NoConvergence {
+                iterations: MAX_CRITICAL_PRICE_ITERATIONS
+            })
+        );
+        assert!(ComplexChooser::price(&cp).is_nan());
+    }
+
+    #[test]
+    fn immediate_choice_and_zero_volatility() {
+        let dp = DerivativeParameter::new(100.0, 105.0, 1.0, 0.05, 0.25).with_dividend_yield(0.02);
+        let (call, put) = (BlackScholesMerton::call(&dp), BlackScholesMerton::put(&dp));
+
+        // the compound options are exercised immediately if they are in the money
+        let cp = CompoundParameter::new(dp, ExerciseType::Call, 8.0, 0.0);
+        assert_approx_eq!(Geske::call(&cp), (call - 8.0).max(0.0), 1e-12);
+        assert_approx_eq!(Geske::put(&cp), (8.0 - call).max(0.0), 1e-12);
+        let cp = CompoundParameter::new(dp, ExerciseType::Put, 8.0, 0.0);
+        assert_approx_eq!(Geske::call(&cp), (put - 8.0).max(0.0), 1e-12);
+        assert_approx_eq!(Geske::put(&cp), (8.0 - put).max(0.0), 1e-12);
+
+        // the choosers pick the more valuable option immediately
+        let cp = ChooserParameter::new(dp, 0.0);
+        assert_approx_eq!(SimpleChooser::price(&cp), call.max(put), 1e-12);
+        let cp = ComplexChooserParameter::new(dp, 95.0, 1.2, 0.0);
+        let (call_at_choice, put_at_choice) = cp.options_at_choice(dp.asset_price);
+        assert_approx_eq!(
+            ComplexChooser::price(&cp),
+            BlackScholesMerton::call(&call_at_choice).max(BlackScholesMerton::put(&put_at_choice)),
+            1e-12
+        );
+
+        // without volatility the asset is at the forward at the first expiration
+        let dp = DerivativeParameter { vola: 0.0, ..dp };
+        let forward = DerivativeParameter {
+            asset_price: 100.0 * (0.03_f64 * 0.5).exp(),
+            time_to_expiration: 0.5,
+            ..dp
+        };
+        let discount_factor = (-0.05_f64 * 0.5).exp();
+        let cp = CompoundParameter::new(dp, ExerciseType::Put, 1.0, 0.5);
+        assert_approx_eq!(
+            Geske::put(&cp),
+            discount_factor * (1.0 - BlackScholesMerton::put(&forward)).max(0.0),
+            1e-12
+        );
+        let cp = ChooserParameter::new(dp, 0.5);
+        let price = SimpleChooser::price(&cp);
+        assert!(price.is_finite());
+        assert_approx_eq!(
+            price,
+            discount_factor
+                * BlackScholesMerton::call(&forward).max(BlackScholesMerton::put(&forward)),
+            1e-12
+        );
+        assert!(
+            ComplexChooser::price(&ComplexChooserParameter::new(dp, 95.0, 1.2, 0.5)).is_finite()
+        );
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        let dp = DerivativeParameter::new(100.0, 100.0, 1.0, 0.05, 0.25);
+        assert!(CompoundParameter::try_new(dp, ExerciseType::Call, 5.0, 0.5).is_ok());
+        assert_eq!(
+            CompoundParameter::try_new(dp, ExerciseType::Call, 5.0, 1.0).unwrap_err(),
+            PricingError::InvalidParameter {
+                name: "compound_expiration",
+                value: 1.0
+            }
+        );
+        assert!(CompoundParameter::try_new(dp, ExerciseType::Put, 5.0, -0.1).is_err());
+        assert!(CompoundParameter::try_new(dp, ExerciseType::Put, f64::NAN, 0.5).is_err());
+
+        assert!(ChooserParameter::try_new(dp, 0.0).is_ok());
+        assert!(ChooserParameter::try_new(dp, 1.5).is_err());
+
+        assert!(ComplexChooserParameter::try_new(dp, 95.0, 1.2, 0.5).is_ok());
+        assert_eq!(
+            ComplexChooserParameter::try_new(dp, 95.0, 0.4, 0.5).unwrap_err(),
+            PricingError::InvalidParameter {
+                name: "choice_time",
+                value: 0.5
+            }
+        );
+        assert!(ComplexChooserParameter::try_new(dp, 95.0, 1.2, 1.0).is_err());
+    }
+}
@@ -0,0 +1,565 @@
+use std::ops::{Mul, Sub};
+
+use crate::analytic::black_scholes::{OptionGreeks, OptionPrice};
+use crate::common::models::{DerivativeParameter, ExerciseType};
+use crate::common::numeric::Numeric;
+
+/// Partial derivatives of an undiscounted payoff expectation $U(F, w)$ with respect to the forward
+/// $F$ of the (powered) asset and the total volatility $w$ until expiration.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ForwardPartials<T: Numeric = f64> {
+    u: T,
+    u_f: T,
+    u_ff: T,
+    u_w: T,
+    u_fw: T,
+    u_ww: T,
+}
+
+impl<T: Numeric> Sub for ForwardPartials<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self {
+            u: self.u - rhs.u,
+            u_f: self.u_f - rhs.u_f,
+            u_ff: self.u_ff - rhs.u_ff,
+            u_w: self.u_w - rhs.u_w,
+            u_fw: self.u_fw - rhs.u_fw,
+            u_ww: self.u_ww - rhs.u_ww,
+        }
+    }
+}
+
+impl<T: Numeric> Mul<T> for ForwardPartials<T> {
+    type Output = Self;
+
+    fn mul(self, factor: T) -> Self {
+        Self {
+            u: self.u * factor,
+            u_f: self.u_f * factor,
+            u_ff: self.u_ff * factor,
+            u_w: self.u_w * factor,
+            u_fw: self.u_fw * factor,
+            u_ww: self.u_ww * factor,
+        }
+    }
+}
+
+/// The lognormal distribution of $S_T^i$ for an exponent $i$, with the forward
+/// $F = S^i e^{\kappa T}$, $\kappa = i b + i (i - 1) \sigma^2 / 2$, and the total volatility
+/// $w = |i| \sigma \sqrt{T}$, which is positive for negative exponents as well. Payoffs are valued as $e^{-rT} U(F, w)$ and all Greeks follow
+/// from the partials of $U$ by the chain rule.
+pub(crate) struct PoweredForward<T: Numeric = f64> {
+    s: T,
+    sigma: T,
+    t: T,
+    r: T,
+    exponent: T,
+    kappa: T,
+    forward: T,
+    w: T,
+}
+
+impl<T: Numeric> PoweredForward<T> {
+    pub(crate) fn new(dp: &DerivativeParameter<T>, exponent: T) -> Self {
+        let kappa = exponent * dp.cost_of_carry()
+            + exponent * (exponent - T::one()) * dp.vola.powi(2) / T::from_f64(2.0);
+        Self {
+            s: dp.asset_price,
+            sigma: dp.vola,
+            t: dp.time_to_expiration,
+            r: dp.rfr,
+            exponent,
+            kappa,
+            forward: dp.asset_price.powf(exponent) * (kappa * dp.time_to_expiration).exp(),
+            w: exponent.abs() * dp.vola * dp.time_to_expiration.sqrt(),
+        }
+    }
+
+    /// At expiry or without volatility the payoff is triggered iff the forward is in the money,
+    /// which gives infinite d1 and d2 as in the generalized Black-Scholes formula.
+    fn d1_d2(&self, strike: T) -> (T, T) {
+        if self.is_deterministic() {
+            let d = T::from_f64(if self.forward > strike {
+                f64::INFINITY
+            } else {
+                f64::NEG_INFINITY
+            });
+            return (d, d);
+        }
+        let d1 = ((self.forward / strike).ln() + self.w.powi(2) / T::from_f64(2.0)) / self.w;
+        (d1, d1 - self.w)
+    }
+
+    /// Without total volatility all partials with respect to it vanish, and so do the derivatives
+    /// of the indicator off the strike; at the strike its Dirac delta is taken as zero as well.
+    fn is_deterministic(&self) -> bool {
+        self.w == T::zero()
+    }
+
+    fn phi(exercise_type: &ExerciseType) -> T {
+        T::from_f64(exercise_type.sign())
+    }
+
+    /// One unit of cash paid if $S_T^i$ ends above (call) or below (put) the strike.
+    pub(crate) fn cash_partials(
+        &self,
+        strike: T,
+        exercise_type: &ExerciseType,
+    ) -> ForwardPartials<T> {
+        let (d1, d2) = self.d1_d2(strike);
+        let (f, w, phi) = (self.forward, self.w, Self::phi(exercise_type));
+        if self.is_deterministic() {
+            return ForwardPartials {
+                u: (phi * d2).norm_cdf(),
+                u_f: T::zero(),
+                u_ff: T::zero(),
+                u_w: T::zero(),
+                u_fw: T::zero(),
+                u_ww: T::zero(),
+            };
+        }
+        let n2 = d2.norm_pdf();
+        ForwardPartials {
+            u: (phi * d2).norm_cdf(),
+            u_f: phi * n2 / (f * w),
+            u_ff: -phi * n2 * d1 / (f * w).powi(2),
+            u_w: -phi * n2 * d1 / w,
+            u_fw: phi * n2 * (d1 * d2 - T::one()) / (f * w.powi(2)),
+            u_ww: -phi * n2 * (d1.powi(2) * d2 - d1 - d2) / w.powi(2),
+        }
+    }
+
+    /// The (powered) asset $S_T^i$ paid if it ends above (call) or below (put) the strike.
+    pub(crate) fn asset_partials(
+        &self,
+        strike: T,
+        exercise_type: &ExerciseType,
+    ) -> ForwardPartials<T> {
+        let (d1, d2) = self.d1_d2(strike);
+        let (f, w, phi) = (self.forward, self.w, Self::phi(exercise_type));
+        if self.is_deterministic() {
+            return ForwardPartials {
+                u: f * (phi * d1).norm_cdf(),
+                u_f: (phi * d1).norm_cdf(),
+                u_ff: T::zero(),
+                u_w: T::zero(),
+                u_fw: T::zero(),
+                u_ww: T::zero(),
+            };
+        }
+        let n1 = d1.norm_pdf();
+        ForwardPartials {
+            u: f * (phi * d1).norm_cdf(),
+            u_f: (phi * d1).norm_cdf() + phi * n1 / w,
+            u_ff: -phi * n1 * d2 / (f * w.powi(2)),
+            u_w: -phi * f * n1 * d2 / w,
+            u_fw: phi * n1 * (d2.powi(2) - T::one()) / w.powi(2),
+            u_ww: -phi * f * n1 * (d1 * d2.powi(2) - d1 - d2) / w.powi(2),
+        }
+    }
+
+    /// The payoff $(\phi (S_T^i - K))^+$.
+    pub(crate) fn vanilla_partials(
+        &self,
+        strike: T,
+        exercise_type: &ExerciseType,
+    ) -> ForwardPartials<T> {
+        (self.asset_partials(strike, exercise_type)
+            - self.cash_partials(strike, exercise_type) * strike)
+            * Self::phi(exercise_type)
+    }
+
+    fn discount_factor(&self) -> T {
+        (-self.r * self.t).exp()
+    }
+
+    pub(crate) fn price(&self, p: &ForwardPartials<T>) -> T {
+        self.discount_factor() * p.u
+    }
+}
+
+impl PoweredForward {
+    /// dw/dT, taken as zero in the limits where all partials with respect to $w$ vanish.
+    fn w_t(&self) -> f64 {
+        if self.is_deterministic() {
+            0.0
+        } else {
+            self.w / (2.0 * self.t)
+        }
+    }
+
+    /// dF/dsigma
+    fn forward_sigma(&self) -> f64 {
+        self.forward * self.exponent * (self.exponent - 1.0) * self.sigma * self.t
+    }
+
+    /// dw/dsigma
+    fn w_sigma(&self) -> f64 {
+        self.exponent.abs() * self.t.sqrt()
+    }
+
+    pub(crate) fn delta(&self, p: &ForwardPartials) -> f64 {
+        self.discount_factor() * p.u_f * self.exponent * self.forward / self.s
+    }
+
+    pub(crate) fn gamma(&self, p: &ForwardPartials) -> f64 {
+        let i = self.exponent;
+        self.discount_factor()
+            * (p.u_ff * (i * self.forward / self.s).powi(2)
+                + p.u_f * i * (i - 1.0) * self.forward / self.s.powi(2))
+    }
+
+    pub(crate) fn vega(&self, p: &ForwardPartials) -> f64 {
+        self.discount_factor() * (p.u_f * self.forward_sigma() + p.u_w * self.w_sigma())
+    }
+
+    pub(crate) fn theta(&self, p: &ForwardPartials) -> f64 {
+        self.r * self.price(p)
+            - self.discount_factor() * (p.u_f * self.kappa * self.forward + p.u_w * self.w_t())
+    }
+
+    /// Sensitivity to the interest rate if the cost of carry moves along with it (b = r - q).
+    pub(crate) fn rho(&self, p: &ForwardPartials) -> f64 {
+        -self.t * self.price(p)
+            + self.discount_factor() * p.u_f * self.exponent * self.t * self.forward
+    }
+
+    pub(crate) fn vanna(&self, p: &ForwardPartials) -> f64 {
+        let u_f_sigma = p.u_ff * self.forward_sigma() + p.u_fw * self.w_sigma();
+        self.discount_factor() * self.exponent / self.s
+            * (u_f_sigma * self.forward + p.u_f * self.forward_sigma())
+    }
+
+    pub(crate) fn volga(&self, p: &ForwardPartials) -> f64 {
+        let i = self.exponent;
+        let forward_sigma_sigma = i
+            * (i - 1.0)
+            * self.t
+            * self.forward
+            * (1.0 + i * (i - 1.0) * self.sigma.powi(2) * self.t);
+        let u_f_sigma = p.u_ff * self.forward_sigma() + p.u_fw * self.w_sigma();
+        let u_w_sigma = p.u_fw * self.forward_sigma() + p.u_ww * self.w_sigma();
+        self.discount_factor()
+            * (u_f_sigma * self.forward_sigma()
+                + p.u_f * forward_sigma_sigma
+                + u_w_sigma * self.w_sigma())
+    }
+
+    pub(crate) fn charm(&self, p: &ForwardPartials) -> f64 {
+        let u_f_t = p.u_ff * self.kappa * self.forward + p.u_fw * self.w_t();
+        self.r * self.delta(p)
+            - self.discount_factor() * self.exponent / self.s
+                * (u_f_t * self.forward + p.u_f * self.kappa * self.forward)
+    }
+}
+
+/// Implements [OptionPrice] and [OptionGreeks] for a pricer from its [ForwardPartials].
+macro_rules! impl_forward_partials_greeks {
+    ($pricer:ty, $params:ident) => {
+        impl<T: Numeric> OptionPrice<T> for $pricer {
+            type Params = $params<T>;
+
+            fn call(params: &$params<T>) -> T {
+                Self::price(params, &ExerciseType::Call)
+            }
+
+            fn put(params: &$params<T>) -> T {
+                Self::price(params, &ExerciseType::Put)
+            }
+
+            fn price(params: &$params<T>, exercise_type: &ExerciseType) -> T {
+                let (forward, partials) = Self::partials(params, exercise_type);
+                forward.price(&partials)
+            }
+        }
+
+        impl OptionGreeks for $pricer {
+            fn delta(params: &$params, exercise_type: &ExerciseType) -> f64 {
+                let (forward, partials) = Self::partials(params, exercise_type);
+                forward.delta(&partials)
+            }
+
+            fn gamma(params: &$params, exercise_type: &ExerciseType) -> f64 {
+                let (forward, partials) = Self::partials(params, exercise_type);
+                forward.gamma(&partials)
+            }
+
+            fn vega(params: &$params, exercise_type: &ExerciseType) -> f64 {
+                let (forward, partials) = Self::partials(params, exercise_type);
+                forward.vega(&partials)
+            }
+
+            fn theta(params: &$params, exercise_type: &ExerciseType) -> f64 {
+                let (forward, partials) = Self::partials(params, exercise_type);
+                forward.theta(&partials)
+            }
+
+            fn rho(params: &$params, exercise_type: &ExerciseType) -> f64 {
+                let (forward, partials) = Self::partials(params, exercise_type);
+                forward.rho(&partials)
+            }
+
+            fn vanna(params: &$params, exercise_type: &ExerciseType) -> f64 {
+                let (forward, partials) = Self::partials(params, exercise_type);
+                forward.vanna(&partials)
+            }
+
+            fn volga(params: &$params, exercise_type: &ExerciseType) -> f64 {
+                let (forward, partials) = Self::partials(params, exercise_type);
+                forward.volga(&partials)
+            }
+
+            fn charm(params: &$params, exercise_type: &ExerciseType) -> f64 {
+                let (forward, partials) = Self::partials(params, exercise_type);
+                forward.charm(&partials)
+            }
+        }
+    };
+}
+
+pub(crate) use impl_forward_partials_greeks;
+
+/// Pays one unit of cash at expiration if the asset ends above (call) or below (put) the strike.
+/// Scale linearly for other cash amounts.
+/// https://en.wikipedia.org/wiki/Binary_option
+pub struct CashOrNothing;
+
+impl CashOrNothing {
+    fn partials<T: Numeric>(
+        dp: &DerivativeParameter<T>,
+        exercise_type: &ExerciseType,
+    ) -> (PoweredForward<T>, ForwardPartials<T>) {
+        let forward = PoweredForward::new(dp, T::one());
+        let partials = forward.cash_partials(dp.strike, exercise_type);
+        (forward, partials)
+    }
+}
+
+impl_forward_partials_greeks!(CashOrNothing, DerivativeParameter);
+
+/// Pays the asset at expiration if it ends above (call) or below (put) the strike.
+/// https://en.wikipedia.org/wiki/Binary_option
+pub struct AssetOrNothing;
+
+impl AssetOrNothing {
+    fn partials<T: Numeric>(
+        dp: &DerivativeParameter<T>,
+        exercise_type: &ExerciseType,
+    ) -> (PoweredForward<T>, ForwardPartials<T>) {
+        let forward = PoweredForward::new(dp, T::one());
+        let partials = forward.asset_partials(dp.strike, exercise_type);
+        (forward, partials)
+    }
+}
+
+impl_forward_partials_greeks!(AssetOrNothing, DerivativeParameter);
+
+#[derive(Clone, Copy, Debug)]
+pub struct GapParameter<T: Numeric = f64> {
+    /// the strike of the option parameters triggers the payoff
+    pub option_params: DerivativeParameter<T>,
+    /// the strike paid (call) or received (put) if the payoff is triggered
+    pub payoff_strike: T,
+}
+
+impl<T: Numeric> GapParameter<T> {
+    pub fn new(option_params: DerivativeParameter<T>, payoff_strike: T) -> Self {
+        Self {
+            option_params,
+            payoff_strike,
+        }
+    }
+}
+
+/// Pays $S_T - K_2$ (call) if $S_T > K_1$, or $K_2 - S_T$ (put) if $S_T < K_1$ at expiration,
+/// for the trigger strike $K_1$ and the payoff strike $K_2$. The payoff can be negative.
+/// See Haug, The Complete Guide to Option Pricing Formulas, chapter 4.19.1.
+pub struct Gap;
+
+impl Gap {
+    fn partials<T: Numeric>(
+        gp: &GapParameter<T>,
+        exercise_type: &ExerciseType,
+    ) -> (PoweredForward<T>, ForwardPartials<T>) {
+        let dp = &gp.option_params;
+        let forward = PoweredForward::new(dp, T::one());
+        let phi = PoweredForward::phi(exercise_type);
+        let partials = (forward.asset_partials(dp.strike, exercise_type)
+            - forward.cash_partials(dp.strike, exercise_type) * gp.payoff_strike)
+            * phi;
+        (forward, partials)
+    }
+}
+
+impl_forward_partials_greeks!(Gap, GapParameter);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::black_scholes::tests::assert_dual_derivatives_match_finite_differences;
+    use crate::analytic::black_scholes::tests::{
+        assert_greeks_match_finite_differences, assert_greeks_match_finite_differences_with,
+    };
+    use crate::analytic::black_scholes::BlackScholesMerton;
+    use crate::common::numeric::Dual;
+    use assert_approx_eq::assert_approx_eq;
+
+    const TOLERANCE: f64 = 1e-4;
+
+    #[test]
+    fn cash_or_nothing() {
+        // Haug, The Complete Guide to Option Pricing Formulas, example 4.19.2
+        let dp = DerivativeParameter::new(100.0, 80.0, 0.75, 0.06, 0.35).with_dividend_yield(0.06);
+        assert_approx_eq!(10.0 * CashOrNothing::put(&dp), 2.6710, TOLERANCE);
+    }
+
+    #[test]
+    fn asset_or_nothing() {
+        // Haug, The Complete Guide to Option Pricing Formulas, example 4.19.3
+        let dp = DerivativeParameter::new(70.0, 65.0, 0.5, 0.07, 0.27).with_dividend_yield(0.05);
+        assert_approx_eq!(AssetOrNothing::put(&dp), 20.2069, TOLERANCE);
+    }
+
+    #[test]
+    fn gap() {
+        // Haug, The Complete Guide to Option Pricing Formulas, example 4.19.1
+        let dp = DerivativeParameter::new(50.0, 50.0, 0.5, 0.09, 0.2);
+        let gp = GapParameter::new(dp, 57.0);
+        assert_approx_eq!(Gap::call(&gp), -0.0053, TOLERANCE);
+    }
+
+    #[test]
+    fn digital_decomposition() {
+        let dp = DerivativeParameter::new(100.0, 95.0, 1.3, 0.04, 0.25).with_dividend_yield(0.02);
+        let call = AssetOrNothing::call(&dp) - dp.strike * CashOrNothing::call(&dp);
+        assert_approx_eq!(call, BlackScholesMerton::call(&dp), 1e-10);
+
+        let put = dp.strike * CashOrNothing::put(&dp) - AssetOrNothing::put(&dp);
+        assert_approx_eq!(put, BlackScholesMerton::put(&dp), 1e-10);
+
+        let gp = GapParameter::new(dp, dp.strike);
+        assert_approx_eq!(Gap::call(&gp), BlackScholesMerton::call(&dp), 1e-10);
+        assert_approx_eq!(
+            Gap::delta(&gp, &ExerciseType::Put),
+            BlackScholesMerton::delta(&dp, &ExerciseType::Put),
+            1e-10
+        );
+    }
+
+    #[test]
+    fn expiry_and_zero_volatility() {
+        let expired = |asset_price| DerivativeParameter::new(asset_price, 100.0, 0.0, 0.05, 0.3);
+        // in the money, at the money and out of the money for the call; at the money only the
+        // put is exercised, as in the Black-Scholes limit
+        for (asset_price, is_call_triggered) in [(110.0, true), (100.0, false), (90.0, false)] {
+            let dp = expired(asset_price);
+            let (call, put) = if is_call_triggered {
+                (1.0, 0.0)
+            } else {
+                (0.0, 1.0)
+            };
+            assert_eq!(CashOrNothing::call(&dp), call);
+            assert_eq!(CashOrNothing::put(&dp), put);
+            assert_eq!(AssetOrNothing::call(&dp), call * asset_price);
+            assert_eq!(AssetOrNothing::put(&dp), put * asset_price);
+            let gp = GapParameter::new(dp, 105.0);
+            assert_eq!(Gap::call(&gp), call * (asset_price - 105.0));
+            assert_eq!(Gap::put(&gp), put * (105.0 - asset_price));
+
+            for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+                assert_eq!(CashOrNothing::delta(&dp, &exercise_type), 0.0);
+                assert_eq!(CashOrNothing::gamma(&dp, &exercise_type), 0.0);
+                assert_eq!(CashOrNothing::vega(&dp, &exercise_type), 0.0);
+                for greek in [
+                    CashOrNothing::theta,
+                    CashOrNothing::rho,
+                    CashOrNothing::vanna,
+                    CashOrNothing::volga,
+                    CashOrNothing::charm,
+                    AssetOrNothing::delta,
+                    AssetOrNothing::gamma,
+                    AssetOrNothing::theta,
+                    AssetOrNothing::charm,
+                ] {
+                    assert!(greek(&dp, &exercise_type).is_finite());
+                }
+                assert!(Gap::theta(&gp, &exercise_type).is_finite());
+            }
+        }
+
+        // without volatility the discounted payoff of the forward
+        let dp = DerivativeParameter::new(100.0, 102.0, 1.0, 0.05, 0.0).with_dividend_yield(0.02);
+        let forward = 100.0 * 0.03_f64.exp();
+        let discount_factor = (-0.05_f64).exp();
+        assert_approx_eq!(CashOrNothing::call(&dp), discount_factor, 1e-12);
+        assert_eq!(CashOrNothing::put(&dp), 0.0);
+        assert_approx_eq!(AssetOrNothing::call(&dp), discount_factor * forward, 1e-12);
+        assert_approx_eq!(
+            AssetOrNothing::delta(&dp, &ExerciseType::Call),
+            (-0.02_f64).exp(),
+            1e-12
+        );
+        assert_eq!(CashOrNothing::delta(&dp, &ExerciseType::Call), 0.0);
+        assert_eq!(CashOrNothing::gamma(&dp, &ExerciseType::Call), 0.0);
+        assert_approx_eq!(
+            CashOrNothing::theta(&dp, &ExerciseType::Call),
+            0.05 * discount_factor,
+            1e-12
+        );
+    }
+
+    #[test]
+    fn greeks_finite_differences() {
+        let dp = DerivativeParameter::new(100.0, 105.0, 0.8, 0.05, 0.3).with_dividend_yield(0.02);
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            assert_greeks_match_finite_differences::<CashOrNothing>(&dp, &exercise_type);
+            assert_greeks_match_finite_differences::<AssetOrNothing>(&dp, &exercise_type);
+
+            assert_greeks_match_finite_differences_with::<Gap>(&dp, &exercise_type, |dp| {
+                GapParameter::new(dp, 110.0)
+            });
+        }
+    }
+
+    #[test]
+    fn automatic_differentiation() {
+        let dp = DerivativeParameter::new(100.0, 105.0, 0.75, 0.05, 0.3).with_dividend_yield(0.02);
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            let et = &exercise_type;
+            assert_dual_derivatives_match_finite_differences::<CashOrNothing>(
+                &dp,
+                et,
+                |dp| dp,
+                |dp| dp,
+            );
+            assert_dual_derivatives_match_finite_differences::<AssetOrNothing>(
+                &dp,
+                et,
+                |dp| dp,
+                |dp| dp,
+            );
+            assert_dual_derivatives_match_finite_differences::<Gap>(
+                &dp,
+                et,
+                |dp| GapParameter::new(dp, 95.0),
+                |dp| GapParameter::new(dp, Dual::constant(95.0)),
+            );
+
+            // the second order agrees with the closed form
+            let gamma = CashOrNothing::price(
+                &DerivativeParameter {
+                    asset_price: Dual::variable(Dual::variable(dp.asset_price)),
+                    ..dp.map(|x| Dual::constant(Dual::constant(x)))
+                },
+                et,
+            );
+            assert_approx_eq!(
+                gamma.derivative.derivative,
+                CashOrNothing::gamma(&dp, et),
+                1e-12
+            );
+        }
+    }
+}
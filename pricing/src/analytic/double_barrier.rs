@@ -0,0 +1,432 @@
+use crate::analytic::black_scholes::{BlackScholesMerton, OptionPrice};
+use crate::common::models::{DerivativeParameter, ExerciseType};
+use crate::common::numeric::Numeric;
+
+/// Default absolute tolerance on the terms at which the series is truncated.
+const SERIES_TOLERANCE: f64 = 1e-12;
+const MAX_SERIES_TERMS: i32 = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DoubleBarrierType {
+    /// the option is activated if either barrier is hit before expiration
+    KnockIn,
+    /// the option is extinguished if either barrier is hit before expiration
+    KnockOut,
+}
+
+/// The barriers are monitored continuously and may be curved, i.e.
+/// $U_t = U e^{\delta_1 t}$ and $L_t = L e^{\delta_2 t}$.
+#[derive(Clone, Copy, Debug)]
+pub struct DoubleBarrierParameter<T: Numeric = f64> {
+    pub option_params: DerivativeParameter<T>,
+    pub barrier_type: DoubleBarrierType,
+    pub lower_barrier: T,
+    pub upper_barrier: T,
+    /// the exponential growth rate $\delta_2$ of the lower barrier
+    pub lower_curvature: T,
+    /// the exponential growth rate $\delta_1$ of the upper barrier
+    pub upper_curvature: T,
+    /// the series is truncated once the terms fall below the tolerance
+    pub tolerance: f64,
+}
+
+impl<T: Numeric> DoubleBarrierParameter<T> {
+    pub fn new(
+        option_params: DerivativeParameter<T>,
+        barrier_type: DoubleBarrierType,
+        lower_barrier: T,
+        upper_barrier: T,
+    ) -> Self {
+        Self {
+            option_params,
+            barrier_type,
+            lower_barrier,
+            upper_barrier,
+            lower_curvature: T::zero(),
+            upper_curvature: T::zero(),
+            tolerance: SERIES_TOLERANCE,
+        }
+    }
+
+    pub fn with_curvature(self, lower_curvature: T, upper_curvature: T) -> Self {
+        Self {
+            lower_curvature,
+            upper_curvature,
+            ..self
+        }
+    }
+
+    pub fn with_tolerance(self, tolerance: f64) -> Self {
+        Self { tolerance, ..self }
+    }
+
+    /// Whether the asset price lies outside of the barriers.
+    pub fn is_breached(&self) -> bool {
+        self.option_params.asset_price <= self.lower_barrier
+            || self.option_params.asset_price >= self.upper_barrier
+    }
+}
+
+/// European double barrier options as series expansion.
+/// See Ikeda and Kunitomo, Pricing Options with Curved Boundaries (1992), and
+/// Haug, The Complete Guide to Option Pricing Formulas, chapter 4.17.3.
+pub struct IkedaKunitomo;
+
+impl IkedaKunitomo {
+    /// The n-th term of the series for the knock-out option.
+    fn knock_out_term<T: Numeric>(
+        dbp: &DoubleBarrierParameter<T>,
+        exercise_type: &ExerciseType,
+        n: i32,
+    ) -> T {
+        let dp = &dbp.option_params;
+        let (s, x, t, r, b, sigma) = (
+            dp.asset_price,
+            dp.strike,
+            dp.time_to_expiration,
+            dp.rfr,
+            dp.cost_of_carry(),
+            dp.vola,
+        );
+        let (l, u) = (dbp.lower_barrier, dbp.upper_barrier);
+        let (delta1, delta2) = (dbp.upper_curvature, dbp.lower_curvature);
+        let n_f = T::from_f64(n as f64);
+        let (one, two) = (T::one(), T::from_f64(2.0));
+
+        let sigma_exp = sigma * t.sqrt();
+        let drift = (b + sigma.powi(2) / two) * t;
+        let d = |numerator_ln: T| (numerator_ln + drift) / sigma_exp;
+
+        let mu1 = two * (b - delta2 - n_f * (delta1 - delta2)) / sigma.powi(2) + one;
+        let mu2 = two * n_f * (delta1 - delta2) / sigma.powi(2);
+        let mu3 = two * (b - delta2 + n_f * (delta1 - delta2)) / sigma.powi(2) + one;
+
+        // ln(U^n / L^n) and ln(L^{n+1} / (U^n S)) for numerically stable powers
+        let ln_ratio = n_f * (u / l).ln();
+        let ln_reflected = (n_f + one) * l.ln() - n_f * u.ln() - s.ln();
+        let ln_l_s = (l / s).ln();
+
+        // the payoff at expiration is bounded by the strike and the barriers, which cap (call)
+        // or floor (put) the in-the-money region
+        let (lower_end, upper_end) = Self::barriers_at_expiration(dbp);
+        let (near, far) = match exercise_type {
+            ExerciseType::Call => (x.max(lower_end), upper_end),
+            ExerciseType::Put => (lower_end, x.min(upper_end)),
+        };
+        let d1 = d((s / near).ln() + two * ln_ratio);
+        let d2 = d((s / far).ln() + two * ln_ratio);
+        let d3 = d(two * (n_f + one) * l.ln() - near.ln() - s.ln() - two * n_f * u.ln());
+        let d4 = d(two * (n_f + one) * l.ln() - far.ln() - s.ln() - two * n_f * u.ln());
+
+        let asset_term = (mu1 * ln_ratio + mu2 * ln_l_s).exp() * (d1.norm_cdf() - d2.norm_cdf())
+            - (mu3 * ln_reflected).exp() * (d3.norm_cdf() - d4.norm_cdf());
+        let strike_term = ((mu1 - two) * ln_ratio + mu2 * ln_l_s).exp()
+            * ((d1 - sigma_exp).norm_cdf() - (d2 - sigma_exp).norm_cdf())
+            - ((mu3 - two) * ln_reflected).exp()
+                * ((d3 - sigma_exp).norm_cdf() - (d4 - sigma_exp).norm_cdf());
+
+        let asset_value = s * ((b - r) * t).exp() * asset_term;
+        let strike_value = x * (-r * t).exp() * strike_term;
+        match exercise_type {
+            ExerciseType::Call => asset_value - strike_value,
+            ExerciseType::Put => strike_value - asset_value,
+        }
+    }
+
+    /// The lower and upper barrier at expiration, $L e^{\delta_2 T}$ and $U e^{\delta_1 T}$.
+    fn barriers_at_expiration<T: Numeric>(dbp: &DoubleBarrierParameter<T>) -> (T, T) {
+        let t = dbp.option_params.time_to_expiration;
+        (
+            dbp.lower_barrier * (dbp.lower_curvature * t).exp(),
+            dbp.upper_barrier * (dbp.upper_curvature * t).exp(),
+        )
+    }
+
+    fn knock_out_price<T: Numeric>(
+        dbp: &DoubleBarrierParameter<T>,
+        exercise_type: &ExerciseType,
+    ) -> T {
+        // the option expires out of the money whenever it survives
+        let (lower_end, upper_end) = Self::barriers_at_expiration(dbp);
+        let strike = dbp.option_params.strike;
+        let is_worthless = match exercise_type {
+            ExerciseType::Call => strike >= upper_end,
+            ExerciseType::Put => strike <= lower_end,
+        };
+        if is_worthless {
+            return T::zero();
+        }
+
+        let mut price = Self::knock_out_term(dbp, exercise_type, 0);
+        for n in 1..=MAX_SERIES_TERMS {
+            let terms = Self::knock_out_term(dbp, exercise_type, n)
+                + Self::knock_out_term(dbp, exercise_type, -n);
+            price = price + terms;
+            if terms.abs().to_f64() < dbp.tolerance {
+                break;
+            }
+        }
+        price
+    }
+
+    /// Without volatility or at expiry the asset moves deterministically to the forward. Since
+    /// the logarithms of the path and of the barriers are linear in time, the path leaves the
+    /// corridor if and only if the forward lies outside of the barriers at expiration.
+    fn deterministic_knock_out_price<T: Numeric>(
+        dbp: &DoubleBarrierParameter<T>,
+        exercise_type: &ExerciseType,
+    ) -> T {
+        let dp = &dbp.option_params;
+        let forward = dp.asset_price * (dp.cost_of_carry() * dp.time_to_expiration).exp();
+        let (lower_end, upper_end) = Self::barriers_at_expiration(dbp);
+        if forward <= lower_end || forward >= upper_end {
+            T::zero()
+        } else {
+            BlackScholesMerton::price(dp, exercise_type)
+        }
+    }
+
+    fn double_barrier_price<T: Numeric>(
+        dbp: &DoubleBarrierParameter<T>,
+        exercise_type: &ExerciseType,
+    ) -> T {
+        let vanilla_price = BlackScholesMerton::price(&dbp.option_params, exercise_type);
+        let knock_out_price = if dbp.is_breached() {
+            T::zero()
+        } else if dbp.option_params.vola * dbp.option_params.time_to_expiration.sqrt() == T::zero()
+        {
+            Self::deterministic_knock_out_price(dbp, exercise_type)
+        } else {
+            Self::knock_out_price(dbp, exercise_type)
+        };
+        match dbp.barrier_type {
+            DoubleBarrierType::KnockOut => knock_out_price,
+            // in-out parity
+            DoubleBarrierType::KnockIn => vanilla_price - knock_out_price,
+        }
+    }
+}
+
+impl<T: Numeric> OptionPrice<T> for IkedaKunitomo {
+    type Params = DoubleBarrierParameter<T>;
+
+    fn call(dbp: &DoubleBarrierParameter<T>) -> T {
+        Self::double_barrier_price(dbp, &ExerciseType::Call)
+    }
+
+    fn put(dbp: &DoubleBarrierParameter<T>) -> T {
+        Self::double_barrier_price(dbp, &ExerciseType::Put)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::barrier::{BarrierParameter, BarrierType, ReinerRubinstein};
+    use crate::analytic::black_scholes::tests::assert_dual_derivatives_match_finite_differences;
+    use crate::common::numeric::Dual;
+    use assert_approx_eq::assert_approx_eq;
+
+    const TOLERANCE: f64 = 1e-4;
+
+    #[test]
+    fn knock_out_calls() {
+        // Haug, The Complete Guide to Option Pricing Formulas, table 4-22,
+        // with S = X = 100, T = 0.25, r = b = 0.1 and flat barriers
+        let references = [
+            (50.0, 150.0, [4.3515, 6.1644, 7.0373]),
+            (60.0, 140.0, [4.3505, 5.8500, 5.7726]),
+            (70.0, 130.0, [4.3139, 4.8293, 3.7765]),
+            (80.0, 120.0, [3.7516, 2.6387, 1.4903]),
+            // cross-checked with the eigenfunction (Fourier sine series) expansion
+            (90.0, 110.0, [1.2055, 0.3098, 0.0477]),
+        ];
+        for (lower_barrier, upper_barrier, prices) in references {
+            for (vola, price) in [0.15, 0.25, 0.35].iter().zip(prices) {
+                let dp = DerivativeParameter::new(100.0, 100.0, 0.25, 0.1, *vola);
+                let dbp = DoubleBarrierParameter::new(
+                    dp,
+                    DoubleBarrierType::KnockOut,
+                    lower_barrier,
+                    upper_barrier,
+                );
+                assert_approx_eq!(IkedaKunitomo::call(&dbp), price, TOLERANCE);
+            }
+        }
+    }
+
+    #[test]
+    fn far_away_barriers() {
+        let dp = DerivativeParameter::new(100.0, 95.0, 1.0, 0.05, 0.2).with_dividend_yield(0.02);
+        let dbp = DoubleBarrierParameter::new(dp, DoubleBarrierType::KnockOut, 1.0, 10_000.0)
+            .with_curvature(0.05, -0.05);
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            assert_approx_eq!(
+                IkedaKunitomo::price(&dbp, &exercise_type),
+                BlackScholesMerton::price(&dp, &exercise_type),
+                1e-8
+            );
+        }
+
+        let dbp = DoubleBarrierParameter {
+            barrier_type: DoubleBarrierType::KnockIn,
+            ..dbp
+        };
+        assert_approx_eq!(IkedaKunitomo::call(&dbp), 0.0, 1e-8);
+    }
+
+    #[test]
+    fn single_barrier_limits() {
+        let dp = DerivativeParameter::new(100.0, 100.0, 0.5, 0.08, 0.25).with_dividend_yield(0.04);
+
+        // the upper barrier is out of reach
+        let dbp = DoubleBarrierParameter::new(dp, DoubleBarrierType::KnockOut, 90.0, 500.0);
+        let bp = BarrierParameter::new(dp, BarrierType::DownAndOut, 90.0, 0.0);
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            assert_approx_eq!(
+                IkedaKunitomo::price(&dbp, &exercise_type),
+                ReinerRubinstein::price(&bp, &exercise_type),
+                1e-8
+            );
+        }
+
+        // the lower barrier is out of reach
+        let dbp = DoubleBarrierParameter::new(dp, DoubleBarrierType::KnockIn, 20.0, 115.0);
+        let bp = BarrierParameter::new(dp, BarrierType::UpAndIn, 115.0, 0.0);
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            assert_approx_eq!(
+                IkedaKunitomo::price(&dbp, &exercise_type),
+                ReinerRubinstein::price(&bp, &exercise_type),
+                1e-8
+            );
+        }
+    }
+
+    #[test]
+    fn strikes_outside_barriers() {
+        let dp = DerivativeParameter::new(100.0, 100.0, 0.5, 0.05, 0.25);
+        let with_strike = |strike, barrier_type| {
+            let dp = DerivativeParameter { strike, ..dp };
+            DoubleBarrierParameter::new(dp, barrier_type, 90.0, 120.0)
+        };
+        for (exercise_type, strikes) in [
+            (ExerciseType::Call, [60.0, 85.0, 130.0, 150.0]),
+            (ExerciseType::Put, [60.0, 80.0, 125.0, 150.0]),
+        ] {
+            for strike in strikes {
+                let knock_out = with_strike(strike, DoubleBarrierType::KnockOut);
+                let knock_in = with_strike(strike, DoubleBarrierType::KnockIn);
+                let knock_out_price = IkedaKunitomo::price(&knock_out, &exercise_type);
+                let knock_in_price = IkedaKunitomo::price(&knock_in, &exercise_type);
+                assert!(knock_out_price >= 0.0);
+                assert!(knock_in_price >= 0.0);
+                assert_approx_eq!(
+                    knock_out_price + knock_in_price,
+                    BlackScholesMerton::price(&knock_out.option_params, &exercise_type),
+                    1e-10
+                );
+            }
+        }
+
+        // the payoff is capped by the barriers, so any strike outside is equivalent to the
+        // barrier itself plus a fixed amount
+        let below = IkedaKunitomo::call(&with_strike(60.0, DoubleBarrierType::KnockOut));
+        let at_lower = IkedaKunitomo::call(&with_strike(90.0, DoubleBarrierType::KnockOut));
+        let survival =
+            IkedaKunitomo::call(&with_strike(89.0, DoubleBarrierType::KnockOut)) - at_lower;
+        assert_approx_eq!(below, at_lower + 30.0 * survival, 1e-8);
+        assert_eq!(
+            IkedaKunitomo::call(&with_strike(150.0, DoubleBarrierType::KnockOut)),
+            0.0
+        );
+        assert_eq!(
+            IkedaKunitomo::put(&with_strike(60.0, DoubleBarrierType::KnockOut)),
+            0.0
+        );
+    }
+
+    #[test]
+    fn expiry_and_zero_volatility() {
+        // the intrinsic value at expiry inside the corridor, nothing outside
+        let expired = |asset_price, barrier_type| {
+            let dp = DerivativeParameter::new(asset_price, 100.0, 0.0, 0.05, 0.25);
+            DoubleBarrierParameter::new(dp, barrier_type, 90.0, 120.0)
+        };
+        assert_eq!(
+            IkedaKunitomo::call(&expired(110.0, DoubleBarrierType::KnockOut)),
+            10.0
+        );
+        assert_eq!(
+            IkedaKunitomo::put(&expired(95.0, DoubleBarrierType::KnockOut)),
+            5.0
+        );
+        assert_eq!(
+            IkedaKunitomo::call(&expired(110.0, DoubleBarrierType::KnockIn)),
+            0.0
+        );
+        assert_eq!(
+            IkedaKunitomo::call(&expired(125.0, DoubleBarrierType::KnockOut)),
+            0.0
+        );
+        assert_eq!(
+            IkedaKunitomo::call(&expired(125.0, DoubleBarrierType::KnockIn)),
+            25.0
+        );
+
+        // the forward stays inside the corridor
+        let dp = DerivativeParameter::new(100.0, 95.0, 1.0, 0.05, 0.0).with_dividend_yield(0.02);
+        let dbp = DoubleBarrierParameter::new(dp, DoubleBarrierType::KnockOut, 90.0, 120.0);
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            let price: f64 = IkedaKunitomo::price(&dbp, &exercise_type);
+            assert!(price.is_finite());
+            assert_approx_eq!(price, BlackScholesMerton::price(&dp, &exercise_type), 1e-12);
+        }
+
+        // the forward rises above the upper barrier, which curves down
+        let dbp = DoubleBarrierParameter::new(dp, DoubleBarrierType::KnockOut, 90.0, 102.0)
+            .with_curvature(0.0, -0.01);
+        assert_eq!(IkedaKunitomo::call(&dbp), 0.0);
+        let dbp = DoubleBarrierParameter {
+            barrier_type: DoubleBarrierType::KnockIn,
+            ..dbp
+        };
+        assert_approx_eq!(
+            IkedaKunitomo::call(&dbp),
+            BlackScholesMerton::call(&dp),
+            1e-12
+        );
+    }
+
+    #[test]
+    fn truncation_tolerance() {
+        let dp = DerivativeParameter::new(100.0, 100.0, 1.0, 0.03, 0.25);
+        let dbp = DoubleBarrierParameter::new(dp, DoubleBarrierType::KnockOut, 70.0, 140.0)
+            .with_curvature(-0.02, 0.01);
+        let coarse = IkedaKunitomo::put(&dbp.with_tolerance(1e-2));
+        let fine = IkedaKunitomo::put(&dbp);
+        assert!(fine > 0.1);
+        assert_approx_eq!(coarse, fine, 1e-2);
+    }
+
+    #[test]
+    fn automatic_differentiation() {
+        let dp = DerivativeParameter::new(100.0, 100.0, 0.25, 0.1, 0.25);
+        for barrier_type in [DoubleBarrierType::KnockIn, DoubleBarrierType::KnockOut] {
+            for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+                assert_dual_derivatives_match_finite_differences::<IkedaKunitomo>(
+                    &dp,
+                    &exercise_type,
+                    |dp| {
+                        DoubleBarrierParameter::new(dp, barrier_type, 80.0, 120.0)
+                            .with_curvature(0.1, -0.1)
+                    },
+                    |dp| {
+                        let (lower, upper) = (Dual::constant(80.0), Dual::constant(120.0));
+                        DoubleBarrierParameter::new(dp, barrier_type, lower, upper)
+                            .with_curvature(Dual::constant(0.1), Dual::constant(-0.1))
+                    },
+                );
+            }
+        }
+    }
+}
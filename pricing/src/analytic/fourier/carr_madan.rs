@@ -0,0 +1,198 @@
+use std::f64::consts::PI;
+
+use num_complex::Complex64;
+use rustfft::FftPlanner;
+
+use crate::analytic::fourier::{
+    check_strikes, put_call_parity, CharacteristicFunction, FourierPricer,
+};
+use crate::common::models::{DerivativeParameter, ExerciseType};
+use crate::error::PricingError;
+
+/// Computes call prices for an equidistant grid of log strikes with a single fast Fourier
+/// transform of the damped call price, and interpolates linearly between them. Puts are valued
+/// by put-call parity. Strikes off the grid are rejected rather than extrapolated.
+/// See Carr and Madan, Option Valuation Using the Fast Fourier Transform (1999).
+#[derive(Clone, Copy, Debug)]
+pub struct CarrMadan {
+    /// the exponent $\alpha$ of the damping factor $e^{\alpha k}$ of the call prices
+    pub damping: f64,
+    /// the number of points of the transform, a power of two
+    pub nr_points: usize,
+    /// the spacing $\eta$ of the integration grid, the log strikes are spaced by $2\pi / (N \eta)$
+    pub grid_spacing: f64,
+}
+
+impl CarrMadan {
+    pub fn new(damping: f64, nr_points: usize, grid_spacing: f64) -> Self {
+        Self {
+            damping,
+            nr_points,
+            grid_spacing,
+        }
+    }
+
+    /// The call prices on the grid of log strikes centered around the log forward.
+    fn call_grid<M: CharacteristicFunction>(
+        &self,
+        model: &M,
+        dp: &DerivativeParameter,
+    ) -> (Vec<f64>, Vec<f64>) {
+        let (alpha, n, eta) = (self.damping, self.nr_points, self.grid_spacing);
+        let t = dp.time_to_expiration;
+        let log_forward = super::forward(dp).ln();
+        let lambda = 2.0 * PI / (n as f64 * eta);
+        let lowest_log_strike = log_forward - lambda * n as f64 / 2.0;
+
+        let mut buffer: Vec<Complex64> = (0..n)
+            .map(|j| {
+                let v = j as f64 * eta;
+                // the characteristic function of ln(S_T) at v - (alpha + 1) i
+                let u = Complex64::new(v, -(alpha + 1.0));
+                let phi =
+                    model.characteristic_function(u, t) * (Complex64::i() * u * log_forward).exp();
+                let psi = (-dp.rfr * t).exp() * phi
+                    / Complex64::new(alpha.powi(2) + alpha - v.powi(2), (2.0 * alpha + 1.0) * v);
+                // Simpson's rule
+                let weight = match j {
+                    0 => 1.0 / 3.0,
+                    j if j % 2 == 1 => 4.0 / 3.0,
+                    _ => 2.0 / 3.0,
+                };
+                (Complex64::new(0.0, -v * lowest_log_strike)).exp() * psi * eta * weight
+            })
+            .collect();
+        FftPlanner::new().plan_fft_forward(n).process(&mut buffer);
+
+        buffer
+            .iter()
+            .enumerate()
+            .map(|(idx, value)| {
+                let log_strike = lowest_log_strike + idx as f64 * lambda;
+                (log_strike, (-alpha * log_strike).exp() / PI * value.re)
+            })
+            .unzip()
+    }
+}
+
+impl Default for CarrMadan {
+    fn default() -> Self {
+        Self::new(1.5, 8192, 0.25)
+    }
+}
+
+impl FourierPricer for CarrMadan {
+    fn strike_strip<M: CharacteristicFunction>(
+        &self,
+        model: &M,
+        dp: &DerivativeParameter,
+        strikes: &[f64],
+        exercise_type: &ExerciseType,
+    ) -> Result<Vec<f64>, PricingError> {
+        check_strikes(strikes)?;
+        let (log_strikes, calls) = self.call_grid(model, dp);
+        let lambda = log_strikes[1] - log_strikes[0];
+        let last = self.nr_points - 1;
+        strikes
+            .iter()
+            .map(|&strike| {
+                let position = (strike.ln() - log_strikes[0]) / lambda;
+                PricingError::check_parameter(
+                    "strike",
+                    strike,
+                    (0.0..=last as f64).contains(&position),
+                )?;
+                let idx = (position.floor() as usize).min(last - 1);
+                let fraction = position - idx as f64;
+                let call = (1.0 - fraction) * calls[idx] + fraction * calls[idx + 1];
+                Ok(match exercise_type {
+                    ExerciseType::Call => call,
+                    ExerciseType::Put => call - put_call_parity(dp, strike),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::fourier::tests::{
+        assert_black_scholes_strip, heston_reference, variance_gamma_reference,
+    };
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn black_scholes() {
+        assert_black_scholes_strip(&CarrMadan::default(), 1e-3);
+    }
+
+    #[test]
+    fn heston() {
+        let (model, dp, price) = heston_reference();
+        assert_approx_eq!(
+            CarrMadan::default()
+                .price(&model, &dp, &ExerciseType::Call)
+                .unwrap(),
+            price,
+            1e-3
+        );
+    }
+
+    #[test]
+    fn variance_gamma() {
+        let (model, dp, price) = variance_gamma_reference();
+        assert_approx_eq!(
+            CarrMadan::default()
+                .price(&model, &dp, &ExerciseType::Call)
+                .unwrap(),
+            price,
+            1e-3
+        );
+    }
+
+    #[test]
+    fn grid_strikes() {
+        // no interpolation is needed on the grid points
+        let (model, dp, _) = heston_reference();
+        let pricer = CarrMadan::default();
+        let (log_strikes, calls) = pricer.call_grid(&model, &dp);
+        let idx = pricer.nr_points / 2 + 10;
+        let dp = DerivativeParameter {
+            strike: log_strikes[idx].exp(),
+            ..dp
+        };
+        assert_approx_eq!(
+            calls[idx],
+            crate::analytic::fourier::Cos::default()
+                .price(&model, &dp, &ExerciseType::Call)
+                .unwrap(),
+            1e-6
+        );
+    }
+
+    #[test]
+    fn strikes_off_the_grid() {
+        let (model, dp, _) = heston_reference();
+        let pricer = CarrMadan::default();
+        let (log_strikes, _) = pricer.call_grid(&model, &dp);
+        let (lowest, highest) = (
+            log_strikes[0].exp(),
+            log_strikes[pricer.nr_points - 1].exp(),
+        );
+        for strike in [lowest, highest] {
+            assert!(pricer
+                .strike_strip(&model, &dp, &[strike], &ExerciseType::Put)
+                .is_ok());
+        }
+        for strike in [lowest / 1.01, highest * 1.01] {
+            assert_eq!(
+                pricer.strike_strip(&model, &dp, &[100.0, strike], &ExerciseType::Put),
+                Err(PricingError::InvalidParameter {
+                    name: "strike",
+                    value: strike
+                })
+            );
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use std::f64::consts::PI;
+
+use num_complex::Complex64;
+
+use crate::analytic::fourier::{
+    check_strikes, put_call_parity, CharacteristicFunction, FourierPricer,
+};
+use crate::common::models::{DerivativeParameter, ExerciseType};
+use crate::error::PricingError;
+
+/// Expands the density of the log return into a Fourier-cosine series on an interval, whose
+/// coefficients follow directly from the characteristic function. Puts are valued by the series
+/// and calls by put-call parity.
+/// See Fang and Oosterlee, A Novel Pricing Method for European Options Based on
+/// Fourier-Cosine Series Expansions (2008).
+#[derive(Clone, Copy, Debug)]
+pub struct Cos {
+    /// the number of terms of the series
+    pub nr_terms: usize,
+    /// the half width of the interval $[c_1 - L \sqrt{c_2 + \sqrt{c_4}}, c_1 + L \sqrt{c_2 + \sqrt{c_4}}]$
+    /// in terms of the cumulants
+    pub truncation: f64,
+}
+
+impl Cos {
+    pub fn new(nr_terms: usize, truncation: f64) -> Self {
+        Self {
+            nr_terms,
+            truncation,
+        }
+    }
+}
+
+impl Default for Cos {
+    fn default() -> Self {
+        Self::new(512, 12.0)
+    }
+}
+
+/// The integrals $\int_c^d e^y \cos(u (y - c)) dy$ and $\int_c^d \cos(u (y - c)) dy$.
+fn payoff_integrals(u: f64, c: f64, d: f64) -> (f64, f64) {
+    let (sin, cos) = (u * (d - c)).sin_cos();
+    let chi = (cos * d.exp() - c.exp() + u * sin * d.exp()) / (1.0 + u.powi(2));
+    let psi = if u == 0.0 { d - c } else { sin / u };
+    (chi, psi)
+}
+
+impl FourierPricer for Cos {
+    fn strike_strip<M: CharacteristicFunction>(
+        &self,
+        model: &M,
+        dp: &DerivativeParameter,
+        strikes: &[f64],
+        exercise_type: &ExerciseType,
+    ) -> Result<Vec<f64>, PricingError> {
+        check_strikes(strikes)?;
+        let t = dp.time_to_expiration;
+        let (c1, c2, c4) = model.cumulants(t);
+        let half_width = self.truncation * (c2 + c4.abs().sqrt()).sqrt();
+        let (a, b) = (c1 - half_width, c1 + half_width);
+
+        // the terms of the series which do not depend on the strike
+        let terms: Vec<(f64, f64)> = (0..self.nr_terms)
+            .map(|k| {
+                let u = k as f64 * PI / (b - a);
+                let weight = if k == 0 { 0.5 } else { 1.0 };
+                let term = model.characteristic_function(Complex64::new(u, 0.0), t)
+                    * Complex64::new(0.0, -u * a).exp();
+                (u, weight * term.re)
+            })
+            .collect();
+
+        let forward = super::forward(dp);
+        let discount_factor = (-dp.rfr * t).exp();
+        Ok(strikes
+            .iter()
+            .map(|&strike| {
+                // the put pays K (1 - e^y) for y = ln(S_T / K) in [x + a, 0]
+                let lower = (forward / strike).ln() + a;
+                let put = if lower >= 0.0 {
+                    0.0
+                } else {
+                    let upper = (lower + b - a).min(0.0);
+                    let series: f64 = terms
+                        .iter()
+                        .map(|(u, term)| {
+                            let (chi, psi) = payoff_integrals(*u, lower, upper);
+                            term * (psi - chi)
+                        })
+                        .sum();
+                    discount_factor * 2.0 / (b - a) * strike * series
+                };
+                match exercise_type {
+                    ExerciseType::Call => put + put_call_parity(dp, strike),
+                    ExerciseType::Put => put,
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::fourier::tests::{
+        assert_black_scholes_strip, heston_reference, variance_gamma_reference,
+    };
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn black_scholes() {
+        assert_black_scholes_strip(&Cos::default(), 1e-10);
+    }
+
+    #[test]
+    fn heston() {
+        let (model, dp, price) = heston_reference();
+        assert_approx_eq!(
+            Cos::default()
+                .price(&model, &dp, &ExerciseType::Call)
+                .unwrap(),
+            price,
+            1e-7
+        );
+    }
+
+    #[test]
+    fn variance_gamma() {
+        // the density of short dated variance gamma processes has a cusp
+        let (model, dp, price) = variance_gamma_reference();
+        assert_approx_eq!(
+            Cos::new(1024, 10.0)
+                .price(&model, &dp, &ExerciseType::Call)
+                .unwrap(),
+            price,
+            1e-6
+        );
+    }
+}
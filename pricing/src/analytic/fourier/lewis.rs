@@ -0,0 +1,126 @@
+use std::f64::consts::PI;
+
+use num_complex::Complex64;
+
+use crate::analytic::fourier::{
+    check_strikes, put_call_parity, CharacteristicFunction, FourierPricer,
+};
+use crate::common::models::{DerivativeParameter, ExerciseType};
+use crate::error::PricingError;
+
+/// Values calls by a single real integral along the line $\mathrm{Im}(u) = -1/2$,
+/// $C = e^{-rT} \left(F - \frac{\sqrt{FK}}{\pi} \int_0^\infty
+/// \mathrm{Re}\left[e^{iuk} \phi_T(u - i/2)\right] \frac{du}{u^2 + 1/4} \right)$ with
+/// $k = \ln(F / K)$, which is integrated by Simpson's rule. Puts are valued by put-call parity.
+/// See Lewis, A Simple Option Formula for General Jump-Diffusion and other Exponential Lévy
+/// Processes (2001).
+#[derive(Clone, Copy, Debug)]
+pub struct Lewis {
+    /// the upper limit at which the integral is truncated, which has to grow for
+    /// characteristic functions decaying slowly like those of short dated Lévy processes
+    pub upper_limit: f64,
+    /// the number of intervals of Simpson's rule, an even number
+    pub nr_intervals: usize,
+}
+
+impl Lewis {
+    pub fn new(upper_limit: f64, nr_intervals: usize) -> Self {
+        Self {
+            upper_limit,
+            nr_intervals,
+        }
+    }
+}
+
+impl Default for Lewis {
+    fn default() -> Self {
+        Self::new(200.0, 4000)
+    }
+}
+
+impl FourierPricer for Lewis {
+    fn strike_strip<M: CharacteristicFunction>(
+        &self,
+        model: &M,
+        dp: &DerivativeParameter,
+        strikes: &[f64],
+        exercise_type: &ExerciseType,
+    ) -> Result<Vec<f64>, PricingError> {
+        check_strikes(strikes)?;
+        let t = dp.time_to_expiration;
+        let h = self.upper_limit / self.nr_intervals as f64;
+
+        // the nodes and weighted integrands which do not depend on the strike
+        let nodes: Vec<(f64, Complex64)> = (0..=self.nr_intervals)
+            .map(|j| {
+                let u = j as f64 * h;
+                let weight = if j == 0 || j == self.nr_intervals {
+                    1.0
+                } else if j % 2 == 1 {
+                    4.0
+                } else {
+                    2.0
+                };
+                let phi = model.characteristic_function(Complex64::new(u, -0.5), t);
+                (u, phi * weight * h / 3.0 / (u.powi(2) + 0.25))
+            })
+            .collect();
+
+        let forward = super::forward(dp);
+        let discount_factor = (-dp.rfr * t).exp();
+        Ok(strikes
+            .iter()
+            .map(|&strike| {
+                let k = (forward / strike).ln();
+                let integral: f64 = nodes
+                    .iter()
+                    .map(|(u, term)| (Complex64::new(0.0, u * k).exp() * term).re)
+                    .sum();
+                let call = discount_factor * (forward - (forward * strike).sqrt() / PI * integral);
+                match exercise_type {
+                    ExerciseType::Call => call,
+                    ExerciseType::Put => call - put_call_parity(dp, strike),
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::fourier::tests::{
+        assert_black_scholes_strip, heston_reference, variance_gamma_reference,
+    };
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn black_scholes() {
+        assert_black_scholes_strip(&Lewis::default(), 1e-10);
+    }
+
+    #[test]
+    fn heston() {
+        let (model, dp, price) = heston_reference();
+        assert_approx_eq!(
+            Lewis::default()
+                .price(&model, &dp, &ExerciseType::Call)
+                .unwrap(),
+            price,
+            1e-7
+        );
+    }
+
+    #[test]
+    fn variance_gamma() {
+        // the characteristic function decays slowly for short expirations
+        let (model, dp, price) = variance_gamma_reference();
+        assert_approx_eq!(
+            Lewis::new(10_000.0, 200_000)
+                .price(&model, &dp, &ExerciseType::Call)
+                .unwrap(),
+            price,
+            1e-6
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use num_complex::Complex64;
+
+use crate::common::models::{DerivativeParameter, ExerciseType};
+use crate::error::PricingError;
+
+pub mod carr_madan;
+pub mod cos;
+pub mod lewis;
+pub mod models;
+
+pub use carr_madan::CarrMadan;
+pub use cos::Cos;
+pub use lewis::Lewis;
+pub use models::{
+    BlackScholesModel, HestonModel, MertonJumpDiffusion, NormalInverseGaussian, VarianceGamma,
+};
+
+/// Risk-neutral models of the log return $X_T = \ln(S_T / F_T)$ of an asset relative to its
+/// forward price, given by their characteristic function. The martingale condition
+/// $E[e^{X_T}] = 1$ has to hold, i.e. $\phi_T(-i) = 1$.
+pub trait CharacteristicFunction {
+    /// $\phi_T(u) = E[e^{i u X_T}]$, also evaluated at complex arguments.
+    fn characteristic_function(&self, u: Complex64, t: f64) -> Complex64;
+
+    /// The cumulants $c_1$, $c_2$ and $c_4$ of $X_T$, by default from central finite differences
+    /// of the cumulant generating function $\ln \phi_T(-iv)$.
+    fn cumulants(&self, t: f64) -> (f64, f64, f64) {
+        let h = 1e-2;
+        let k = |v: f64| {
+            self.characteristic_function(Complex64::new(0.0, -v), t)
+                .ln()
+                .re
+        };
+        let (k_m2, k_m1, k_p1, k_p2) = (k(-2.0 * h), k(-h), k(h), k(2.0 * h));
+        let c1 = (-k_p2 + 8.0 * k_p1 - 8.0 * k_m1 + k_m2) / (12.0 * h);
+        let c2 = (-k_p2 + 16.0 * k_p1 + 16.0 * k_m1 - k_m2) / (12.0 * h.powi(2));
+        let c4 = (k_p2 - 4.0 * k_p1 - 4.0 * k_m1 + k_m2) / h.powi(4);
+        (c1, c2, c4)
+    }
+}
+
+/// European option pricers for models given by their [CharacteristicFunction].
+/// The option parameters provide the market data, their volatility is not used.
+pub trait FourierPricer {
+    /// The prices of options for several strikes in one pass; the strike of the option
+    /// parameters is not used. Strikes which are not positive are rejected.
+    fn strike_strip<M: CharacteristicFunction>(
+        &self,
+        model: &M,
+        dp: &DerivativeParameter,
+        strikes: &[f64],
+        exercise_type: &ExerciseType,
+    ) -> Result<Vec<f64>, PricingError>;
+
+    fn price<M: CharacteristicFunction>(
+        &self,
+        model: &M,
+        dp: &DerivativeParameter,
+        exercise_type: &ExerciseType,
+    ) -> Result<f64, PricingError> {
+        Ok(self.strike_strip(model, dp, &[dp.strike], exercise_type)?[0])
+    }
+}
+
+/// The forward price of the asset at expiration.
+fn forward(dp: &DerivativeParameter) -> f64 {
+    dp.asset_price * (dp.cost_of_carry() * dp.time_to_expiration).exp()
+}
+
+/// Rejects strikes which are not positive, whose log strikes are undefined.
+fn check_strikes(strikes: &[f64]) -> Result<(), PricingError> {
+    strikes
+        .iter()
+        .try_for_each(|&strike| PricingError::check_parameter("strike", strike, strike > 0.0))
+}
+
+/// The difference $C - P = e^{-rT} (F - K)$ of call and put prices.
+fn put_call_parity(dp: &DerivativeParameter, strike: f64) -> f64 {
+    (-dp.rfr * dp.time_to_expiration).exp() * (forward(dp) - strike)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::analytic::black_scholes::{BlackScholesMerton, OptionPrice};
+    use assert_approx_eq::assert_approx_eq;
+
+    /// Fang and Oosterlee, A Novel Pricing Method for European Options Based on
+    /// Fourier-Cosine Series Expansions (2008), section 5.2, whose reference price
+    /// differs from the converged prices by about 2e-8.
+    pub(crate) fn heston_reference() -> (HestonModel, DerivativeParameter, f64) {
+        let model = HestonModel::new(0.0175, 1.5768, 0.0398, 0.5751, -0.5711);
+        let dp = DerivativeParameter::new(100.0, 100.0, 1.0, 0.0, 0.0);
+        (model, dp, 5.785155450)
+    }
+
+    /// Fang and Oosterlee (2008), section 5.4.
+    pub(crate) fn variance_gamma_reference() -> (VarianceGamma, DerivativeParameter, f64) {
+        let model = VarianceGamma::new(0.12, 0.2, -0.14);
+        let dp = DerivativeParameter::new(100.0, 90.0, 0.1, 0.1, 0.0);
+        (model, dp, 10.993703187)
+    }
+
+    pub(crate) fn assert_black_scholes_strip<P: FourierPricer>(pricer: &P, tolerance: f64) {
+        let dp = DerivativeParameter::new(100.0, 100.0, 0.75, 0.05, 0.25).with_dividend_yield(0.02);
+        let model = BlackScholesModel::new(dp.vola);
+        let strikes = [70.0, 85.0, 100.0, 115.0, 130.0];
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            let prices = pricer
+                .strike_strip(&model, &dp, &strikes, &exercise_type)
+                .unwrap();
+            for (strike, price) in strikes.iter().zip(prices) {
+                let dp = DerivativeParameter {
+                    strike: *strike,
+                    ..dp
+                };
+                assert_approx_eq!(
+                    price,
+                    BlackScholesMerton::price(&dp, &exercise_type),
+                    tolerance
+                );
+            }
+        }
+    }
+
+    fn assert_pricers_agree<M: CharacteristicFunction>(model: &M) {
+        let dp = DerivativeParameter::new(100.0, 95.0, 0.5, 0.03, 0.0).with_dividend_yield(0.01);
+        let strikes = [80.0, 95.0, 110.0];
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            let cos = Cos::default()
+                .strike_strip(model, &dp, &strikes, &exercise_type)
+                .unwrap();
+            let carr_madan = CarrMadan::default()
+                .strike_strip(model, &dp, &strikes, &exercise_type)
+                .unwrap();
+            let lewis = Lewis::default()
+                .strike_strip(model, &dp, &strikes, &exercise_type)
+                .unwrap();
+            for i in 0..strikes.len() {
+                assert_approx_eq!(cos[i], carr_madan[i], 1e-3);
+                assert_approx_eq!(cos[i], lewis[i], 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn non_positive_strikes() {
+        let model = BlackScholesModel::new(0.25);
+        let dp = DerivativeParameter::new(100.0, 0.0, 0.75, 0.05, 0.25);
+        let error = Err(PricingError::InvalidParameter {
+            name: "strike",
+            value: 0.0,
+        });
+        assert_eq!(
+            Cos::default().price(&model, &dp, &ExerciseType::Call),
+            error
+        );
+        assert_eq!(
+            CarrMadan::default().price(&model, &dp, &ExerciseType::Call),
+            error
+        );
+        assert_eq!(
+            Lewis::default().price(&model, &dp, &ExerciseType::Put),
+            error
+        );
+    }
+
+    #[test]
+    fn pricers_agree() {
+        assert_pricers_agree(&HestonModel::new(0.04, 2.0, 0.05, 0.4, -0.7));
+        assert_pricers_agree(&MertonJumpDiffusion::new(0.15, 0.5, -0.1, 0.2));
+        assert_pricers_agree(&VarianceGamma::new(0.2, 0.25, -0.1));
+        assert_pricers_agree(&NormalInverseGaussian::new(8.0, -2.0, 0.4));
+    }
+}
@@ -0,0 +1,240 @@
+use num_complex::Complex64;
+
+use crate::analytic::fourier::CharacteristicFunction;
+
+const I: Complex64 = Complex64::new(0.0, 1.0);
+
+/// Geometric Brownian motion with constant volatility.
+#[derive(Clone, Copy, Debug)]
+pub struct BlackScholesModel {
+    pub vola: f64,
+}
+
+impl BlackScholesModel {
+    pub fn new(vola: f64) -> Self {
+        Self { vola }
+    }
+}
+
+impl CharacteristicFunction for BlackScholesModel {
+    fn characteristic_function(&self, u: Complex64, t: f64) -> Complex64 {
+        (-0.5 * self.vola.powi(2) * t * (u * u + I * u)).exp()
+    }
+
+    fn cumulants(&self, t: f64) -> (f64, f64, f64) {
+        let variance = self.vola.powi(2) * t;
+        (-variance / 2.0, variance, 0.0)
+    }
+}
+
+/// Stochastic variance following the square-root process
+/// $dv_t = \kappa (\theta - v_t) dt + \sigma \sqrt{v_t} dW_t$, correlated with the asset.
+/// https://en.wikipedia.org/wiki/Heston_model
+#[derive(Clone, Copy, Debug)]
+pub struct HestonModel {
+    /// the initial variance $v_0$
+    pub initial_variance: f64,
+    /// the speed $\kappa$ of the mean reversion
+    pub mean_reversion: f64,
+    /// the long term variance $\theta$
+    pub long_term_variance: f64,
+    /// the volatility $\sigma$ of the variance
+    pub vol_of_vol: f64,
+    /// the correlation $\rho$ of the asset and its variance
+    pub correlation: f64,
+}
+
+impl HestonModel {
+    pub fn new(
+        initial_variance: f64,
+        mean_reversion: f64,
+        long_term_variance: f64,
+        vol_of_vol: f64,
+        correlation: f64,
+    ) -> Self {
+        Self {
+            initial_variance,
+            mean_reversion,
+            long_term_variance,
+            vol_of_vol,
+            correlation,
+        }
+    }
+}
+
+/// The "little trap" formulation, which avoids the discontinuities of the complex logarithm.
+/// See Albrecher et al., The Little Heston Trap (2007).
+impl CharacteristicFunction for HestonModel {
+    fn characteristic_function(&self, u: Complex64, t: f64) -> Complex64 {
+        let (c, d) = self.characteristic_exponents(u, t);
+        (c + d * self.initial_variance).exp()
+    }
+}
+
+impl HestonModel {
+    /// The functions $C$ and $D$ of the affine characteristic function
+    /// $\phi_T(u) = e^{C(u, T) + D(u, T) v_0}$.
+    pub(crate) fn characteristic_exponents(&self, u: Complex64, t: f64) -> (Complex64, Complex64) {
+        let (kappa, sigma) = (self.mean_reversion, self.vol_of_vol);
+        let xi = kappa - self.correlation * sigma * I * u;
+        let d = (xi * xi + sigma.powi(2) * (I * u + u * u)).sqrt();
+        let g = (xi - d) / (xi + d);
+        let exp_dt = (-d * t).exp();
+
+        let c = kappa * self.long_term_variance / sigma.powi(2)
+            * ((xi - d) * t - 2.0 * ((1.0 - g * exp_dt) / (1.0 - g)).ln());
+        let d_term = (xi - d) / sigma.powi(2) * (1.0 - exp_dt) / (1.0 - g * exp_dt);
+        (c, d_term)
+    }
+}
+
+/// Geometric Brownian motion with lognormal jumps arriving at the rate of a Poisson process.
+/// See Merton, Option Pricing when Underlying Stock Returns are Discontinuous (1976).
+#[derive(Clone, Copy, Debug)]
+pub struct MertonJumpDiffusion {
+    /// the volatility of the diffusion
+    pub vola: f64,
+    /// the expected number of jumps per year
+    pub jump_intensity: f64,
+    /// the mean of the logarithmic jump sizes
+    pub jump_mean: f64,
+    /// the standard deviation of the logarithmic jump sizes
+    pub jump_vola: f64,
+}
+
+impl MertonJumpDiffusion {
+    pub fn new(vola: f64, jump_intensity: f64, jump_mean: f64, jump_vola: f64) -> Self {
+        Self {
+            vola,
+            jump_intensity,
+            jump_mean,
+            jump_vola,
+        }
+    }
+
+    /// The expected relative jump size $E[J - 1] = e^{\mu + \delta^2 / 2} - 1$.
+    pub fn mean_jump_size(&self) -> f64 {
+        (self.jump_mean + self.jump_vola.powi(2) / 2.0).exp() - 1.0
+    }
+}
+
+impl CharacteristicFunction for MertonJumpDiffusion {
+    fn characteristic_function(&self, u: Complex64, t: f64) -> Complex64 {
+        let drift = -self.vola.powi(2) / 2.0 - self.jump_intensity * self.mean_jump_size();
+        let jumps = (I * u * self.jump_mean - 0.5 * self.jump_vola.powi(2) * u * u).exp() - 1.0;
+        (t * (I * u * drift - 0.5 * self.vola.powi(2) * u * u + self.jump_intensity * jumps)).exp()
+    }
+}
+
+/// Brownian motion with drift $\theta$ and volatility $\sigma$ evaluated at a gamma process
+/// with variance rate $\nu$.
+/// See Madan, Carr and Chang, The Variance Gamma Process and Option Pricing (1998).
+#[derive(Clone, Copy, Debug)]
+pub struct VarianceGamma {
+    pub vola: f64,
+    /// the variance rate $\nu$ of the gamma time change, controls the kurtosis
+    pub variance_rate: f64,
+    /// the drift $\theta$ of the Brownian motion, controls the skewness
+    pub drift: f64,
+}
+
+impl VarianceGamma {
+    pub fn new(vola: f64, variance_rate: f64, drift: f64) -> Self {
+        Self {
+            vola,
+            variance_rate,
+            drift,
+        }
+    }
+}
+
+impl CharacteristicFunction for VarianceGamma {
+    fn characteristic_function(&self, u: Complex64, t: f64) -> Complex64 {
+        let (sigma, nu, theta) = (self.vola, self.variance_rate, self.drift);
+        let omega = (1.0 - theta * nu - sigma.powi(2) * nu / 2.0).ln() / nu;
+        let base = 1.0 - I * u * theta * nu + sigma.powi(2) * nu / 2.0 * u * u;
+        (I * u * omega * t).exp() * base.powf(-t / nu)
+    }
+}
+
+/// Normal inverse Gaussian Lévy process with tail heaviness $\alpha$, asymmetry $\beta$ and
+/// scale $\delta$, where $|\beta| + 1 < \alpha$.
+/// See Barndorff-Nielsen, Processes of Normal Inverse Gaussian Type (1998).
+#[derive(Clone, Copy, Debug)]
+pub struct NormalInverseGaussian {
+    pub alpha: f64,
+    pub beta: f64,
+    pub delta: f64,
+}
+
+impl NormalInverseGaussian {
+    pub fn new(alpha: f64, beta: f64, delta: f64) -> Self {
+        Self { alpha, beta, delta }
+    }
+}
+
+impl CharacteristicFunction for NormalInverseGaussian {
+    fn characteristic_function(&self, u: Complex64, t: f64) -> Complex64 {
+        let (alpha, beta, delta) = (self.alpha, self.beta, self.delta);
+        let gamma = (alpha.powi(2) - beta.powi(2)).sqrt();
+        let omega = delta * ((alpha.powi(2) - (beta + 1.0).powi(2)).sqrt() - gamma);
+        let exponent = -delta * ((alpha.powi(2) - (beta + I * u).powi(2)).sqrt() - gamma);
+        (t * (I * u * omega + exponent)).exp()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    fn assert_martingale<M: CharacteristicFunction>(model: &M) {
+        for t in [0.1, 1.0, 5.0] {
+            let phi = model.characteristic_function(-I, t);
+            assert_approx_eq!(phi.re, 1.0, 1e-12);
+            assert_approx_eq!(phi.im, 0.0, 1e-12);
+
+            let phi = model.characteristic_function(Complex64::new(0.0, 0.0), t);
+            assert_approx_eq!(phi.re, 1.0, 1e-12);
+        }
+    }
+
+    #[test]
+    fn martingale_condition() {
+        assert_martingale(&BlackScholesModel::new(0.3));
+        assert_martingale(&HestonModel::new(0.04, 2.0, 0.05, 0.4, -0.7));
+        assert_martingale(&MertonJumpDiffusion::new(0.15, 0.5, -0.1, 0.2));
+        assert_martingale(&VarianceGamma::new(0.2, 0.25, -0.1));
+        assert_martingale(&NormalInverseGaussian::new(8.0, -2.0, 0.4));
+    }
+
+    #[test]
+    fn finite_difference_cumulants() {
+        struct Gbm(BlackScholesModel);
+        impl CharacteristicFunction for Gbm {
+            fn characteristic_function(&self, u: Complex64, t: f64) -> Complex64 {
+                self.0.characteristic_function(u, t)
+            }
+        }
+
+        let model = BlackScholesModel::new(0.3);
+        let (c1, c2, c4) = Gbm(model).cumulants(2.0);
+        let (b1, b2, b4) = model.cumulants(2.0);
+        assert_approx_eq!(c1, b1, 1e-10);
+        assert_approx_eq!(c2, b2, 1e-8);
+        assert_approx_eq!(c4, b4, 1e-4);
+    }
+
+    #[test]
+    fn merton_jump_moments() {
+        // the variance of the log returns adds the variance of the compound Poisson jumps
+        let model = MertonJumpDiffusion::new(0.15, 0.5, -0.1, 0.2);
+        let (_, c2, c4) = model.cumulants(1.0);
+        assert_approx_eq!(
+            c2,
+            0.15_f64.powi(2) + 0.5 * (0.1_f64.powi(2) + 0.2_f64.powi(2)),
+            1e-6
+        );
+        assert!(c4 > 0.0);
+    }
+}
@@ -0,0 +1,403 @@
+use std::f64::consts::PI;
+
+use num_complex::Complex64;
+
+use crate::analytic::black_scholes::{BlackScholesMerton, OptionGreeks, OptionPrice};
+use crate::analytic::fourier::HestonModel;
+use crate::common::models::{DerivativeParameter, ExerciseType};
+use crate::common::quadrature::integrate_semi_infinite;
+use crate::error::PricingError;
+
+/// Default absolute tolerance of the numerical integration.
+const INTEGRATION_TOLERANCE: f64 = 1e-10;
+
+/// The volatility of the option parameters is the initial volatility $\sqrt{v_0}$ of the
+/// variance process $dv_t = \kappa (\theta - v_t) dt + \sigma \sqrt{v_t} dW_t$.
+#[derive(Clone, Copy, Debug)]
+pub struct HestonParameter {
+    pub option_params: DerivativeParameter,
+    /// the speed $\kappa$ of the mean reversion
+    pub mean_reversion: f64,
+    /// the long term variance $\theta$
+    pub long_term_variance: f64,
+    /// the volatility $\sigma$ of the variance
+    pub vol_of_vol: f64,
+    /// the correlation $\rho$ of the asset and its variance
+    pub correlation: f64,
+    /// the absolute tolerance of the numerical integration
+    pub tolerance: f64,
+}
+
+impl HestonParameter {
+    pub fn new(
+        option_params: DerivativeParameter,
+        mean_reversion: f64,
+        long_term_variance: f64,
+        vol_of_vol: f64,
+        correlation: f64,
+    ) -> Self {
+        Self {
+            option_params,
+            mean_reversion,
+            long_term_variance,
+            vol_of_vol,
+            correlation,
+            tolerance: INTEGRATION_TOLERANCE,
+        }
+    }
+
+    pub fn with_tolerance(self, tolerance: f64) -> Self {
+        Self { tolerance, ..self }
+    }
+
+    pub fn model(&self) -> HestonModel {
+        HestonModel::new(
+            self.option_params.vola.powi(2),
+            self.mean_reversion,
+            self.long_term_variance,
+            self.vol_of_vol,
+            self.correlation,
+        )
+    }
+}
+
+/// The price and Greeks of an option for one strike. Vega, vanna and volga refer to the
+/// initial volatility $\sqrt{v_0}$.
+#[derive(Clone, Copy, Debug)]
+pub struct HestonValuation {
+    pub strike: f64,
+    pub price: f64,
+    pub delta: f64,
+    pub gamma: f64,
+    pub vega: f64,
+    pub theta: f64,
+    pub rho: f64,
+    pub vanna: f64,
+    pub volga: f64,
+    pub charm: f64,
+}
+
+/// European options in the Heston stochastic volatility model, using Lewis' integral over the
+/// "little trap" characteristic function of [HestonModel]. The Greeks follow from the same
+/// integral, theta and charm from the pricing PDE. At expiry the options are valued in the
+/// Black-Scholes limit with the initial volatility. Prices and Greeks are NaN if the integral
+/// does not converge, see [Heston::strike_strip] for the error.
+/// See Heston, A Closed-Form Solution for Options with Stochastic Volatility (1993), and
+/// Lewis, A Simple Option Formula for General Jump-Diffusion and other Exponential Lévy
+/// Processes (2001).
+pub struct Heston;
+
+/// The partial derivatives $(m, n)$ of the undiscounted price with respect to the log forward
+/// $x$ and the initial variance $v$ which are needed for the Greeks.
+const DERIVATIVES: [(i32, i32); 9] = [
+    (0, 0),
+    (1, 0),
+    (2, 0),
+    (3, 0),
+    (0, 1),
+    (1, 1),
+    (2, 1),
+    (0, 2),
+    (1, 2),
+];
+
+impl Heston {
+    /// The prices and Greeks for several strikes with a single numerical integration; the
+    /// strike of the option parameters is not used. Fails if the integral does not converge
+    /// to the tolerance.
+    pub fn strike_strip(
+        hp: &HestonParameter,
+        strikes: &[f64],
+        exercise_type: &ExerciseType,
+    ) -> Result<Vec<HestonValuation>, PricingError> {
+        let dp = &hp.option_params;
+        let (s, t, r, b) = (
+            dp.asset_price,
+            dp.time_to_expiration,
+            dp.rfr,
+            dp.cost_of_carry(),
+        );
+        let v = dp.vola.powi(2);
+        let (kappa, theta, sigma, rho) = (
+            hp.mean_reversion,
+            hp.long_term_variance,
+            hp.vol_of_vol,
+            hp.correlation,
+        );
+        let forward = s * (b * t).exp();
+        let discount_factor = (-r * t).exp();
+        let model = hp.model();
+
+        // the derivatives of e^{x / 2} e^{iuk} phi(u - i/2) with respect to x and v are
+        // (1/2 + iu)^m D^n times the function itself, which does not decay at expiry
+        let integrands = |u: f64| {
+            let z = Complex64::new(u, -0.5);
+            let (c, d) = model.characteristic_exponents(z, t);
+            let phi = (c + d * v).exp() / (u.powi(2) + 0.25);
+            let a = Complex64::new(0.5, u);
+            strikes
+                .iter()
+                .flat_map(|strike| {
+                    let integrand = Complex64::new(0.0, u * (forward / strike).ln()).exp() * phi;
+                    DERIVATIVES.map(|(m, n)| (a.powi(m) * d.powi(n) * integrand).re)
+                })
+                .collect()
+        };
+        if t == 0.0 {
+            return Ok(strikes
+                .iter()
+                .map(|&strike| Self::black_scholes_limit(dp, strike, exercise_type))
+                .collect());
+        }
+        let integrals = integrate_semi_infinite(integrands, hp.tolerance)?;
+
+        let valuations = strikes
+            .iter()
+            .zip(integrals.chunks(DERIVATIVES.len()))
+            .map(|(&strike, integral)| {
+                // G_{mn} of the call C = e^{-rT} G(x, v) = e^{-rT} (F - sqrt(FK) / pi * I)
+                let mut g = [0.0; 9];
+                for (idx, (_, n)) in DERIVATIVES.iter().enumerate() {
+                    let forward_term = if *n == 0 { forward } else { 0.0 };
+                    g[idx] = forward_term - (forward * strike).sqrt() / PI * integral[idx];
+                }
+                if *exercise_type == ExerciseType::Put {
+                    // put-call parity P = C - e^{-rT} (F - K)
+                    for (idx, (_, n)) in DERIVATIVES.iter().enumerate() {
+                        if *n == 0 {
+                            g[idx] -= forward;
+                        }
+                    }
+                    g[0] += strike;
+                }
+                let [g00, g10, g20, g30, g01, g11, g21, g02, g12] = g;
+
+                // the generator of the undiscounted price in x and v
+                let time_derivative = |gxx: f64, gx: f64, gxv: f64, gvv: f64, gv: f64| {
+                    v / 2.0 * (gxx - gx)
+                        + rho * sigma * v * gxv
+                        + sigma.powi(2) * v / 2.0 * gvv
+                        + kappa * (theta - v) * gv
+                };
+                let price = discount_factor * g00;
+                let delta = discount_factor * g10 / s;
+                HestonValuation {
+                    strike,
+                    price,
+                    delta,
+                    gamma: discount_factor * (g20 - g10) / s.powi(2),
+                    vega: 2.0 * v.sqrt() * discount_factor * g01,
+                    theta: r * price
+                        - discount_factor * (time_derivative(g20, g10, g11, g02, g01) + b * g10),
+                    rho: -t * price + t * discount_factor * g10,
+                    vanna: 2.0 * v.sqrt() * discount_factor * g11 / s,
+                    volga: 2.0 * discount_factor * g01 + 4.0 * v * discount_factor * g02,
+                    charm: r * delta
+                        - discount_factor / s
+                            * (time_derivative(g30, g20, g21, g12, g11) + b * g20),
+                }
+            })
+            .collect();
+        Ok(valuations)
+    }
+
+    /// The limit at expiry, in which the variance stays at its initial value.
+    fn black_scholes_limit(
+        dp: &DerivativeParameter,
+        strike: f64,
+        exercise_type: &ExerciseType,
+    ) -> HestonValuation {
+        let gbs = BlackScholesMerton::generalized(&DerivativeParameter { strike, ..*dp });
+        HestonValuation {
+            strike,
+            price: gbs.price(exercise_type),
+            delta: gbs.delta(exercise_type),
+            gamma: gbs.gamma(),
+            vega: gbs.vega(),
+            theta: gbs.theta(exercise_type),
+            rho: gbs.rho(exercise_type),
+            vanna: gbs.vanna(),
+            volga: gbs.volga(),
+            charm: gbs.charm(exercise_type),
+        }
+    }
+
+    /// The valuation for the strike of the option parameters, NaN if the integral does not
+    /// converge.
+    fn valuation(hp: &HestonParameter, exercise_type: &ExerciseType) -> HestonValuation {
+        let strike = hp.option_params.strike;
+        match Self::strike_strip(hp, &[strike], exercise_type) {
+            Ok(valuations) => valuations[0],
+            Err(_) => HestonValuation {
+                strike,
+                price: f64::NAN,
+                delta: f64::NAN,
+                gamma: f64::NAN,
+                vega: f64::NAN,
+                theta: f64::NAN,
+                rho: f64::NAN,
+                vanna: f64::NAN,
+                volga: f64::NAN,
+                charm: f64::NAN,
+            },
+        }
+    }
+}
+
+impl OptionPrice for Heston {
+    type Params = HestonParameter;
+
+    fn call(hp: &HestonParameter) -> f64 {
+        Self::valuation(hp, &ExerciseType::Call).price
+    }
+
+    fn put(hp: &HestonParameter) -> f64 {
+        Self::valuation(hp, &ExerciseType::Put).price
+    }
+}
+
+impl OptionGreeks for Heston {
+    fn delta(hp: &HestonParameter, exercise_type: &ExerciseType) -> f64 {
+        Self::valuation(hp, exercise_type).delta
+    }
+
+    fn gamma(hp: &HestonParameter, exercise_type: &ExerciseType) -> f64 {
+        Self::valuation(hp, exercise_type).gamma
+    }
+
+    fn vega(hp: &HestonParameter, exercise_type: &ExerciseType) -> f64 {
+        Self::valuation(hp, exercise_type).vega
+    }
+
+    fn theta(hp: &HestonParameter, exercise_type: &ExerciseType) -> f64 {
+        Self::valuation(hp, exercise_type).theta
+    }
+
+    fn rho(hp: &HestonParameter, exercise_type: &ExerciseType) -> f64 {
+        Self::valuation(hp, exercise_type).rho
+    }
+
+    fn vanna(hp: &HestonParameter, exercise_type: &ExerciseType) -> f64 {
+        Self::valuation(hp, exercise_type).vanna
+    }
+
+    fn volga(hp: &HestonParameter, exercise_type: &ExerciseType) -> f64 {
+        Self::valuation(hp, exercise_type).volga
+    }
+
+    fn charm(hp: &HestonParameter, exercise_type: &ExerciseType) -> f64 {
+        Self::valuation(hp, exercise_type).charm
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::black_scholes::tests::assert_greeks_match_finite_differences_with;
+    use crate::analytic::fourier::{Cos, FourierPricer};
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn fang_oosterlee_reference() {
+        // Fang and Oosterlee (2008), section 5.2, whose reference differs by about 2e-8
+        let dp = DerivativeParameter::new(100.0, 100.0, 1.0, 0.0, 0.0175_f64.sqrt());
+        let hp = HestonParameter::new(dp, 1.5768, 0.0398, 0.5751, -0.5711);
+        assert_approx_eq!(Heston::call(&hp), 5.785155450, 1e-7);
+    }
+
+    #[test]
+    fn strike_strip() {
+        let dp = DerivativeParameter::new(100.0, 0.0, 0.5, 0.04, 0.25).with_dividend_yield(0.02);
+        let hp = HestonParameter::new(dp, 1.5, 0.06, 0.7, -0.6);
+        let strikes = [60.0, 80.0, 100.0, 120.0, 150.0];
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            let prices = Cos::default()
+                .strike_strip(&hp.model(), &dp, &strikes, &exercise_type)
+                .unwrap();
+            let valuations = Heston::strike_strip(&hp, &strikes, &exercise_type).unwrap();
+            for (valuation, price) in valuations.iter().zip(prices) {
+                assert_approx_eq!(valuation.price, price, 1e-8);
+            }
+        }
+    }
+
+    #[test]
+    fn long_maturity_and_high_vol_of_vol() {
+        // the original formulation of Heston jumps across branches of the logarithm here
+        let dp = DerivativeParameter::new(100.0, 110.0, 15.0, 0.03, 0.3);
+        let hp = HestonParameter::new(dp, 0.5, 0.09, 1.5, -0.9);
+        let cos = Cos::new(4096, 12.0)
+            .price(&hp.model(), &dp, &ExerciseType::Call)
+            .unwrap();
+        assert_approx_eq!(Heston::call(&hp), cos, 1e-6);
+    }
+
+    #[test]
+    fn greeks_finite_differences() {
+        let dp = DerivativeParameter::new(100.0, 105.0, 0.8, 0.05, 0.2).with_dividend_yield(0.02);
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            assert_greeks_match_finite_differences_with::<Heston>(&dp, &exercise_type, |dp| {
+                HestonParameter::new(dp, 2.0, 0.05, 0.5, -0.7)
+            });
+        }
+    }
+
+    #[test]
+    fn expiry() {
+        let dp = DerivativeParameter::new(100.0, 0.0, 0.0, 0.05, 0.2);
+        let hp = HestonParameter::new(dp, 2.0, 0.05, 0.5, -0.7);
+        let strikes = [90.0, 100.0, 110.0];
+        let calls = Heston::strike_strip(&hp, &strikes, &ExerciseType::Call).unwrap();
+        for (valuation, intrinsic) in calls.iter().zip([10.0, 0.0, 0.0]) {
+            assert_eq!(valuation.price, intrinsic);
+            assert_eq!(valuation.gamma, 0.0);
+            assert_eq!(valuation.vega, 0.0);
+        }
+        assert_eq!(calls[0].delta, 1.0);
+        let puts = Heston::strike_strip(&hp, &strikes, &ExerciseType::Put).unwrap();
+        assert_eq!(puts[2].price, 10.0);
+        assert_eq!(puts[2].delta, -1.0);
+    }
+
+    #[test]
+    fn short_maturity() {
+        // the variance hardly moves from its initial value, the prices are close to the
+        // Black-Scholes ones up to the skew from the correlation
+        let dp = DerivativeParameter::new(100.0, 0.0, 1e-4, 0.05, 0.2);
+        let hp = HestonParameter::new(dp, 2.0, 0.05, 0.5, -0.7);
+        let strikes = [99.9, 100.0, 100.1];
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            let valuations = Heston::strike_strip(&hp, &strikes, &exercise_type).unwrap();
+            let prices = Cos::new(4096, 12.0)
+                .strike_strip(&hp.model(), &dp, &strikes, &exercise_type)
+                .unwrap();
+            for ((valuation, strike), cos) in valuations.iter().zip(strikes).zip(prices) {
+                assert_approx_eq!(valuation.price, cos, 1e-8);
+                let dp = DerivativeParameter { strike, ..dp };
+                let price = BlackScholesMerton::price(&dp, &exercise_type);
+                assert_approx_eq!(valuation.price, price, 1e-3);
+                let delta = BlackScholesMerton::delta(&dp, &exercise_type);
+                assert_approx_eq!(valuation.delta, delta, 2e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn no_convergence() {
+        let dp = DerivativeParameter::new(100.0, 100.0, 1.0, 0.05, 0.2);
+        let hp = HestonParameter::new(dp, 2.0, 0.05, 0.5, -0.7);
+        let cos = Cos::default()
+            .price(&hp.model(), &dp, &ExerciseType::Call)
+            .unwrap();
+        assert_approx_eq!(Heston::call(&hp), cos, 1e-8);
+        for tolerance in [1e-300, f64::NAN] {
+            let hp = hp.with_tolerance(tolerance);
+            assert!(matches!(
+                Heston::strike_strip(&hp, &[100.0], &ExerciseType::Call),
+                Err(PricingError::NoConvergence { .. })
+            ));
+            assert!(Heston::call(&hp).is_nan());
+            assert!(Heston::delta(&hp, &ExerciseType::Call).is_nan());
+        }
+    }
+}
@@ -0,0 +1,235 @@
+use crate::analytic::black_scholes::{Black76, BlackScholesMerton, OptionGreeks};
+use crate::common::models::{DerivativeParameter, ExerciseType};
+use crate::error::PricingError;
+
+/// Absolute tolerance on the volatility between two consecutive iterations.
+const VOLATILITY_TOLERANCE: f64 = 1e-12;
+const MAX_ITERATIONS: usize = 200;
+
+/// Inverts the pricing formula with respect to the volatility.
+/// The `vola` field of the derivative parameters is ignored.
+pub trait ImpliedVolatility: OptionGreeks<Params = DerivativeParameter> {
+    /// Model-free bounds (lower, upper) of the price, as attained for zero and infinite volatility.
+    fn price_bounds(dp: &DerivativeParameter, exercise_type: &ExerciseType) -> (f64, f64);
+
+    /// Starting point of the Newton iterations.
+    fn initial_guess(price: f64, dp: &DerivativeParameter, exercise_type: &ExerciseType) -> f64;
+
+    fn implied_volatility(
+        price: f64,
+        dp: &DerivativeParameter,
+        exercise_type: &ExerciseType,
+    ) -> Result<f64, PricingError> {
+        solve_implied_volatility(
+            price,
+            Self::price_bounds(dp, exercise_type),
+            Self::initial_guess(price, dp, exercise_type),
+            |vola| {
+                let trial_dp = DerivativeParameter { vola, ..*dp };
+                (
+                    Self::price(&trial_dp, exercise_type),
+                    Self::vega(&trial_dp, exercise_type),
+                )
+            },
+        )
+    }
+}
+
+/// Safeguarded Newton-Raphson: the root is kept in a bracket $[\sigma_l, \sigma_h]$,
+/// and whenever a Newton step leaves the bracket, a bisection step is taken instead.
+/// Since prices are increasing in the volatility, this converges for any price within the bounds.
+/// A price or vega which is not finite, e.g. NaN, stops the iterations as not converging.
+/// See Jäckel, By Implication (2006), for the choice of the initial guess.
+pub(crate) fn solve_implied_volatility(
+    price: f64,
+    (lower_bound, upper_bound): (f64, f64),
+    initial_guess: f64,
+    price_and_vega: impl Fn(f64) -> (f64, f64),
+) -> Result<f64, PricingError> {
+    PricingError::check_parameter("price", price, price.is_finite())?;
+    if price < lower_bound {
+        return Err(PricingError::PriceBelowLowerBound {
+            price,
+            bound: lower_bound,
+        });
+    }
+    if price >= upper_bound {
+        return Err(PricingError::PriceAboveUpperBound {
+            price,
+            bound: upper_bound,
+        });
+    }
+    if price == lower_bound {
+        return Ok(0.0);
+    }
+
+    // expand the bracket until it contains the implied volatility
+    let mut low = 0.0;
+    let mut high = initial_guess;
+    let mut iterations = 0;
+    loop {
+        let trial_price = price_and_vega(high).0;
+        if !trial_price.is_finite() {
+            return Err(PricingError::NoConvergence { iterations });
+        }
+        if trial_price >= price {
+            break;
+        }
+        low = high;
+        high *= 2.0;
+        iterations += 1;
+        if iterations >= MAX_ITERATIONS || !high.is_finite() {
+            return Err(PricingError::NoConvergence { iterations });
+        }
+    }
+
+    let mut vola = if low == 0.0 { initial_guess } else { high };
+    while iterations < MAX_ITERATIONS {
+        iterations += 1;
+        let (trial_price, vega) = price_and_vega(vola);
+        if !trial_price.is_finite() || !vega.is_finite() {
+            return Err(PricingError::NoConvergence { iterations });
+        }
+        let diff = trial_price - price;
+        if diff == 0.0 {
+            return Ok(vola);
+        }
+        if diff < 0.0 {
+            low = vola;
+        } else {
+            high = vola;
+        }
+
+        let newton_vola = vola - diff / vega;
+        let next_vola = if newton_vola > low && newton_vola < high {
+            newton_vola
+        } else {
+            0.5 * (low + high)
+        };
+
+        if (next_vola - vola).abs() < VOLATILITY_TOLERANCE {
+            return Ok(next_vola);
+        }
+        vola = next_vola;
+    }
+    Err(PricingError::NoConvergence { iterations })
+}
+
+/// The inflection point of the price as function of the volatility,
+/// from which Newton's method converges monotonically.
+fn lognormal_initial_guess(forward: f64, strike: f64, time_to_expiration: f64) -> f64 {
+    (2.0 * (forward / strike).ln().abs() / time_to_expiration)
+        .sqrt()
+        .max(0.1)
+}
+
+impl ImpliedVolatility for BlackScholesMerton {
+    fn price_bounds(dp: &DerivativeParameter, exercise_type: &ExerciseType) -> (f64, f64) {
+        Self::generalized(dp).price_bounds(exercise_type)
+    }
+
+    fn initial_guess(_price: f64, dp: &DerivativeParameter, _exercise_type: &ExerciseType) -> f64 {
+        let forward = dp.asset_price * (dp.cost_of_carry() * dp.time_to_expiration).exp();
+        lognormal_initial_guess(forward, dp.strike, dp.time_to_expiration)
+    }
+}
+
+impl ImpliedVolatility for Black76 {
+    fn price_bounds(dp: &DerivativeParameter, exercise_type: &ExerciseType) -> (f64, f64) {
+        Self::generalized(dp).price_bounds(exercise_type)
+    }
+
+    fn initial_guess(_price: f64, dp: &DerivativeParameter, _exercise_type: &ExerciseType) -> f64 {
+        lognormal_initial_guess(dp.asset_price, dp.strike, dp.time_to_expiration)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    const TOLERANCE: f64 = 1e-8;
+
+    fn assert_round_trip<P: ImpliedVolatility>(dp: &DerivativeParameter) {
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            let price = P::price(dp, &exercise_type);
+            let implied_vola = P::implied_volatility(price, dp, &exercise_type).unwrap();
+            assert_approx_eq!(implied_vola, dp.vola, TOLERANCE);
+        }
+    }
+
+    #[test]
+    fn implied_volatility_round_trip() {
+        for strike in [70.0, 90.0, 100.0, 110.0, 140.0] {
+            for vola in [0.1, 0.2, 0.6, 1.5] {
+                let dp = DerivativeParameter::new(100.0, strike, 1.5, 0.03, vola);
+                assert_round_trip::<BlackScholesMerton>(&dp);
+                assert_round_trip::<Black76>(&dp);
+            }
+        }
+    }
+
+    #[test]
+    fn implied_volatility_short_expiry() {
+        let dp = DerivativeParameter::new(100.0, 105.0, 1.0 / 365.0, 0.01, 0.3);
+        assert_round_trip::<BlackScholesMerton>(&dp);
+        assert_round_trip::<Black76>(&dp);
+    }
+
+    #[test]
+    fn implied_volatility_reference() {
+        // Hull, Options, Futures and Other Derivatives, chapter 15
+        let dp = DerivativeParameter::new(21.0, 20.0, 0.25, 0.1, 0.0);
+        let implied_vola =
+            BlackScholesMerton::implied_volatility(1.875, &dp, &ExerciseType::Call).unwrap();
+        assert_approx_eq!(implied_vola, 0.235, 1e-3);
+    }
+
+    #[test]
+    fn implied_volatility_out_of_bounds() {
+        let dp = DerivativeParameter::new(100.0, 90.0, 1.0, 0.05, 0.0);
+        let call = ExerciseType::Call;
+
+        let lower_bound = 100.0 - 90.0 * (-0.05_f64).exp();
+        assert_eq!(
+            BlackScholesMerton::implied_volatility(5.0, &dp, &call),
+            Err(PricingError::PriceBelowLowerBound {
+                price: 5.0,
+                bound: lower_bound
+            })
+        );
+        assert_eq!(
+            BlackScholesMerton::implied_volatility(100.0, &dp, &call),
+            Err(PricingError::PriceAboveUpperBound {
+                price: 100.0,
+                bound: 100.0
+            })
+        );
+        assert_eq!(
+            BlackScholesMerton::implied_volatility(lower_bound, &dp, &call),
+            Ok(0.0)
+        );
+    }
+
+    #[test]
+    fn implied_volatility_not_finite() {
+        // a pricer returning NaN fails instead of converging to some volatility
+        let nan_price = |vola: f64| (if vola > 0.3 { f64::NAN } else { vola }, 1.0);
+        assert_eq!(
+            solve_implied_volatility(0.5, (0.0, 1.0), 0.2, nan_price),
+            Err(PricingError::NoConvergence { iterations: 1 })
+        );
+        let nan_vega = |vola: f64| (vola, f64::NAN);
+        assert_eq!(
+            solve_implied_volatility(0.5, (0.0, 1.0), 0.8, nan_vega),
+            Err(PricingError::NoConvergence { iterations: 1 })
+        );
+
+        let dp = DerivativeParameter::new(100.0, 90.0, 1.0, 0.05, 0.0);
+        assert!(matches!(
+            BlackScholesMerton::implied_volatility(f64::NAN, &dp, &ExerciseType::Call),
+            Err(PricingError::InvalidParameter { name: "price", .. })
+        ));
+    }
+}
@@ -0,0 +1,336 @@
+use crate::analytic::black_scholes::OptionPrice;
+use crate::common::models::DerivativeParameter;
+use crate::common::numeric::Numeric;
+
+/// Below this cost of carry the premium of the extremum is evaluated in the limit $b \to 0$,
+/// which avoids the cancellation of the closed form.
+const CARRY_THRESHOLD: f64 = 1e-9;
+
+/// The running minimum and maximum default to the asset price for new trades and are the
+/// extrema observed so far for seasoned trades.
+#[derive(Clone, Copy, Debug)]
+pub struct LookbackParameter<T: Numeric = f64> {
+    pub option_params: DerivativeParameter<T>,
+    /// the minimum of the asset price observed so far
+    pub running_minimum: T,
+    /// the maximum of the asset price observed so far
+    pub running_maximum: T,
+}
+
+impl<T: Numeric> LookbackParameter<T> {
+    pub fn new(option_params: DerivativeParameter<T>) -> Self {
+        Self {
+            option_params,
+            running_minimum: option_params.asset_price,
+            running_maximum: option_params.asset_price,
+        }
+    }
+
+    pub fn with_running_extrema(self, running_minimum: T, running_maximum: T) -> Self {
+        Self {
+            running_minimum,
+            running_maximum,
+            ..self
+        }
+    }
+}
+
+/// The value of receiving $\phi (X_T - S_T)$ for the extremum $X_T$ of the asset price,
+/// which is the maximum for $\eta = 1$ and the minimum for $\eta = -1$, as if it was at the
+/// given level today: the vanilla part $\phi (S e^{(b - r)T} N(\phi a_1) - X e^{-rT} N(\phi a_2))$
+/// plus the premium of the extremum moving beyond the level.
+fn extremum_value<T: Numeric>(dp: &DerivativeParameter<T>, level: T, phi: f64, eta: f64) -> T {
+    let (s, t, r, b, sigma) = (
+        dp.asset_price,
+        dp.time_to_expiration,
+        dp.rfr,
+        dp.cost_of_carry(),
+        dp.vola,
+    );
+    let (two, sign) = (T::from_f64(2.0), T::from_f64(eta));
+    let sigma_sqrt_t = sigma * t.sqrt();
+    if sigma_sqrt_t == T::zero() {
+        // at expiry or without volatility the asset moves deterministically to the forward
+        let forward = s * (b * t).exp();
+        let extremum = if eta > 0.0 {
+            level.max(s).max(forward)
+        } else {
+            level.min(s).min(forward)
+        };
+        let payoff = if phi == eta {
+            sign * (extremum - level)
+        } else {
+            sign * (extremum - forward)
+        };
+        return payoff * (-r * t).exp();
+    }
+    let phi = T::from_f64(phi);
+    let a1 = ((s / level).ln() + (b + sigma.powi(2) / two) * t) / sigma_sqrt_t;
+    let a2 = a1 - sigma_sqrt_t;
+    let vanilla = phi
+        * (s * ((b - r) * t).exp() * (phi * a1).norm_cdf()
+            - level * (-r * t).exp() * (phi * a2).norm_cdf());
+
+    let premium = if b.abs().to_f64() < CARRY_THRESHOLD {
+        let moneyness = (s / level).ln();
+        sign * (sigma.powi(2) * t / two + moneyness) * (sign * a1).norm_cdf()
+            + sigma_sqrt_t * a1.norm_pdf()
+    } else {
+        let exponent = -two * b / sigma.powi(2);
+        let shift = two * b * t.sqrt() / sigma;
+        sigma.powi(2) / (two * b)
+            * sign
+            * ((b * t).exp() * (sign * a1).norm_cdf()
+                - (s / level).powf(exponent) * (sign * (a1 - shift)).norm_cdf())
+    };
+    vanilla + s * (-r * t).exp() * premium
+}
+
+/// Floating strike lookback options with continuous monitoring: the call pays the asset price
+/// less its minimum and the put pays the maximum less the asset price.
+/// See Goldman, Sosin and Gatto, Path Dependent Options: Buy at the Low, Sell at the High (1979),
+/// and Haug, The Complete Guide to Option Pricing Formulas, chapter 4.15.1.
+pub struct FloatingStrikeLookback;
+
+impl<T: Numeric> OptionPrice<T> for FloatingStrikeLookback {
+    type Params = LookbackParameter<T>;
+
+    fn call(lp: &LookbackParameter<T>) -> T {
+        extremum_value(&lp.option_params, lp.running_minimum, 1.0, -1.0)
+    }
+
+    fn put(lp: &LookbackParameter<T>) -> T {
+        extremum_value(&lp.option_params, lp.running_maximum, -1.0, 1.0)
+    }
+}
+
+/// Fixed strike lookback options with continuous monitoring: the call pays the maximum of the
+/// asset price over the strike and the put pays the strike over the minimum.
+/// See Conze and Viswanathan, Path Dependent Options: The Case of Lookback Options (1991), and
+/// Haug, The Complete Guide to Option Pricing Formulas, chapter 4.15.2.
+pub struct FixedStrikeLookback;
+
+impl<T: Numeric> OptionPrice<T> for FixedStrikeLookback {
+    type Params = LookbackParameter<T>;
+
+    fn call(lp: &LookbackParameter<T>) -> T {
+        let dp = &lp.option_params;
+        let (strike, maximum) = (dp.strike, lp.running_maximum);
+        if strike > maximum {
+            extremum_value(dp, strike, 1.0, 1.0)
+        } else {
+            // the maximum exceeds the strike already
+            let intrinsic = (maximum - strike) * (-dp.rfr * dp.time_to_expiration).exp();
+            intrinsic + extremum_value(dp, maximum, 1.0, 1.0)
+        }
+    }
+
+    fn put(lp: &LookbackParameter<T>) -> T {
+        let dp = &lp.option_params;
+        let (strike, minimum) = (dp.strike, lp.running_minimum);
+        if strike < minimum {
+            extremum_value(dp, strike, -1.0, -1.0)
+        } else {
+            let intrinsic = (strike - minimum) * (-dp.rfr * dp.time_to_expiration).exp();
+            intrinsic + extremum_value(dp, minimum, -1.0, -1.0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::black_scholes::tests::assert_dual_derivatives_match_finite_differences;
+    use crate::analytic::black_scholes::BlackScholesMerton;
+    use crate::common::models::ExerciseType;
+    use crate::common::numeric::Dual;
+    use crate::simulation::products::european_option::MonteCarloEuropeanOption;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn floating_strike() {
+        // Haug, The Complete Guide to Option Pricing Formulas, example 4.15.1
+        let dp = DerivativeParameter::new(120.0, 0.0, 0.5, 0.1, 0.3).with_dividend_yield(0.06);
+        let lp = LookbackParameter::new(dp).with_running_extrema(100.0, 120.0);
+        assert_approx_eq!(FloatingStrikeLookback::call(&lp), 25.3533, 1e-4);
+
+        // the floating strike is worth at least the vanilla option at the running extremum
+        let call = DerivativeParameter {
+            strike: 100.0,
+            ..dp
+        };
+        assert!(FloatingStrikeLookback::call(&lp) > BlackScholesMerton::call(&call));
+        let put = DerivativeParameter {
+            strike: 120.0,
+            ..dp
+        };
+        assert!(FloatingStrikeLookback::put(&lp) > BlackScholesMerton::put(&put));
+    }
+
+    #[test]
+    fn fixed_floating_parity() {
+        // max(M, K) - K is the floating strike put on max(M, K) plus a forward
+        let dp = DerivativeParameter::new(100.0, 0.0, 0.75, 0.05, 0.3).with_dividend_yield(0.03);
+        let forward =
+            |strike: f64| 100.0 * (-0.03_f64 * 0.75).exp() - strike * (-0.05_f64 * 0.75).exp();
+        for (strike, minimum, maximum) in [
+            (110.0, 90.0, 105.0),
+            (95.0, 90.0, 105.0),
+            (85.0, 80.0, 100.0),
+        ] {
+            let lp = LookbackParameter::new(DerivativeParameter { strike, ..dp })
+                .with_running_extrema(minimum, maximum);
+            let floating_put = lp.with_running_extrema(minimum, maximum.max(strike));
+            assert_approx_eq!(
+                FixedStrikeLookback::call(&lp),
+                FloatingStrikeLookback::put(&floating_put) + forward(strike),
+                1e-10
+            );
+            let floating_call = lp.with_running_extrema(minimum.min(strike), maximum);
+            assert_approx_eq!(
+                FixedStrikeLookback::put(&lp),
+                FloatingStrikeLookback::call(&floating_call) - forward(strike),
+                1e-10
+            );
+        }
+    }
+
+    #[test]
+    fn zero_cost_of_carry() {
+        let dp = DerivativeParameter::new(100.0, 100.0, 1.0, 0.05, 0.2).with_dividend_yield(0.05);
+        let lp = LookbackParameter::new(dp);
+        let carry = |b: f64| LookbackParameter {
+            option_params: dp.with_dividend_yield(0.05 - b),
+            ..lp
+        };
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            let floating = FloatingStrikeLookback::price(&lp, &exercise_type);
+            let fixed = FixedStrikeLookback::price(&lp, &exercise_type);
+            for b in [1e-7, -1e-7] {
+                assert_approx_eq!(
+                    FloatingStrikeLookback::price(&carry(b), &exercise_type),
+                    floating,
+                    1e-5
+                );
+                assert_approx_eq!(
+                    FixedStrikeLookback::price(&carry(b), &exercise_type),
+                    fixed,
+                    1e-5
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn expired() {
+        let dp = DerivativeParameter::new(100.0, 95.0, 0.0, 0.05, 0.2);
+        let lp = LookbackParameter::new(dp).with_running_extrema(90.0, 110.0);
+        assert_eq!(FloatingStrikeLookback::call(&lp), 10.0);
+        assert_eq!(FloatingStrikeLookback::put(&lp), 10.0);
+        assert_eq!(FixedStrikeLookback::call(&lp), 15.0);
+        assert_eq!(FixedStrikeLookback::put(&lp), 5.0);
+
+        // the limit is continuous
+        let lp = LookbackParameter {
+            option_params: DerivativeParameter {
+                time_to_expiration: 1e-12,
+                ..dp
+            },
+            ..lp
+        };
+        assert_approx_eq!(FloatingStrikeLookback::call(&lp), 10.0, 1e-8);
+        assert_approx_eq!(FloatingStrikeLookback::put(&lp), 10.0, 1e-8);
+        assert_approx_eq!(FixedStrikeLookback::call(&lp), 15.0, 1e-8);
+        assert_approx_eq!(FixedStrikeLookback::put(&lp), 5.0, 1e-8);
+    }
+
+    #[test]
+    fn discretely_monitored_simulation() {
+        // shifting the extrema by exp(0.5826 sigma sqrt(dt)) corrects for the discrete monitoring
+        // of the paths, see Broadie, Glasserman and Kou, A Continuity Correction for Discrete
+        // Barrier Options (1997)
+        let mc_option: MonteCarloEuropeanOption<rand_hc::Hc128Rng> = MonteCarloEuropeanOption::new(
+            DerivativeParameter::new(100.0, 105.0, 0.5, 0.05, 0.25),
+            20_000,
+            100,
+            42,
+        );
+        let correction = (0.5826 * 0.25 * mc_option.dt().sqrt()).exp();
+        let discount_factor = mc_option.discount_factor(0.5);
+        let lp = LookbackParameter::new(mc_option.option_params);
+
+        let floating_call = mc_option
+            .sample_payoffs(|path| {
+                let minimum = path.iter().copied().fold(f64::INFINITY, f64::min) / correction;
+                path.last().map(|last| (last - minimum) * discount_factor)
+            })
+            .unwrap();
+        assert_approx_eq!(floating_call, FloatingStrikeLookback::call(&lp), 0.1);
+
+        let fixed_call = mc_option
+            .sample_payoffs(|path| {
+                let maximum = path.iter().copied().fold(0.0, f64::max) * correction;
+                Some((maximum - 105.0).max(0.0) * discount_factor)
+            })
+            .unwrap();
+        assert_approx_eq!(fixed_call, FixedStrikeLookback::call(&lp), 0.1);
+    }
+
+    #[test]
+    fn zero_volatility() {
+        // the asset rises deterministically to the forward above the running maximum
+        let dp = DerivativeParameter::new(100.0, 95.0, 1.0, 0.05, 0.0).with_dividend_yield(0.02);
+        let lp = LookbackParameter::new(dp).with_running_extrema(90.0, 102.0);
+        let (forward, discount_factor) = (100.0 * 0.03_f64.exp(), (-0.05_f64).exp());
+        assert_approx_eq!(
+            FloatingStrikeLookback::call(&lp),
+            (forward - 90.0) * discount_factor,
+            1e-12
+        );
+        assert_approx_eq!(FloatingStrikeLookback::put(&lp), 0.0, 1e-12);
+        assert_approx_eq!(
+            FixedStrikeLookback::call(&lp),
+            (forward - 95.0) * discount_factor,
+            1e-12
+        );
+        assert_approx_eq!(
+            FixedStrikeLookback::put(&lp),
+            (95.0 - 90.0) * discount_factor,
+            1e-12
+        );
+    }
+
+    #[test]
+    fn automatic_differentiation() {
+        let dp = DerivativeParameter::new(100.0, 110.0, 0.5, 0.1, 0.3).with_dividend_yield(0.06);
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            let et = &exercise_type;
+            // new trades, whose extrema move with the asset price
+            let (new, dual_new) = (LookbackParameter::new, LookbackParameter::new);
+            assert_dual_derivatives_match_finite_differences::<FloatingStrikeLookback>(
+                &dp, et, new, dual_new,
+            );
+            assert_dual_derivatives_match_finite_differences::<FixedStrikeLookback>(
+                &dp, et, new, dual_new,
+            );
+            // seasoned trades
+            let seasoned = |dp| LookbackParameter::new(dp).with_running_extrema(90.0, 115.0);
+            let dual_seasoned = |dp| {
+                LookbackParameter::new(dp)
+                    .with_running_extrema(Dual::constant(90.0), Dual::constant(115.0))
+            };
+            assert_dual_derivatives_match_finite_differences::<FloatingStrikeLookback>(
+                &dp,
+                et,
+                seasoned,
+                dual_seasoned,
+            );
+            assert_dual_derivatives_match_finite_differences::<FixedStrikeLookback>(
+                &dp,
+                et,
+                seasoned,
+                dual_seasoned,
+            );
+        }
+    }
+}
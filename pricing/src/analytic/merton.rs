@@ -0,0 +1,206 @@
+use crate::analytic::black_scholes::{BlackScholesMerton, OptionPrice};
+use crate::analytic::fourier::MertonJumpDiffusion;
+use crate::common::models::{DerivativeParameter, ExerciseType};
+use crate::common::numeric::Numeric;
+
+/// Default absolute tolerance on the terms at which the series is truncated.
+const SERIES_TOLERANCE: f64 = 1e-12;
+const MAX_SERIES_TERMS: i32 = 500;
+
+/// The volatility of the option parameters is the volatility of the diffusion, the log jump
+/// sizes are normally distributed.
+#[derive(Clone, Copy, Debug)]
+pub struct MertonParameter<T: Numeric = f64> {
+    pub option_params: DerivativeParameter<T>,
+    /// the expected number of jumps per year
+    pub jump_intensity: T,
+    /// the mean of the logarithmic jump sizes
+    pub jump_mean: T,
+    /// the standard deviation of the logarithmic jump sizes
+    pub jump_vola: T,
+    /// the series is truncated once the terms fall below the tolerance
+    pub tolerance: f64,
+}
+
+impl<T: Numeric> MertonParameter<T> {
+    pub fn new(
+        option_params: DerivativeParameter<T>,
+        jump_intensity: T,
+        jump_mean: T,
+        jump_vola: T,
+    ) -> Self {
+        Self {
+            option_params,
+            jump_intensity,
+            jump_mean,
+            jump_vola,
+            tolerance: SERIES_TOLERANCE,
+        }
+    }
+
+    pub fn with_tolerance(self, tolerance: f64) -> Self {
+        Self { tolerance, ..self }
+    }
+}
+
+impl MertonParameter {
+    pub fn model(&self) -> MertonJumpDiffusion {
+        MertonJumpDiffusion::new(
+            self.option_params.vola,
+            self.jump_intensity,
+            self.jump_mean,
+            self.jump_vola,
+        )
+    }
+}
+
+/// European options in Merton's jump-diffusion model as Poisson weighted sum of
+/// Black-Scholes-Merton prices. Given $n$ jumps until expiration, the asset is lognormal with
+/// the variance $\sigma^2 + n \delta^2 / T$ and the cost of carry
+/// $b - \lambda k + n \ln(1 + k) / T$, where $k = e^{\mu + \delta^2 / 2} - 1$.
+/// See Merton, Option Pricing when Underlying Stock Returns are Discontinuous (1976).
+pub struct Merton;
+
+impl Merton {
+    fn merton_price<T: Numeric>(mp: &MertonParameter<T>, exercise_type: &ExerciseType) -> T {
+        let dp = &mp.option_params;
+        let t = dp.time_to_expiration;
+        if t == T::zero() {
+            // no jumps occur until expiration, which leaves the intrinsic value
+            return BlackScholesMerton::price(dp, exercise_type);
+        }
+        // ln(1 + k), see MertonJumpDiffusion::mean_jump_size
+        let log_jump_size = mp.jump_mean + mp.jump_vola.powi(2) / T::from_f64(2.0);
+        let k = log_jump_size.exp() - T::one();
+        let expected_jumps = mp.jump_intensity * t;
+        let scale = dp.asset_price.max(dp.strike);
+
+        let mut poisson_weight = (-expected_jumps).exp();
+        let mut price = T::zero();
+        for n in 0..MAX_SERIES_TERMS {
+            let n_f = T::from_f64(n as f64);
+            if n > 0 {
+                poisson_weight = poisson_weight * expected_jumps / n_f;
+            }
+            let conditional_dp = DerivativeParameter {
+                vola: (dp.vola.powi(2) + n_f * mp.jump_vola.powi(2) / t).sqrt(),
+                dividend_yield: dp.dividend_yield + mp.jump_intensity * k - n_f * log_jump_size / t,
+                ..*dp
+            };
+            price =
+                price + poisson_weight * BlackScholesMerton::price(&conditional_dp, exercise_type);
+            // the terms are bounded by the weights times the asset price or the strike
+            if n_f > expected_jumps && (poisson_weight * scale).to_f64() < mp.tolerance {
+                break;
+            }
+        }
+        price
+    }
+}
+
+impl<T: Numeric> OptionPrice<T> for Merton {
+    type Params = MertonParameter<T>;
+
+    fn call(mp: &MertonParameter<T>) -> T {
+        Self::merton_price(mp, &ExerciseType::Call)
+    }
+
+    fn put(mp: &MertonParameter<T>) -> T {
+        Self::merton_price(mp, &ExerciseType::Put)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::black_scholes::tests::assert_dual_derivatives_match_finite_differences;
+    use crate::analytic::fourier::{Cos, FourierPricer};
+    use crate::common::numeric::Dual;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn no_jumps() {
+        let dp = DerivativeParameter::new(100.0, 95.0, 0.5, 0.05, 0.2).with_dividend_yield(0.02);
+        let mp = MertonParameter::new(dp, 0.0, -0.1, 0.3);
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            assert_approx_eq!(
+                Merton::price(&mp, &exercise_type),
+                BlackScholesMerton::price(&dp, &exercise_type),
+                1e-12
+            );
+        }
+    }
+
+    #[test]
+    fn characteristic_function_pricing() {
+        let dp = DerivativeParameter::new(100.0, 0.0, 0.25, 0.04, 0.15).with_dividend_yield(0.01);
+        let mp = MertonParameter::new(dp, 1.5, -0.08, 0.25);
+        let strikes = [70.0, 90.0, 100.0, 110.0, 130.0];
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            let prices = Cos::default()
+                .strike_strip(&mp.model(), &dp, &strikes, &exercise_type)
+                .unwrap();
+            for (strike, price) in strikes.iter().zip(prices) {
+                let mp = MertonParameter {
+                    option_params: DerivativeParameter {
+                        strike: *strike,
+                        ..dp
+                    },
+                    ..mp
+                };
+                assert_approx_eq!(Merton::price(&mp, &exercise_type), price, 1e-8);
+            }
+        }
+    }
+
+    #[test]
+    fn put_call_parity() {
+        let dp = DerivativeParameter::new(100.0, 105.0, 2.0, 0.03, 0.2).with_dividend_yield(0.01);
+        let mp = MertonParameter::new(dp, 3.0, 0.05, 0.1);
+        assert_approx_eq!(
+            Merton::call(&mp) - Merton::put(&mp),
+            dp.asset_price * (-0.02_f64).exp() - dp.strike * (-0.06_f64).exp(),
+            1e-10
+        );
+    }
+
+    #[test]
+    fn truncation_tolerance() {
+        let dp = DerivativeParameter::new(100.0, 100.0, 1.0, 0.05, 0.2);
+        let mp = MertonParameter::new(dp, 5.0, -0.05, 0.1);
+        let coarse = Merton::call(&mp.with_tolerance(1e-2));
+        let fine = Merton::call(&mp);
+        assert_approx_eq!(coarse, fine, 1e-2);
+    }
+
+    #[test]
+    fn expiry() {
+        for (strike, call, put) in [(95.0, 5.0, 0.0), (100.0, 0.0, 0.0), (105.0, 0.0, 5.0)] {
+            let dp = DerivativeParameter::new(100.0, strike, 0.0, 0.05, 0.2);
+            let mp = MertonParameter::new(dp, 3.0, -0.1, 0.3);
+            assert_eq!(Merton::call(&mp), call);
+            assert_eq!(Merton::put(&mp), put);
+        }
+
+        // the limit is continuous
+        let dp = DerivativeParameter::new(100.0, 95.0, 1e-10, 0.05, 0.2);
+        let mp = MertonParameter::new(dp, 3.0, -0.1, 0.3);
+        assert_approx_eq!(Merton::call(&mp), 5.0, 1e-6);
+    }
+
+    #[test]
+    fn automatic_differentiation() {
+        let dp = DerivativeParameter::new(100.0, 95.0, 0.5, 0.05, 0.2).with_dividend_yield(0.02);
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            assert_dual_derivatives_match_finite_differences::<Merton>(
+                &dp,
+                &exercise_type,
+                |dp| MertonParameter::new(dp, 1.5, -0.08, 0.25),
+                |dp| {
+                    let c = Dual::constant;
+                    MertonParameter::new(dp, c(1.5), c(-0.08), c(0.25))
+                },
+            );
+        }
+    }
+}
@@ -1 +1,17 @@
+pub mod american;
+pub mod asian;
+pub mod bachelier;
+pub mod barrier;
+pub mod batch;
 pub mod black_scholes;
+pub mod compound;
+pub mod digital;
+pub mod double_barrier;
+pub mod fourier;
+pub mod heston;
+pub mod implied_volatility;
+pub mod lookback;
+pub mod merton;
+pub mod power;
+pub mod sabr;
+pub mod spread;
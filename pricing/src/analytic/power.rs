@@ -0,0 +1,156 @@
+use crate::analytic::black_scholes::{OptionGreeks, OptionPrice};
+use crate::analytic::digital::{impl_forward_partials_greeks, ForwardPartials, PoweredForward};
+use crate::common::models::{DerivativeParameter, ExerciseType};
+use crate::common::numeric::Numeric;
+
+#[derive(Clone, Copy, Debug)]
+pub struct PowerParameter<T: Numeric = f64> {
+    pub option_params: DerivativeParameter<T>,
+    /// the power $i$ to which the asset price is raised at expiration
+    pub exponent: T,
+}
+
+impl<T: Numeric> PowerParameter<T> {
+    pub fn new(option_params: DerivativeParameter<T>, exponent: T) -> Self {
+        Self {
+            option_params,
+            exponent,
+        }
+    }
+}
+
+/// Standard power options paying $\max(S_T^i - K, 0)$ (call) or $\max(K - S_T^i, 0)$ (put).
+/// As $S_T^i$ is lognormal with volatility $i \sigma$, they are priced like options on its forward.
+/// See Haug, The Complete Guide to Option Pricing Formulas, chapter 4.15.
+pub struct Power;
+
+impl Power {
+    fn partials<T: Numeric>(
+        pp: &PowerParameter<T>,
+        exercise_type: &ExerciseType,
+    ) -> (PoweredForward<T>, ForwardPartials<T>) {
+        let forward = PoweredForward::new(&pp.option_params, pp.exponent);
+        let partials = forward.vanilla_partials(pp.option_params.strike, exercise_type);
+        (forward, partials)
+    }
+}
+
+impl_forward_partials_greeks!(Power, PowerParameter);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::black_scholes::tests::assert_dual_derivatives_match_finite_differences;
+    use crate::analytic::black_scholes::tests::assert_greeks_match_finite_differences_with;
+    use crate::analytic::black_scholes::{Black76, BlackScholesMerton};
+    use crate::common::numeric::Dual;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn unit_exponent() {
+        let dp = DerivativeParameter::new(100.0, 95.0, 1.3, 0.04, 0.25).with_dividend_yield(0.02);
+        let pp = PowerParameter::new(dp, 1.0);
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            assert_approx_eq!(
+                Power::price(&pp, &exercise_type),
+                BlackScholesMerton::price(&dp, &exercise_type),
+                1e-10
+            );
+            assert_approx_eq!(
+                Power::vega(&pp, &exercise_type),
+                BlackScholesMerton::vega(&dp, &exercise_type),
+                1e-10
+            );
+        }
+    }
+
+    #[test]
+    fn squared_asset() {
+        // S_T^2 is lognormal with forward S^2 exp((2b + sigma^2) T) and volatility 2 sigma
+        let dp = DerivativeParameter::new(10.0, 110.0, 0.5, 0.08, 0.15).with_dividend_yield(0.03);
+        let pp = PowerParameter::new(dp, 2.0);
+        let forward = dp.asset_price.powi(2)
+            * ((2.0 * dp.cost_of_carry() + dp.vola.powi(2)) * dp.time_to_expiration).exp();
+        let black_dp = DerivativeParameter::new(forward, 110.0, 0.5, 0.08, 0.3);
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            assert_approx_eq!(
+                Power::price(&pp, &exercise_type),
+                Black76::price(&black_dp, &exercise_type),
+                1e-10
+            );
+        }
+    }
+
+    #[test]
+    fn negative_exponent() {
+        // S_T^{-1} is lognormal with forward S^{-1} exp((sigma^2 - b) T) and volatility sigma,
+        // so the call still pays if it ends above the strike
+        let dp = DerivativeParameter::new(20.0, 0.05, 0.5, 0.04, 0.25).with_dividend_yield(0.01);
+        let pp = PowerParameter::new(dp, -1.0);
+        let forward =
+            ((dp.vola.powi(2) - dp.cost_of_carry()) * dp.time_to_expiration).exp() / dp.asset_price;
+        let black_dp = DerivativeParameter::new(forward, 0.05, 0.5, 0.04, 0.25);
+        for strike in [0.04, 0.05, 0.06] {
+            let pp = PowerParameter::new(DerivativeParameter { strike, ..dp }, -1.0);
+            let black_dp = DerivativeParameter { strike, ..black_dp };
+            for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+                let price = Power::price(&pp, &exercise_type);
+                assert!(price > 0.0);
+                assert_approx_eq!(price, Black76::price(&black_dp, &exercise_type), 1e-12);
+            }
+        }
+
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            assert_greeks_match_finite_differences_with::<Power>(&dp, &exercise_type, |dp| {
+                PowerParameter {
+                    option_params: dp,
+                    ..pp
+                }
+            });
+        }
+    }
+
+    #[test]
+    fn greeks_finite_differences() {
+        let dp = DerivativeParameter::new(10.0, 105.0, 0.8, 0.05, 0.2).with_dividend_yield(0.02);
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            assert_greeks_match_finite_differences_with::<Power>(&dp, &exercise_type, |dp| {
+                PowerParameter::new(dp, 2.0)
+            });
+        }
+
+        let dp = DerivativeParameter::new(100.0, 10.0, 1.2, 0.03, 0.3);
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            assert_greeks_match_finite_differences_with::<Power>(&dp, &exercise_type, |dp| {
+                PowerParameter::new(dp, 0.5)
+            });
+        }
+    }
+
+    #[test]
+    fn automatic_differentiation() {
+        let dp = DerivativeParameter::new(10.0, 110.0, 0.5, 0.08, 0.15).with_dividend_yield(0.03);
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            assert_dual_derivatives_match_finite_differences::<Power>(
+                &dp,
+                &exercise_type,
+                |dp| PowerParameter::new(dp, 2.0),
+                |dp| PowerParameter::new(dp, Dual::constant(2.0)),
+            );
+        }
+
+        // the sensitivity to the exponent
+        let pp = PowerParameter::new(dp, 2.0);
+        let h = 1e-5;
+        let shifted = |exponent: f64| Power::call(&PowerParameter { exponent, ..pp });
+        let dual = Power::call(&PowerParameter::new(
+            dp.map(Dual::constant),
+            Dual::variable(2.0),
+        ));
+        assert_approx_eq!(
+            dual.derivative,
+            (shifted(2.0 + h) - shifted(2.0 - h)) / (2.0 * h),
+            1e-4
+        );
+    }
+}
@@ -0,0 +1,335 @@
+use crate::analytic::bachelier::Bachelier;
+use crate::analytic::black_scholes::{Black76, OptionPrice};
+use crate::common::models::{DerivativeParameter, ExerciseType};
+use crate::common::numeric::Numeric;
+
+/// Below this log-moneyness the at-the-money limits of the expansions are used.
+const ATM_THRESHOLD: f64 = 1e-8;
+
+/// The asymptotic expansion of the SABR implied volatility.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SabrExpansion {
+    /// Hagan's lognormal (Black) volatility
+    HaganLognormal,
+    /// Hagan's normal (Bachelier) volatility
+    HaganNormal,
+    /// Obloj's correction of the leading order term of the lognormal volatility, which is
+    /// more accurate for strikes far from the forward
+    Obloj,
+}
+
+/// The forward $F_t$ follows $dF_t = \alpha_t (F_t + s)^\beta dW_t$ with the stochastic volatility
+/// $d\alpha_t = \nu \alpha_t dZ_t$ and $d\langle W, Z \rangle_t = \rho dt$, for the shift $s$.
+/// The asset price of the option parameters is the forward and their volatility is not used.
+#[derive(Clone, Copy, Debug)]
+pub struct SabrParameter<T: Numeric = f64> {
+    pub option_params: DerivativeParameter<T>,
+    /// the initial volatility $\alpha$
+    pub alpha: T,
+    /// the CEV exponent $\beta$ in $[0, 1]$
+    pub beta: T,
+    /// the correlation $\rho$ of the forward and its volatility
+    pub rho: T,
+    /// the volatility $\nu$ of the volatility
+    pub nu: T,
+    /// the shift $s$ allowing for forwards and strikes above $-s$, e.g. negative rates
+    pub shift: T,
+    pub expansion: SabrExpansion,
+}
+
+impl<T: Numeric> SabrParameter<T> {
+    pub fn new(option_params: DerivativeParameter<T>, alpha: T, beta: T, rho: T, nu: T) -> Self {
+        Self {
+            option_params,
+            alpha,
+            beta,
+            rho,
+            nu,
+            shift: T::zero(),
+            expansion: SabrExpansion::HaganLognormal,
+        }
+    }
+
+    pub fn with_shift(self, shift: T) -> Self {
+        Self { shift, ..self }
+    }
+
+    pub fn with_expansion(self, expansion: SabrExpansion) -> Self {
+        Self { expansion, ..self }
+    }
+
+    /// The shifted forward and strike.
+    fn shifted(&self) -> (T, T) {
+        (
+            self.option_params.asset_price + self.shift,
+            self.option_params.strike + self.shift,
+        )
+    }
+
+    /// The time dependent correction $1 + (\ldots) T$ common to all expansions, where
+    /// `beta_term` is the coefficient of $\alpha^2 / (FK)^{1-\beta}$.
+    fn time_correction(&self, beta_term: T) -> T {
+        let c = T::from_f64;
+        let (f, k) = self.shifted();
+        let (alpha, beta, rho, nu) = (self.alpha, self.beta, self.rho, self.nu);
+        let fk_beta = (f * k).powf((T::one() - beta) / c(2.0));
+        T::one()
+            + (beta_term * alpha.powi(2) / fk_beta.powi(2)
+                + rho * beta * nu * alpha / (c(4.0) * fk_beta)
+                + (c(2.0) - c(3.0) * rho.powi(2)) / c(24.0) * nu.powi(2))
+                * self.option_params.time_to_expiration
+    }
+
+    /// $\ln\left((\sqrt{1 - 2 \rho z + z^2} + z - \rho) / (1 - \rho)\right)$
+    fn x(&self, z: T) -> T {
+        let rho = self.rho;
+        let one = T::one();
+        (((one - T::from_f64(2.0) * rho * z + z.powi(2)).sqrt() + z - rho) / (one - rho)).ln()
+    }
+
+    /// $z / x(z)$, which is $1 - \rho z / 2$ to first order for $z \to 0$.
+    fn z_over_x(&self, z: T) -> T {
+        if z.abs().to_f64() < ATM_THRESHOLD {
+            T::one() - self.rho * z / T::from_f64(2.0)
+        } else {
+            z / self.x(z)
+        }
+    }
+
+    /// Hagan et al., Managing Smile Risk (2002), equation (2.17a).
+    fn hagan_lognormal_volatility(&self) -> T {
+        let c = T::from_f64;
+        let (f, k) = self.shifted();
+        let (alpha, beta, nu) = (self.alpha, self.beta, self.nu);
+        let one_beta = T::one() - beta;
+        let log_fk = (f / k).ln();
+        let fk_beta = (f * k).powf(one_beta / c(2.0));
+        let z = nu / alpha * fk_beta * log_fk;
+        let denominator = fk_beta
+            * (T::one()
+                + one_beta.powi(2) / c(24.0) * log_fk.powi(2)
+                + one_beta.powi(4) / c(1920.0) * log_fk.powi(4));
+        alpha / denominator * self.z_over_x(z) * self.time_correction(one_beta.powi(2) / c(24.0))
+    }
+
+    /// Hagan et al., Managing Smile Risk (2002), equation (B.69b).
+    fn hagan_normal_volatility(&self) -> T {
+        let c = T::from_f64;
+        let (f, k) = self.shifted();
+        let (alpha, beta, nu) = (self.alpha, self.beta, self.nu);
+        let one_beta = T::one() - beta;
+        let log_fk = (f / k).ln();
+        let fk_beta = (f * k).powf(one_beta / c(2.0));
+        let z = nu / alpha * fk_beta * log_fk;
+        let numerator = T::one() + log_fk.powi(2) / c(24.0) + log_fk.powi(4) / c(1920.0);
+        let denominator = T::one()
+            + one_beta.powi(2) / c(24.0) * log_fk.powi(2)
+            + one_beta.powi(4) / c(1920.0) * log_fk.powi(4);
+        alpha * (f * k).powf(beta / c(2.0)) * numerator / denominator
+            * self.z_over_x(z)
+            * self.time_correction(-beta * (c(2.0) - beta) / c(24.0))
+    }
+
+    /// Obloj, Fine-Tune Your Smile: Correction to Hagan et al. (2008), equation (3.5).
+    fn obloj_volatility(&self) -> T {
+        let (f, k) = self.shifted();
+        let (alpha, beta, nu) = (self.alpha, self.beta, self.nu);
+        let one_beta = T::one() - beta;
+        let log_fk = (f / k).ln();
+        let leading_term = if log_fk.abs().to_f64() < ATM_THRESHOLD {
+            // to first order in the log-moneyness, which keeps the skew at the money
+            let f_beta = f.powf(one_beta);
+            let skew = (one_beta - self.rho * nu * f_beta / alpha) / T::from_f64(2.0);
+            alpha / f_beta * (T::one() + skew * log_fk)
+        } else if nu.abs().to_f64() < ATM_THRESHOLD {
+            // the CEV limit
+            let integral = if beta == T::one() {
+                log_fk / alpha
+            } else {
+                (f.powf(one_beta) - k.powf(one_beta)) / (alpha * one_beta)
+            };
+            log_fk / integral
+        } else {
+            let z = if beta == T::one() {
+                nu * log_fk / alpha
+            } else {
+                nu * (f.powf(one_beta) - k.powf(one_beta)) / (alpha * one_beta)
+            };
+            nu * log_fk / self.x(z)
+        };
+        leading_term * self.time_correction(one_beta.powi(2) / T::from_f64(24.0))
+    }
+
+    /// The implied volatility of the expansion, lognormal for shifted forwards and strikes or
+    /// normal.
+    pub fn implied_volatility(&self) -> T {
+        match self.expansion {
+            SabrExpansion::HaganLognormal => self.hagan_lognormal_volatility(),
+            SabrExpansion::HaganNormal => self.hagan_normal_volatility(),
+            SabrExpansion::Obloj => self.obloj_volatility(),
+        }
+    }
+}
+
+/// European options in the SABR model, priced with [Black76] for the shifted forward and
+/// strike and the lognormal implied volatility, or with [Bachelier] and the normal implied
+/// volatility.
+/// https://en.wikipedia.org/wiki/SABR_volatility_model
+pub struct Sabr;
+
+impl Sabr {
+    fn sabr_price<T: Numeric>(sp: &SabrParameter<T>, exercise_type: &ExerciseType) -> T {
+        let dp = DerivativeParameter {
+            vola: sp.implied_volatility(),
+            ..sp.option_params
+        };
+        match sp.expansion {
+            SabrExpansion::HaganNormal => Bachelier::price(&dp, exercise_type),
+            SabrExpansion::HaganLognormal | SabrExpansion::Obloj => {
+                let (f, k) = sp.shifted();
+                let dp = DerivativeParameter {
+                    asset_price: f,
+                    strike: k,
+                    ..dp
+                };
+                Black76::price(&dp, exercise_type)
+            }
+        }
+    }
+}
+
+impl<T: Numeric> OptionPrice<T> for Sabr {
+    type Params = SabrParameter<T>;
+
+    fn call(sp: &SabrParameter<T>) -> T {
+        Self::sabr_price(sp, &ExerciseType::Call)
+    }
+
+    fn put(sp: &SabrParameter<T>) -> T {
+        Self::sabr_price(sp, &ExerciseType::Put)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::black_scholes::tests::assert_dual_derivatives_match_finite_differences;
+    use crate::common::numeric::Dual;
+    use assert_approx_eq::assert_approx_eq;
+
+    const EXPANSIONS: [SabrExpansion; 3] = [
+        SabrExpansion::HaganLognormal,
+        SabrExpansion::HaganNormal,
+        SabrExpansion::Obloj,
+    ];
+
+    fn smile_parameter(strike: f64) -> SabrParameter {
+        let dp = DerivativeParameter::new(0.03, strike, 5.0, 0.02, 0.0);
+        SabrParameter::new(dp, 0.035, 0.5, -0.3, 0.4)
+    }
+
+    #[test]
+    fn at_the_money() {
+        let sp = smile_parameter(0.03);
+        let (alpha, beta, rho, nu, t) = (0.035, 0.5, -0.3, 0.4, 5.0);
+        let f: f64 = 0.03;
+        let atm_vola = alpha / f.powf(1.0 - beta)
+            * (1.0
+                + ((1.0 - beta).powi(2) / 24.0 * alpha.powi(2) / f.powf(2.0 - 2.0 * beta)
+                    + rho * beta * nu * alpha / (4.0 * f.powf(1.0 - beta))
+                    + (2.0 - 3.0 * rho.powi(2)) / 24.0 * nu.powi(2))
+                    * t);
+        assert_approx_eq!(sp.implied_volatility(), atm_vola, 1e-12);
+        let sp = sp.with_expansion(SabrExpansion::Obloj);
+        assert_approx_eq!(sp.implied_volatility(), atm_vola, 1e-12);
+    }
+
+    #[test]
+    fn constant_volatility_limits() {
+        // lognormal dynamics for beta = 1 and normal dynamics for beta = 0 without vol of vol
+        for strike in [0.01, 0.03, 0.08] {
+            let sp = SabrParameter::new(smile_parameter(strike).option_params, 0.2, 1.0, 0.0, 0.0);
+            assert_approx_eq!(sp.implied_volatility(), 0.2, 1e-12);
+            let sp = sp.with_expansion(SabrExpansion::Obloj);
+            assert_approx_eq!(sp.implied_volatility(), 0.2, 1e-12);
+
+            let sp = SabrParameter::new(sp.option_params, 0.006, 0.0, 0.0, 0.0)
+                .with_expansion(SabrExpansion::HaganNormal);
+            assert_approx_eq!(sp.implied_volatility(), 0.006, 1e-12);
+        }
+    }
+
+    #[test]
+    fn expansions_agree() {
+        // all expansions approximate the same prices
+        for strike in [0.015, 0.025, 0.03, 0.04, 0.06] {
+            let sp = smile_parameter(strike);
+            for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+                let price = Sabr::price(&sp, &exercise_type);
+                for expansion in EXPANSIONS {
+                    let other = Sabr::price(&sp.with_expansion(expansion), &exercise_type);
+                    assert_approx_eq!(other, price, 5e-5);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn skew_and_smile() {
+        // the negative correlation skews the smile, the vol of vol lifts its wings
+        let volas: Vec<f64> = [0.015, 0.02, 0.025, 0.03, 0.04, 0.06]
+            .iter()
+            .map(|strike| smile_parameter(*strike).implied_volatility())
+            .collect();
+        assert!(volas[..5].windows(2).all(|w| w[0] > w[1]));
+        assert!(volas[5] > volas[4]);
+    }
+
+    #[test]
+    fn shifted_negative_rates() {
+        let dp = DerivativeParameter::new(-0.002, 0.0, 2.0, -0.005, 0.0);
+        for strike in [-0.006, -0.002, 0.0, 0.004] {
+            let dp = DerivativeParameter { strike, ..dp };
+            for expansion in EXPANSIONS {
+                let sp = SabrParameter::new(dp, 0.02, 0.5, -0.2, 0.3)
+                    .with_shift(0.03)
+                    .with_expansion(expansion);
+                assert!(sp.implied_volatility() > 0.0);
+
+                let call = Sabr::call(&sp);
+                let put = Sabr::put(&sp);
+                let discount_factor = (-dp.rfr * dp.time_to_expiration).exp();
+                assert!(call > discount_factor * (dp.asset_price - strike).max(0.0));
+                assert_approx_eq!(
+                    call - put,
+                    discount_factor * (dp.asset_price - strike),
+                    1e-12
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn automatic_differentiation() {
+        // at and off the money
+        for strike in [0.03, 0.045] {
+            let dp = smile_parameter(strike).option_params;
+            for expansion in EXPANSIONS {
+                for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+                    assert_dual_derivatives_match_finite_differences::<Sabr>(
+                        &dp,
+                        &exercise_type,
+                        |dp| {
+                            SabrParameter::new(dp, 0.035, 0.5, -0.3, 0.4).with_expansion(expansion)
+                        },
+                        |dp| {
+                            let c = Dual::constant;
+                            SabrParameter::new(dp, c(0.035), c(0.5), c(-0.3), c(0.4))
+                                .with_expansion(expansion)
+                        },
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,338 @@
+use crate::analytic::black_scholes::OptionPrice;
+use crate::common::models::{DerivativeParameter, ExerciseType};
+use crate::common::numeric::Numeric;
+
+/// Options on the spread $S_1 - S_2$ of two correlated assets following geometric Brownian motions.
+#[derive(Clone, Copy, Debug)]
+pub struct SpreadParameter<T: Numeric = f64> {
+    /// the first asset $S_1$, the strike and the market data shared by both assets
+    pub option_params: DerivativeParameter<T>,
+    /// the price of the second asset $S_2$
+    pub second_asset_price: T,
+    pub second_vola: T,
+    pub second_dividend_yield: T,
+    /// the correlation between the Brownian motions of both assets
+    pub correlation: T,
+}
+
+impl<T: Numeric> SpreadParameter<T> {
+    pub fn new(
+        option_params: DerivativeParameter<T>,
+        second_asset_price: T,
+        second_vola: T,
+        correlation: T,
+    ) -> Self {
+        Self {
+            option_params,
+            second_asset_price,
+            second_vola,
+            second_dividend_yield: T::zero(),
+            correlation,
+        }
+    }
+
+    pub fn with_second_dividend_yield(self, second_dividend_yield: T) -> Self {
+        Self {
+            second_dividend_yield,
+            ..self
+        }
+    }
+
+    /// The forward prices of both assets.
+    fn forwards(&self) -> (T, T) {
+        let dp = &self.option_params;
+        let t = dp.time_to_expiration;
+        (
+            dp.asset_price * (dp.cost_of_carry() * t).exp(),
+            self.second_asset_price * ((dp.rfr - self.second_dividend_yield) * t).exp(),
+        )
+    }
+
+    fn discount_factor(&self) -> T {
+        (-self.option_params.rfr * self.option_params.time_to_expiration).exp()
+    }
+
+    /// Put prices of spread options from their call prices.
+    fn put_call_parity(&self, call: T) -> T {
+        let (f1, f2) = self.forwards();
+        call - self.discount_factor() * (f1 - f2 - self.option_params.strike)
+    }
+}
+
+/// Exchange options paying $\max(S_1 - S_2, 0)$ (call) or $\max(S_2 - S_1, 0)$ (put) at expiration.
+/// The strike of the option parameters is not used.
+/// See Margrabe, The Value of an Option to Exchange One Asset for Another (1978).
+pub struct Margrabe;
+
+impl Margrabe {
+    fn exchange_price<T: Numeric>(sp: &SpreadParameter<T>, exercise_type: &ExerciseType) -> T {
+        let (s1, s2) = (sp.option_params.vola, sp.second_vola);
+        let sigma = (s1.powi(2) - T::from_f64(2.0) * sp.correlation * s1 * s2 + s2.powi(2)).sqrt();
+        let (f1, f2) = sp.forwards();
+        let (near, far) = match exercise_type {
+            ExerciseType::Call => (f1, f2),
+            ExerciseType::Put => (f2, f1),
+        };
+        sp.discount_factor() * lognormal_call(near, far, sigma, sp.option_params.time_to_expiration)
+    }
+}
+
+impl<T: Numeric> OptionPrice<T> for Margrabe {
+    type Params = SpreadParameter<T>;
+
+    fn call(sp: &SpreadParameter<T>) -> T {
+        Self::exchange_price(sp, &ExerciseType::Call)
+    }
+
+    fn put(sp: &SpreadParameter<T>) -> T {
+        Self::exchange_price(sp, &ExerciseType::Put)
+    }
+}
+
+/// Spread options paying $\max(S_1 - S_2 - K, 0)$ (call) or $\max(K - S_1 + S_2, 0)$ (put),
+/// approximating $S_2 + K$ by a lognormal asset. Exact for $K = 0$.
+/// See Kirk, Correlation in the Energy Markets (1995), and
+/// Haug, The Complete Guide to Option Pricing Formulas, chapter 5.12.1.
+pub struct Kirk;
+
+impl<T: Numeric> OptionPrice<T> for Kirk {
+    type Params = SpreadParameter<T>;
+
+    fn call(sp: &SpreadParameter<T>) -> T {
+        let (s1, s2) = (sp.option_params.vola, sp.second_vola);
+        let (f1, f2) = sp.forwards();
+        let shifted_f2 = f2 + sp.option_params.strike;
+        let weight = f2 / shifted_f2;
+        let sigma = (s1.powi(2) - T::from_f64(2.0) * sp.correlation * s1 * s2 * weight
+            + (s2 * weight).powi(2))
+        .sqrt();
+        sp.discount_factor()
+            * lognormal_call(f1, shifted_f2, sigma, sp.option_params.time_to_expiration)
+    }
+
+    fn put(sp: &SpreadParameter<T>) -> T {
+        sp.put_call_parity(Self::call(sp))
+    }
+}
+
+/// Spread options as [Kirk], but with separate exercise probabilities for both assets and
+/// the strike, which improves the accuracy considerably. Exact for $K = 0$.
+/// See Bjerksund and Stensland, Closed Form Spread Option Valuation (2014).
+pub struct BjerksundStenslandSpread;
+
+impl<T: Numeric> OptionPrice<T> for BjerksundStenslandSpread {
+    type Params = SpreadParameter<T>;
+
+    fn call(sp: &SpreadParameter<T>) -> T {
+        let dp = &sp.option_params;
+        let (s1, s2, rho, t) = (
+            dp.vola,
+            sp.second_vola,
+            sp.correlation,
+            dp.time_to_expiration,
+        );
+        let (f1, f2) = sp.forwards();
+        let a = f2 + dp.strike;
+        let b = f2 / a;
+        let two = T::from_f64(2.0);
+        let sigma_exp = (s1.powi(2) - two * b * rho * s1 * s2 + (b * s2).powi(2)).sqrt() * t.sqrt();
+        if sigma_exp == T::zero() {
+            // the intrinsic value of the forwards at expiry or without volatility
+            return sp.discount_factor() * (f1 - f2 - dp.strike).max(T::zero());
+        }
+        let d = |variance_term: T| ((f1 / a).ln() + variance_term * t) / sigma_exp;
+
+        let d1 = d(s1.powi(2) / two - b * rho * s1 * s2 + (b * s2).powi(2) / two);
+        let d2 = d(-s1.powi(2) / two + rho * s1 * s2 + (b.powi(2) / two - b) * s2.powi(2));
+        let d3 = d(-s1.powi(2) / two + (b * s2).powi(2) / two);
+        sp.discount_factor() * (f1 * d1.norm_cdf() - f2 * d2.norm_cdf() - dp.strike * d3.norm_cdf())
+    }
+
+    fn put(sp: &SpreadParameter<T>) -> T {
+        sp.put_call_parity(Self::call(sp))
+    }
+}
+
+/// The undiscounted Black call on the forward `near` struck at the lognormal `far`, which is
+/// the intrinsic value at expiry or without volatility.
+fn lognormal_call<T: Numeric>(near: T, far: T, sigma: T, t: T) -> T {
+    let sigma_exp = sigma * t.sqrt();
+    if sigma_exp == T::zero() {
+        return (near - far).max(T::zero());
+    }
+    let d1 = ((near / far).ln() + sigma_exp.powi(2) / T::from_f64(2.0)) / sigma_exp;
+    near * d1.norm_cdf() - far * (d1 - sigma_exp).norm_cdf()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::black_scholes::tests::assert_dual_derivatives_match_finite_differences;
+    use crate::common::numeric::Dual;
+    use crate::simulation::products::basket_option::MonteCarloEuropeanBasketOption;
+    use assert_approx_eq::assert_approx_eq;
+    use ndarray::{arr1, arr2};
+
+    /// Parameters with forward prices as asset prices, i.e. a zero cost of carry.
+    #[allow(clippy::too_many_arguments)]
+    fn futures_spread(
+        f1: f64,
+        f2: f64,
+        strike: f64,
+        t: f64,
+        r: f64,
+        s1: f64,
+        s2: f64,
+        rho: f64,
+    ) -> SpreadParameter {
+        let dp = DerivativeParameter::new(f1, strike, t, r, s1).with_dividend_yield(r);
+        SpreadParameter::new(dp, f2, s2, rho).with_second_dividend_yield(r)
+    }
+
+    #[test]
+    fn kirk_reference() {
+        // Haug, The Complete Guide to Option Pricing Formulas, example 5.12.1
+        let sp = futures_spread(28.0, 20.0, 7.0, 0.25, 0.05, 0.29, 0.36, 0.42);
+        assert_approx_eq!(Kirk::call(&sp), 2.1670, 1e-4);
+    }
+
+    #[test]
+    fn exact_integration() {
+        // exact prices by numerical integration over the second asset of the conditional
+        // Black-Scholes prices of the first asset
+        let references = [
+            (
+                futures_spread(28.0, 20.0, 7.0, 0.25, 0.05, 0.29, 0.36, 0.42),
+                2.168834,
+            ),
+            (
+                futures_spread(122.0, 120.0, 3.0, 0.5, 0.05, 0.2, 0.2, 0.8),
+                3.742900,
+            ),
+            (
+                futures_spread(110.0, 100.0, 20.0, 1.0, 0.05, 0.3, 0.25, -0.3),
+                13.511655,
+            ),
+            (
+                futures_spread(100.0, 96.0, 5.0, 1.0, 0.03, 0.25, 0.35, 0.5),
+                11.144858,
+            ),
+        ];
+        for (sp, price) in references {
+            assert_approx_eq!(Kirk::call(&sp), price, 4e-2);
+            assert_approx_eq!(BjerksundStenslandSpread::call(&sp), price, 1e-3);
+        }
+    }
+
+    #[test]
+    fn exchange_option() {
+        let sp = futures_spread(100.0, 100.0, 0.0, 1.0, 0.05, 0.2, 0.3, 0.4);
+        assert_approx_eq!(Margrabe::call(&sp), 10.829800, 1e-6);
+
+        let dp = DerivativeParameter::new(105.0, 0.0, 0.75, 0.04, 0.25).with_dividend_yield(0.01);
+        let sp = SpreadParameter::new(dp, 98.0, 0.3, -0.2).with_second_dividend_yield(0.03);
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            let exchange_price = Margrabe::price(&sp, &exercise_type);
+            assert_approx_eq!(Kirk::price(&sp, &exercise_type), exchange_price, 1e-10);
+            assert_approx_eq!(
+                BjerksundStenslandSpread::price(&sp, &exercise_type),
+                exchange_price,
+                1e-10
+            );
+        }
+    }
+
+    #[test]
+    fn basket_of_two_assets() {
+        // the basket 2 * S_1 / 2 - S_2 is the spread S_1 - S_2
+        let (s1, s2, rho) = (0.25, 0.2, 0.6);
+        let dp = DerivativeParameter::new(100.0, 5.0, 1.0, 0.03, s1);
+        let sp = SpreadParameter::new(dp, 95.0, s2, rho);
+        let cholesky_factor = arr2(&[[s1, 0.0], [rho * s2, s2 * (1.0 - rho.powi(2)).sqrt()]]);
+
+        let mc_option: MonteCarloEuropeanBasketOption<rand_hc::Hc128Rng> =
+            MonteCarloEuropeanBasketOption::new(
+                arr1(&[2.0, -1.0]),
+                arr1(&[50.0, 95.0]),
+                arr1(&[0.03, 0.03]),
+                cholesky_factor,
+                5.0,
+                1.0,
+            )
+            .and_then(|option| option.with_simulation(20_000, 50, 42))
+            .unwrap();
+        assert_approx_eq!(mc_option.call().unwrap(), Kirk::call(&sp), 0.25);
+        assert_approx_eq!(mc_option.put().unwrap(), Kirk::put(&sp), 0.25);
+    }
+
+    #[test]
+    fn expiry_and_zero_volatility() {
+        // at the money at expiry, in and out of the money without volatility
+        let expired = futures_spread(100.0, 95.0, 5.0, 0.0, 0.05, 0.3, 0.2, 0.5);
+        let no_vola = SpreadParameter {
+            option_params: DerivativeParameter {
+                strike: 3.0,
+                time_to_expiration: 1.0,
+                vola: 0.0,
+                ..expired.option_params
+            },
+            second_vola: 0.0,
+            ..expired
+        };
+        let discount_factor = (-0.05_f64).exp();
+        for (sp, call, put) in [(&expired, 0.0, 0.0), (&no_vola, 2.0 * discount_factor, 0.0)] {
+            for pricer_call in [Kirk::call, BjerksundStenslandSpread::call] {
+                assert_approx_eq!(pricer_call(sp), call, 1e-12);
+            }
+            for pricer_put in [Kirk::put, BjerksundStenslandSpread::put] {
+                assert_approx_eq!(pricer_put(sp), put, 1e-12);
+            }
+        }
+        let exchange = SpreadParameter {
+            second_asset_price: 100.0,
+            ..expired
+        };
+        assert_eq!(Margrabe::call(&exchange), 0.0);
+        assert_eq!(Margrabe::put(&exchange), 0.0);
+        assert_approx_eq!(Margrabe::call(&no_vola), 5.0 * discount_factor, 1e-12);
+
+        // perfectly correlated assets with equal volatilities exchange deterministically
+        let correlated = futures_spread(100.0, 95.0, 0.0, 1.0, 0.05, 0.3, 0.3, 1.0);
+        assert_approx_eq!(Margrabe::call(&correlated), 5.0 * discount_factor, 1e-12);
+    }
+
+    #[test]
+    fn nan_volatility() {
+        let sp = futures_spread(100.0, 95.0, 5.0, 1.0, 0.05, f64::NAN, 0.2, 0.5);
+        assert!(Margrabe::call(&sp).is_nan());
+        assert!(Kirk::call(&sp).is_nan());
+        assert!(BjerksundStenslandSpread::call(&sp).is_nan());
+    }
+
+    #[test]
+    fn automatic_differentiation() {
+        let dp = DerivativeParameter::new(28.0, 7.0, 0.25, 0.05, 0.29).with_dividend_yield(0.02);
+        let params =
+            |dp| SpreadParameter::new(dp, 20.0, 0.36, 0.42).with_second_dividend_yield(0.01);
+        let dual_params = |dp| {
+            let c = Dual::constant;
+            SpreadParameter::new(dp, c(20.0), c(0.36), c(0.42)).with_second_dividend_yield(c(0.01))
+        };
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            let et = &exercise_type;
+            assert_dual_derivatives_match_finite_differences::<Margrabe>(
+                &dp,
+                et,
+                params,
+                dual_params,
+            );
+            assert_dual_derivatives_match_finite_differences::<Kirk>(&dp, et, params, dual_params);
+            assert_dual_derivatives_match_finite_differences::<BjerksundStenslandSpread>(
+                &dp,
+                et,
+                params,
+                dual_params,
+            );
+        }
+    }
+}
@@ -1 +1,4 @@
 pub mod models;
+pub mod normal;
+pub mod numeric;
+pub(crate) mod quadrature;
@@ -1,39 +1,110 @@
-pub struct DerivativeParameter {
+use crate::common::numeric::Numeric;
+use crate::error::PricingError;
+
+/// The parameters of an option on a single asset, in f64 unless priced with another [Numeric].
+#[derive(Clone, Copy, Debug)]
+pub struct DerivativeParameter<T: Numeric = f64> {
     /// the asset's price at time t
-    pub asset_price: f64,
+    pub asset_price: T,
     /// the strike or exercise price of the asset
-    pub strike: f64,
+    pub strike: T,
     /// (T - t) in years, where T is the time of the option's expiration and t is the current time
-    pub time_to_expiration: f64,
+    pub time_to_expiration: T,
     /// the annualized risk-free interest rate
-    pub rfr: f64,
+    pub rfr: T,
     /// the annualized standard deviation of the stock's returns
-    pub vola: f64,
+    pub vola: T,
+    /// the annualized continuous dividend yield, or the foreign risk-free rate for FX options
+    pub dividend_yield: T,
 }
 
-impl DerivativeParameter {
-    pub fn new(
-        asset_price: f64,
-        strike: f64,
-        time_to_expiration: f64,
-        rfr: f64,
-        vola: f64,
-    ) -> Self {
+impl<T: Numeric> DerivativeParameter<T> {
+    pub fn new(asset_price: T, strike: T, time_to_expiration: T, rfr: T, vola: T) -> Self {
         Self {
             asset_price,
             strike,
             time_to_expiration,
             rfr,
             vola,
+            dividend_yield: T::zero(),
+        }
+    }
+
+    /// As [DerivativeParameter::new], but rejects parameters no model can price.
+    pub fn try_new(
+        asset_price: T,
+        strike: T,
+        time_to_expiration: T,
+        rfr: T,
+        vola: T,
+    ) -> Result<Self, PricingError> {
+        let dp = Self::new(asset_price, strike, time_to_expiration, rfr, vola);
+        dp.validate()?;
+        Ok(dp)
+    }
+
+    /// Checks that every parameter is finite and that the time to expiration and the volatility
+    /// are not negative. The signs of the asset price and the strike are left to the models, as
+    /// e.g. [crate::analytic::bachelier::Bachelier] prices negative forwards.
+    pub fn validate(&self) -> Result<(), PricingError> {
+        let finite = |name, value: T| {
+            let value = value.to_f64();
+            PricingError::check_parameter(name, value, value.is_finite())
+        };
+        let non_negative = |name, value: T| {
+            finite(name, value)?;
+            PricingError::check_parameter(name, value.to_f64(), value >= T::zero())
+        };
+        finite("asset_price", self.asset_price)?;
+        finite("strike", self.strike)?;
+        non_negative("time_to_expiration", self.time_to_expiration)?;
+        finite("rfr", self.rfr)?;
+        non_negative("vola", self.vola)?;
+        finite("dividend_yield", self.dividend_yield)
+    }
+
+    pub fn with_dividend_yield(self, dividend_yield: T) -> Self {
+        Self {
+            dividend_yield,
+            ..self
+        }
+    }
+
+    /// The cost of carry $b = r - q$ of holding the asset.
+    pub fn cost_of_carry(&self) -> T {
+        self.rfr - self.dividend_yield
+    }
+
+    /// Converts every parameter, e.g. into f32 or into constant [crate::common::numeric::Dual]
+    /// numbers.
+    pub fn map<U: Numeric>(self, f: impl Fn(T) -> U) -> DerivativeParameter<U> {
+        DerivativeParameter {
+            asset_price: f(self.asset_price),
+            strike: f(self.strike),
+            time_to_expiration: f(self.time_to_expiration),
+            rfr: f(self.rfr),
+            vola: f(self.vola),
+            dividend_yield: f(self.dividend_yield),
         }
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ExerciseType {
     Put,
     Call,
 }
 
+impl ExerciseType {
+    /// The sign $\phi$ which is 1 for calls and -1 for puts.
+    pub(crate) fn sign(&self) -> f64 {
+        match self {
+            ExerciseType::Call => 1.0,
+            ExerciseType::Put => -1.0,
+        }
+    }
+}
+
 pub type Underlying = String;
 
 pub enum Greek {
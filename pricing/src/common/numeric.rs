@@ -0,0 +1,317 @@
+use std::cmp::Ordering;
+use std::fmt::Debug;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::common::normal::{cdf, pdf};
+
+/// Mimic the key features of the floating point numbers used by the analytic pricers, such that
+/// they price with f32, f64 or [Dual] numbers alike.
+pub trait Numeric:
+    Copy
+    + Debug
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    fn from_f64(value: f64) -> Self;
+    /// The value as f64, dropping any derivatives.
+    fn to_f64(self) -> f64;
+    fn exp(self) -> Self;
+    fn ln(self) -> Self;
+    fn sqrt(self) -> Self;
+    fn powi(self, n: i32) -> Self;
+    fn powf(self, n: Self) -> Self;
+    fn abs(self) -> Self;
+    fn max(self, other: Self) -> Self;
+    fn min(self, other: Self) -> Self;
+    /// The standard normal distribution.
+    fn norm_cdf(self) -> Self;
+    /// The standard normal density.
+    fn norm_pdf(self) -> Self;
+
+    fn zero() -> Self {
+        Self::from_f64(0.0)
+    }
+
+    fn one() -> Self {
+        Self::from_f64(1.0)
+    }
+}
+
+macro_rules! impl_numeric {
+    ($impl_type:ty) => {
+        impl Numeric for $impl_type {
+            fn from_f64(value: f64) -> Self {
+                value as $impl_type
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn exp(self) -> Self {
+                <$impl_type>::exp(self)
+            }
+
+            fn ln(self) -> Self {
+                <$impl_type>::ln(self)
+            }
+
+            fn sqrt(self) -> Self {
+                <$impl_type>::sqrt(self)
+            }
+
+            fn powi(self, n: i32) -> Self {
+                <$impl_type>::powi(self, n)
+            }
+
+            fn powf(self, n: Self) -> Self {
+                <$impl_type>::powf(self, n)
+            }
+
+            fn abs(self) -> Self {
+                <$impl_type>::abs(self)
+            }
+
+            fn max(self, other: Self) -> Self {
+                <$impl_type>::max(self, other)
+            }
+
+            fn min(self, other: Self) -> Self {
+                <$impl_type>::min(self, other)
+            }
+
+            fn norm_cdf(self) -> Self {
+                cdf(self as f64) as $impl_type
+            }
+
+            fn norm_pdf(self) -> Self {
+                pdf(self as f64) as $impl_type
+            }
+        }
+    };
+}
+
+impl_numeric! { f32 }
+impl_numeric! { f64 }
+
+/// A dual number $a + b \epsilon$ with $\epsilon^2 = 0$ for forward-mode automatic
+/// differentiation: evaluating a function at $x + \epsilon$ yields $f(x) + f'(x) \epsilon$.
+/// Duals of duals carry the derivatives of higher order, e.g. the gamma.
+/// Duals are compared by their values, as the pricers only branch on the values.
+/// See https://en.wikipedia.org/wiki/Automatic_differentiation#Automatic_differentiation_using_dual_numbers
+#[derive(Clone, Copy, Debug)]
+pub struct Dual<T: Numeric = f64> {
+    pub value: T,
+    pub derivative: T,
+}
+
+impl<T: Numeric> Dual<T> {
+    pub fn new(value: T, derivative: T) -> Self {
+        Self { value, derivative }
+    }
+
+    /// The variable to differentiate with respect to.
+    pub fn variable(value: T) -> Self {
+        Self::new(value, T::one())
+    }
+
+    pub fn constant(value: T) -> Self {
+        Self::new(value, T::zero())
+    }
+
+    /// The chain rule for a function with the value $f(x)$ and the derivative $f'(x)$.
+    fn chain(self, value: T, derivative: T) -> Self {
+        Self::new(value, derivative * self.derivative)
+    }
+}
+
+impl<T: Numeric> PartialEq for Dual<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Numeric> PartialOrd for Dual<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+impl<T: Numeric> Add for Dual<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.value + rhs.value, self.derivative + rhs.derivative)
+    }
+}
+
+impl<T: Numeric> Sub for Dual<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.value - rhs.value, self.derivative - rhs.derivative)
+    }
+}
+
+impl<T: Numeric> Mul for Dual<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(
+            self.value * rhs.value,
+            self.derivative * rhs.value + self.value * rhs.derivative,
+        )
+    }
+}
+
+impl<T: Numeric> Div for Dual<T> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        let value = self.value / rhs.value;
+        Self::new(
+            value,
+            (self.derivative - value * rhs.derivative) / rhs.value,
+        )
+    }
+}
+
+impl<T: Numeric> Neg for Dual<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.value, -self.derivative)
+    }
+}
+
+impl<T: Numeric> Numeric for Dual<T> {
+    fn from_f64(value: f64) -> Self {
+        Self::constant(T::from_f64(value))
+    }
+
+    fn to_f64(self) -> f64 {
+        self.value.to_f64()
+    }
+
+    fn exp(self) -> Self {
+        let exp = self.value.exp();
+        self.chain(exp, exp)
+    }
+
+    fn ln(self) -> Self {
+        self.chain(self.value.ln(), T::one() / self.value)
+    }
+
+    fn sqrt(self) -> Self {
+        let sqrt = self.value.sqrt();
+        self.chain(sqrt, T::one() / (T::from_f64(2.0) * sqrt))
+    }
+
+    fn powi(self, n: i32) -> Self {
+        if n == 0 {
+            return Self::one();
+        }
+        let derivative = T::from_f64(n as f64) * self.value.powi(n - 1);
+        self.chain(self.value.powi(n), derivative)
+    }
+
+    /// $d(x^n) = n x^{n - 1} dx + x^n \ln(x) dn$, where the second term is taken for positive
+    /// bases only, such that a zero base with a constant exponent keeps a finite derivative.
+    fn powf(self, n: Self) -> Self {
+        let value = self.value.powf(n.value);
+        let mut derivative = n.value * self.value.powf(n.value - T::one()) * self.derivative;
+        if self.value > T::zero() {
+            derivative = derivative + value * self.value.ln() * n.derivative;
+        }
+        Self::new(value, derivative)
+    }
+
+    fn abs(self) -> Self {
+        if self.value < T::zero() {
+            -self
+        } else {
+            self
+        }
+    }
+
+    /// Ignores NaN as f64::max does.
+    fn max(self, other: Self) -> Self {
+        if self.value.max(other.value) == self.value {
+            self
+        } else {
+            other
+        }
+    }
+
+    /// Ignores NaN as f64::min does.
+    fn min(self, other: Self) -> Self {
+        if self.value.min(other.value) == self.value {
+            self
+        } else {
+            other
+        }
+    }
+
+    fn norm_cdf(self) -> Self {
+        self.chain(self.value.norm_cdf(), self.value.norm_pdf())
+    }
+
+    fn norm_pdf(self) -> Self {
+        let density = self.value.norm_pdf();
+        self.chain(density, -self.value * density)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn elementary_derivatives() {
+        let x = Dual::variable(0.7);
+        let cases: [(Dual, f64); 7] = [
+            (x.exp(), 0.7_f64.exp()),
+            (x.ln(), 1.0 / 0.7),
+            (x.sqrt(), 0.5 / 0.7_f64.sqrt()),
+            (x.powi(3), 3.0 * 0.49),
+            (x.powf(x), 0.7_f64.powf(0.7) * (0.7_f64.ln() + 1.0)),
+            (x.norm_cdf(), pdf(0.7)),
+            (x.norm_pdf(), -0.7 * pdf(0.7)),
+        ];
+        for (dual, derivative) in cases {
+            assert_approx_eq!(dual.derivative, derivative, 1e-15);
+        }
+        assert_eq!((-x).abs().derivative, 1.0);
+        assert_eq!(x.powi(0).derivative, 0.0);
+        assert_approx_eq!(
+            x.powf(Dual::from_f64(2.5)).derivative,
+            2.5 * 0.7_f64.powf(1.5),
+            1e-15
+        );
+    }
+
+    #[test]
+    fn arithmetic() {
+        // f(x) = (x^2 + 1) / (x - 3) has the derivative (x^2 - 6x - 1) / (x - 3)^2
+        let f = |x: Dual| (x * x + Dual::one()) / (x - Dual::from_f64(3.0));
+        let value = f(Dual::variable(1.5));
+        assert_approx_eq!(value.value, -3.25 / 1.5, 1e-15);
+        assert_approx_eq!(value.derivative, (2.25 - 9.0 - 1.0) / 2.25, 1e-15);
+    }
+
+    #[test]
+    fn second_order() {
+        // the second derivative of x e^x is (x + 2) e^x
+        let x = Dual::variable(Dual::variable(0.4));
+        let value = x * x.exp();
+        assert_approx_eq!(value.value.value, 0.4 * 0.4_f64.exp(), 1e-15);
+        assert_approx_eq!(value.derivative.value, 1.4 * 0.4_f64.exp(), 1e-15);
+        assert_approx_eq!(value.value.derivative, 1.4 * 0.4_f64.exp(), 1e-15);
+        assert_approx_eq!(value.derivative.derivative, 2.4 * 0.4_f64.exp(), 1e-15);
+    }
+}
@@ -0,0 +1,247 @@
+use std::f64::consts::PI;
+
+use crate::error::PricingError;
+
+/// Nodes and weights of a Gaussian quadrature rule.
+pub(crate) struct GaussRule {
+    pub(crate) nodes: Vec<f64>,
+    pub(crate) weights: Vec<f64>,
+}
+
+const NEWTON_TOLERANCE: f64 = 1e-14;
+const MAX_NEWTON_ITERATIONS: usize = 100;
+const MAX_BISECTIONS: usize = 30;
+/// The budget of function evaluations of the adaptive quadrature.
+const MAX_EVALUATIONS: usize = 200_000;
+
+/// Gauss-Legendre rule with `n` nodes on $[-1, 1]$, the roots of the Legendre polynomial
+/// $P_n$ found by Newton's method.
+pub(crate) fn gauss_legendre(n: usize) -> GaussRule {
+    let mut nodes = vec![0.0; n];
+    let mut weights = vec![0.0; n];
+    for i in 0..n.div_ceil(2) {
+        let mut x = (PI * (i as f64 + 0.75) / (n as f64 + 0.5)).cos();
+        let mut derivative = 1.0;
+        for _ in 0..MAX_NEWTON_ITERATIONS {
+            // the recurrence (j + 1) P_{j+1} = (2j + 1) x P_j - j P_{j-1}
+            let (mut p, mut p_prev) = (1.0, 0.0);
+            for j in 0..n {
+                let p_next = ((2 * j + 1) as f64 * x * p - j as f64 * p_prev) / (j + 1) as f64;
+                p_prev = p;
+                p = p_next;
+            }
+            derivative = n as f64 * (x * p - p_prev) / (x.powi(2) - 1.0);
+            let step = p / derivative;
+            x -= step;
+            if step.abs() < NEWTON_TOLERANCE {
+                break;
+            }
+        }
+        nodes[i] = -x;
+        nodes[n - 1 - i] = x;
+        weights[i] = 2.0 / ((1.0 - x.powi(2)) * derivative.powi(2));
+        weights[n - 1 - i] = weights[i];
+    }
+    GaussRule { nodes, weights }
+}
+
+/// Gauss-Laguerre rule with `n` nodes for $\int_0^\infty e^{-x} f(x) dx$, the roots of the
+/// Laguerre polynomial $L_n$ found by Newton's method.
+pub(crate) fn gauss_laguerre(n: usize) -> GaussRule {
+    let mut nodes = vec![0.0; n];
+    let mut weights = vec![0.0; n];
+    let mut x: f64 = 0.0;
+    for i in 0..n {
+        // initial guesses of Numerical Recipes, chapter 4.5
+        x = match i {
+            0 => 3.0 / (1.0 + 2.4 * n as f64),
+            1 => x + 15.0 / (1.0 + 2.5 * n as f64),
+            _ => {
+                let ai = (i - 1) as f64;
+                x + (1.0 + 2.55 * ai) / (1.9 * ai) * (x - nodes[i - 2])
+            }
+        };
+        let mut p_prev = 0.0;
+        for _ in 0..MAX_NEWTON_ITERATIONS {
+            // the recurrence (j + 1) L_{j+1} = (2j + 1 - x) L_j - j L_{j-1}
+            let mut p = 1.0;
+            p_prev = 0.0;
+            for j in 0..n {
+                let p_next = (((2 * j + 1) as f64 - x) * p - j as f64 * p_prev) / (j + 1) as f64;
+                p_prev = p;
+                p = p_next;
+            }
+            let derivative = n as f64 * (p - p_prev) / x;
+            let step = p / derivative;
+            x -= step;
+            if step.abs() < NEWTON_TOLERANCE * x.max(1.0) {
+                break;
+            }
+        }
+        nodes[i] = x;
+        // w_i = 1 / (x_i L_n'(x_i)^2) with L_n'(x_i) = -n L_{n-1}(x_i) / x_i
+        weights[i] = x / (n as f64 * p_prev).powi(2);
+    }
+    GaussRule { nodes, weights }
+}
+
+impl GaussRule {
+    /// Integrates the vector valued function over $[a, b]$, for rules on $[-1, 1]$.
+    fn integrate(&self, f: &impl Fn(f64) -> Vec<f64>, a: f64, b: f64) -> Vec<f64> {
+        let (center, half_width) = ((a + b) / 2.0, (b - a) / 2.0);
+        let mut sum: Vec<f64> = Vec::new();
+        for (node, weight) in self.nodes.iter().zip(&self.weights) {
+            let values = f(center + half_width * node);
+            if sum.is_empty() {
+                sum = vec![0.0; values.len()];
+            }
+            for (s, value) in sum.iter_mut().zip(values) {
+                *s += half_width * weight * value;
+            }
+        }
+        sum
+    }
+
+    /// Bisects the interval until the estimates of both halves agree with the whole one
+    /// within the absolute tolerance in every component, or until the evaluations exceed
+    /// their budget.
+    #[allow(clippy::too_many_arguments)]
+    fn integrate_adaptive(
+        &self,
+        f: &impl Fn(f64) -> Vec<f64>,
+        a: f64,
+        b: f64,
+        whole: Vec<f64>,
+        tolerance: f64,
+        depth: usize,
+        evaluations: &mut usize,
+    ) -> Vec<f64> {
+        let mid = (a + b) / 2.0;
+        let left = self.integrate(f, a, mid);
+        let right = self.integrate(f, mid, b);
+        *evaluations += 2 * self.nodes.len();
+        let halves: Vec<f64> = left.iter().zip(&right).map(|(l, r)| l + r).collect();
+        let error = halves
+            .iter()
+            .zip(&whole)
+            .map(|(h, w)| (h - w).abs())
+            .fold(0.0, f64::max);
+        if error < tolerance || depth >= MAX_BISECTIONS || *evaluations >= MAX_EVALUATIONS {
+            return halves;
+        }
+        let (tolerance, depth) = (tolerance / 2.0, depth + 1);
+        let left = self.integrate_adaptive(f, a, mid, left, tolerance, depth, evaluations);
+        let right = self.integrate_adaptive(f, mid, b, right, tolerance, depth, evaluations);
+        left.iter().zip(&right).map(|(l, r)| l + r).collect()
+    }
+}
+
+/// Integrates a vector valued function over $[0, \infty)$ by adaptive Gauss-Legendre
+/// quadrature on intervals of doubling width until their contribution falls below the
+/// tolerance, and by Gauss-Laguerre quadrature on the remaining tail, assuming the function
+/// decays at least exponentially. Fails for functions which decay too slowly to reach the
+/// tolerance within the budget of evaluations.
+pub(crate) fn integrate_semi_infinite(
+    f: impl Fn(f64) -> Vec<f64>,
+    tolerance: f64,
+) -> Result<Vec<f64>, PricingError> {
+    let legendre = gauss_legendre(16);
+    let (mut lower, mut width) = (0.0, 1.0);
+    let mut total: Vec<f64> = Vec::new();
+    let mut evaluations = 0;
+    loop {
+        let whole = legendre.integrate(&f, lower, lower + width);
+        evaluations += legendre.nodes.len();
+        let piece = legendre.integrate_adaptive(
+            &f,
+            lower,
+            lower + width,
+            whole,
+            tolerance,
+            0,
+            &mut evaluations,
+        );
+        let contribution = piece.iter().fold(0.0, |acc: f64, p| acc.max(p.abs()));
+        if total.is_empty() {
+            total = vec![0.0; piece.len()];
+        }
+        for (t, p) in total.iter_mut().zip(piece) {
+            *t += p;
+        }
+        lower += width;
+        if contribution < tolerance {
+            break;
+        }
+        if lower > 1e6 || evaluations >= MAX_EVALUATIONS {
+            return Err(PricingError::NoConvergence {
+                iterations: evaluations,
+            });
+        }
+        width *= 2.0;
+    }
+
+    // the tail with the substitution u = lower + width x
+    let laguerre = gauss_laguerre(16);
+    for (node, weight) in laguerre.nodes.iter().zip(&laguerre.weights) {
+        let values = f(lower + width * node);
+        for (t, value) in total.iter_mut().zip(values) {
+            *t += width * weight * node.exp() * value;
+        }
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn legendre_polynomials() {
+        let rule = gauss_legendre(10);
+        // exact for polynomials up to degree 19
+        let integral = |power: i32| -> f64 {
+            rule.nodes
+                .iter()
+                .zip(&rule.weights)
+                .map(|(x, w)| w * x.powi(power))
+                .sum()
+        };
+        assert_approx_eq!(integral(0), 2.0, 1e-13);
+        assert_approx_eq!(integral(18), 2.0 / 19.0, 1e-13);
+        assert_approx_eq!(integral(7), 0.0, 1e-13);
+    }
+
+    #[test]
+    fn laguerre_moments() {
+        let rule = gauss_laguerre(16);
+        // the moments of the exponential distribution are factorials
+        for (power, factorial) in [(0, 1.0), (1, 1.0), (5, 120.0), (10, 3628800.0)] {
+            let moment: f64 = rule
+                .nodes
+                .iter()
+                .zip(&rule.weights)
+                .map(|(x, w)| w * x.powi(power))
+                .sum();
+            assert_approx_eq!(moment / factorial, 1.0, 1e-10);
+        }
+    }
+
+    #[test]
+    fn oscillating_integrand() {
+        // the integral of cos(a u) e^{-u} over [0, infinity) is 1 / (1 + a^2)
+        let integral = integrate_semi_infinite(
+            |u| vec![(3.0 * u).cos() * (-u).exp(), (-u * u / 2.0).exp()],
+            1e-12,
+        )
+        .unwrap();
+        assert_approx_eq!(integral[0], 0.1, 1e-10);
+        assert_approx_eq!(integral[1], (PI / 2.0).sqrt(), 1e-10);
+    }
+
+    #[test]
+    fn slowly_decaying_integrand() {
+        let integral = integrate_semi_infinite(|u| vec![1.0 / (1.0 + u * u)], 1e-12);
+        assert!(matches!(integral, Err(PricingError::NoConvergence { .. })));
+    }
+}
@@ -0,0 +1,54 @@
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum PricingError {
+    #[error("price {price} is below the no-arbitrage lower bound {bound}")]
+    PriceBelowLowerBound { price: f64, bound: f64 },
+    #[error("price {price} is above the no-arbitrage upper bound {bound}")]
+    PriceAboveUpperBound { price: f64, bound: f64 },
+    #[error("no convergence after {iterations} iterations")]
+    NoConvergence { iterations: usize },
+    #[error("column {column} has {length} entries instead of {expected} or one")]
+    ColumnLengthMismatch {
+        column: &'static str,
+        length: usize,
+        expected: usize,
+    },
+    #[error("parameter {name} has the invalid value {value}")]
+    InvalidParameter { name: &'static str, value: f64 },
+    #[error("{name} has the shape {shape:?} instead of {expected:?}")]
+    DimensionMismatch {
+        name: &'static str,
+        shape: Vec<usize>,
+        expected: Vec<usize>,
+    },
+    #[error("the weights sum to {sum} instead of one")]
+    WeightsDoNotSumToOne { sum: f64 },
+}
+
+impl PricingError {
+    /// Rejects the parameter unless it is valid, where NaN is never valid.
+    pub(crate) fn check_parameter(name: &'static str, value: f64, valid: bool) -> Result<(), Self> {
+        if valid && !value.is_nan() {
+            Ok(())
+        } else {
+            Err(Self::InvalidParameter { name, value })
+        }
+    }
+
+    pub(crate) fn check_shape(
+        name: &'static str,
+        shape: &[usize],
+        expected: &[usize],
+    ) -> Result<(), Self> {
+        if shape == expected {
+            Ok(())
+        } else {
+            Err(Self::DimensionMismatch {
+                name,
+                shape: shape.to_vec(),
+                expected: expected.to_vec(),
+            })
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use crate::common::models::DerivativeParameter;
+use crate::lattice::{impl_lattice_price, Lattice, Tree};
+
+impl Tree {
+    /// The tree moving up by the factor u with the probability p and down by the factor d.
+    fn binomial(nr_steps: usize, dt: f64, (up, down, p): (f64, f64, f64)) -> Self {
+        Self {
+            nr_steps,
+            dt,
+            lowest_move: down,
+            node_ratio: up / down,
+            probabilities: vec![1.0 - p, p],
+        }
+    }
+}
+
+/// The binomial tree with $u = 1 / d = e^{\sigma \sqrt{\Delta t}}$ and the risk neutral
+/// probability. See Cox, Ross and Rubinstein, Option Pricing: A Simplified Approach (1979).
+pub struct CoxRossRubinstein;
+
+impl Lattice for CoxRossRubinstein {
+    fn tree(dp: &DerivativeParameter, nr_steps: usize) -> Tree {
+        let dt = dp.time_to_expiration / nr_steps as f64;
+        let up = (dp.vola * dt.sqrt()).exp();
+        let down = 1.0 / up;
+        let p = ((dp.cost_of_carry() * dt).exp() - down) / (up - down);
+        Tree::binomial(nr_steps, dt, (up, down, p))
+    }
+}
+
+impl_lattice_price!(CoxRossRubinstein);
+
+/// The binomial tree with equal probabilities, whose moves follow the drift of the log price
+/// $(b - \sigma^2 / 2) \Delta t \pm \sigma \sqrt{\Delta t}$.
+/// See Jarrow and Rudd, Option Pricing (1983).
+pub struct JarrowRudd;
+
+impl Lattice for JarrowRudd {
+    fn tree(dp: &DerivativeParameter, nr_steps: usize) -> Tree {
+        let dt = dp.time_to_expiration / nr_steps as f64;
+        let drift = (dp.cost_of_carry() - dp.vola.powi(2) / 2.0) * dt;
+        let up = (drift + dp.vola * dt.sqrt()).exp();
+        let down = (drift - dp.vola * dt.sqrt()).exp();
+        Tree::binomial(nr_steps, dt, (up, down, 0.5))
+    }
+}
+
+impl_lattice_price!(JarrowRudd);
+
+/// The binomial tree matching the first three moments of the log-normal distribution.
+/// See Tian, A Modified Lattice Approach to Option Pricing (1993).
+pub struct Tian;
+
+impl Lattice for Tian {
+    fn tree(dp: &DerivativeParameter, nr_steps: usize) -> Tree {
+        let dt = dp.time_to_expiration / nr_steps as f64;
+        let m = (dp.cost_of_carry() * dt).exp();
+        let v = (dp.vola.powi(2) * dt).exp();
+        let root = (v.powi(2) + 2.0 * v - 3.0).sqrt();
+        let up = m * v / 2.0 * (v + 1.0 + root);
+        let down = m * v / 2.0 * (v + 1.0 - root);
+        let p = (m - down) / (up - down);
+        Tree::binomial(nr_steps, dt, (up, down, p))
+    }
+}
+
+impl_lattice_price!(Tian);
+
+/// The binomial tree centered on the strike, whose probabilities invert the normal distribution
+/// of $d_1$ and $d_2$ by the method of Peizer and Pratt. The prices of European options converge
+/// with the square of the number of steps, which is rounded up to an odd number.
+/// See Leisen and Reimer, Binomial Models for Option Valuation - Examining and Improving
+/// Convergence (1996).
+pub struct LeisenReimer;
+
+impl LeisenReimer {
+    /// The Peizer-Pratt inversion of the normal distribution for n steps.
+    fn peizer_pratt(z: f64, n: f64) -> f64 {
+        let shrinked = z / (n + 1.0 / 3.0 + 0.1 / (n + 1.0));
+        0.5 + z.signum() * 0.5 * (1.0 - (-shrinked.powi(2) * (n + 1.0 / 6.0)).exp()).sqrt()
+    }
+}
+
+impl Lattice for LeisenReimer {
+    fn tree(dp: &DerivativeParameter, nr_steps: usize) -> Tree {
+        let nr_steps = nr_steps | 1;
+        let n = nr_steps as f64;
+        let dt = dp.time_to_expiration / n;
+        let sigma_exp = dp.vola * dp.time_to_expiration.sqrt();
+        let d1 = ((dp.asset_price / dp.strike).ln()
+            + (dp.cost_of_carry() + dp.vola.powi(2) / 2.0) * dp.time_to_expiration)
+            / sigma_exp;
+        let d2 = d1 - sigma_exp;
+
+        let p = Self::peizer_pratt(d2, n);
+        let carry = (dp.cost_of_carry() * dt).exp();
+        let up = carry * Self::peizer_pratt(d1, n) / p;
+        let down = (carry - p * up) / (1.0 - p);
+        Tree::binomial(nr_steps, dt, (up, down, p))
+    }
+}
+
+impl_lattice_price!(LeisenReimer);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::black_scholes::{BlackScholesMerton, OptionPrice};
+    use crate::common::models::ExerciseType;
+    use crate::lattice::{ExerciseStyle, LatticeParameter};
+    use assert_approx_eq::assert_approx_eq;
+
+    /// The American put of Longstaff and Schwartz with S = 36, K = 40, T = 1, r = 0.06 and
+    /// sigma = 0.2, valued by a binomial tree with 3000 steps.
+    const BINOMIAL_AMERICAN_PUT: f64 = 4.4867;
+
+    fn european_error<L: Lattice + OptionPrice<Params = LatticeParameter>>(
+        dp: DerivativeParameter,
+        nr_steps: usize,
+    ) -> f64 {
+        let lp = LatticeParameter::new(dp, nr_steps);
+        [ExerciseType::Call, ExerciseType::Put]
+            .iter()
+            .map(|exercise_type| {
+                (L::price(&lp, exercise_type) - BlackScholesMerton::price(&dp, exercise_type)).abs()
+            })
+            .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn european_convergence() {
+        for dp in [
+            DerivativeParameter::new(100.0, 110.0, 0.5, 0.05, 0.25),
+            DerivativeParameter::new(42.0, 40.0, 2.0, 0.03, 0.4).with_dividend_yield(0.02),
+        ] {
+            assert!(european_error::<CoxRossRubinstein>(dp, 1000) < 1e-2);
+            assert!(european_error::<JarrowRudd>(dp, 1000) < 1e-2);
+            assert!(european_error::<Tian>(dp, 1000) < 1e-2);
+            assert!(european_error::<LeisenReimer>(dp, 101) < 1e-4);
+        }
+    }
+
+    #[test]
+    fn leisen_reimer_convergence() {
+        // the error of the European option falls with the square of the number of steps
+        let dp = DerivativeParameter::new(100.0, 95.0, 1.0, 0.05, 0.3);
+        let coarse = european_error::<LeisenReimer>(dp, 51);
+        let fine = european_error::<LeisenReimer>(dp, 201);
+        assert!(fine < coarse / 10.0);
+        // even steps are rounded up
+        assert_eq!(
+            LeisenReimer::call(&LatticeParameter::new(dp, 50)),
+            LeisenReimer::call(&LatticeParameter::new(dp, 51))
+        );
+    }
+
+    #[test]
+    fn american_options() {
+        let dp = DerivativeParameter::new(36.0, 40.0, 1.0, 0.06, 0.2);
+        let lp = LatticeParameter::new(dp, 1001).with_exercise_style(ExerciseStyle::American);
+        assert_approx_eq!(CoxRossRubinstein::put(&lp), BINOMIAL_AMERICAN_PUT, 2e-3);
+        assert_approx_eq!(JarrowRudd::put(&lp), BINOMIAL_AMERICAN_PUT, 2e-3);
+        assert_approx_eq!(Tian::put(&lp), BINOMIAL_AMERICAN_PUT, 2e-3);
+        assert_approx_eq!(LeisenReimer::put(&lp), BINOMIAL_AMERICAN_PUT, 2e-3);
+
+        // the American call on an asset without dividends is never exercised early
+        assert_approx_eq!(LeisenReimer::call(&lp), BlackScholesMerton::call(&dp), 1e-4);
+    }
+}
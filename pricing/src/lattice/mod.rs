@@ -0,0 +1,483 @@
+//! Recombining binomial and trinomial trees for European, American and Bermudan options.
+//! Discrete cash dividends follow the escrowed dividend model: the tree models the asset price
+//! less the present value of the dividends until expiration, which is added back at each node.
+//! See Haug, The Complete Guide to Option Pricing Formulas, chapter 7, and Hull, Options,
+//! Futures, and Other Derivatives, chapter 21.
+pub mod binomial;
+pub mod trinomial;
+
+use crate::common::models::{DerivativeParameter, ExerciseType};
+
+/// When the holder may exercise, besides at the expiration.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExerciseStyle {
+    European,
+    American,
+    /// the times of the exercise dates in years, rounded to the steps of the tree
+    Bermudan(Vec<f64>),
+}
+
+impl ExerciseStyle {
+    /// Whether the holder may exercise at each of the time steps from today to the expiration.
+    pub(crate) fn exercise_steps(&self, nr_steps: usize, dt: f64) -> Vec<bool> {
+        let mut exercise_steps = match self {
+            ExerciseStyle::European => vec![false; nr_steps + 1],
+            ExerciseStyle::American => vec![true; nr_steps + 1],
+            ExerciseStyle::Bermudan(times) => {
+                let mut exercise_steps = vec![false; nr_steps + 1];
+                for time in times {
+                    let step = (time / dt).round();
+                    if step >= 0.0 {
+                        exercise_steps[(step as usize).min(nr_steps)] = true;
+                    }
+                }
+                exercise_steps
+            }
+        };
+        exercise_steps[nr_steps] = true;
+        exercise_steps
+    }
+}
+
+/// The relative bump of the asset price for the delta without volatility.
+const DETERMINISTIC_BUMP: f64 = 1e-6;
+
+/// A cash dividend paid at the time in years.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Dividend {
+    pub time: f64,
+    pub amount: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct LatticeParameter {
+    pub option_params: DerivativeParameter,
+    /// the number of time steps, at least two
+    pub nr_steps: usize,
+    pub exercise_style: ExerciseStyle,
+    pub dividends: Vec<Dividend>,
+}
+
+impl LatticeParameter {
+    /// European exercise without discrete dividends.
+    pub fn new(option_params: DerivativeParameter, nr_steps: usize) -> Self {
+        Self {
+            option_params,
+            nr_steps,
+            exercise_style: ExerciseStyle::European,
+            dividends: Vec::new(),
+        }
+    }
+
+    pub fn with_exercise_style(self, exercise_style: ExerciseStyle) -> Self {
+        Self {
+            exercise_style,
+            ..self
+        }
+    }
+
+    pub fn with_dividends(self, dividends: Vec<Dividend>) -> Self {
+        Self { dividends, ..self }
+    }
+
+    /// At expiry or without volatility the asset moves deterministically and the tree degenerates.
+    fn is_deterministic(&self) -> bool {
+        self.option_params.vola * self.option_params.time_to_expiration.sqrt() == 0.0
+    }
+
+    /// The present value at time t of the dividends paid after t until the expiration.
+    fn dividends_value(&self, t: f64) -> f64 {
+        let dp = &self.option_params;
+        self.dividends
+            .iter()
+            .filter(|dividend| dividend.time > t && dividend.time <= dp.time_to_expiration)
+            .map(|dividend| dividend.amount * (-dp.rfr * (dividend.time - t)).exp())
+            .sum()
+    }
+}
+
+/// The tree of the asset prices $S d^i g^j$ at the nodes $j$ of step $i$, where $d$ is the
+/// lowest move and $g$ the ratio between neighbouring nodes, and of the probabilities of moving
+/// from node $j$ to the nodes $j, j + 1, ...$ of the next step.
+#[derive(Clone, Debug)]
+pub struct Tree {
+    nr_steps: usize,
+    dt: f64,
+    lowest_move: f64,
+    node_ratio: f64,
+    probabilities: Vec<f64>,
+}
+
+impl Tree {
+    /// The number of nodes at the step.
+    fn nr_nodes(&self, step: usize) -> usize {
+        (self.probabilities.len() - 1) * step + 1
+    }
+
+    /// The step with three nodes, whose values give the gamma and the theta.
+    fn gamma_step(&self) -> usize {
+        2 / (self.probabilities.len() - 1)
+    }
+}
+
+/// The option values at the first steps of the tree, which give the price and the Greeks.
+struct Valuation {
+    tree: Tree,
+    asset_prices: Vec<Vec<f64>>,
+    values: Vec<Vec<f64>>,
+}
+
+/// The Greeks from the option values at the nodes of the first steps, see Hull, Options,
+/// Futures, and Other Derivatives, chapter 21.1.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LatticeGreeks {
+    /// dV/dS
+    pub delta: f64,
+    /// d^2V/dS^2
+    pub gamma: f64,
+    /// dV/dt
+    pub theta: f64,
+}
+
+pub trait Lattice {
+    /// The tree of the asset price of the parameters, e.g. less the dividends.
+    fn tree(dp: &DerivativeParameter, nr_steps: usize) -> Tree;
+
+    fn greeks(lp: &LatticeParameter, exercise_type: &ExerciseType) -> LatticeGreeks {
+        if lp.is_deterministic() {
+            return deterministic_greeks(lp, exercise_type);
+        }
+        let valuation = valuation::<Self>(lp, exercise_type);
+        let (s, v) = (&valuation.asset_prices, &valuation.values);
+        let (asset_price, price) = (s[0][0], v[0][0]);
+        let delta = (v[1][v[1].len() - 1] - v[1][0]) / (s[1][s[1].len() - 1] - s[1][0]);
+
+        let step = valuation.tree.gamma_step();
+        let (s, v) = (&s[step], &v[step]);
+        let upper_delta = (v[2] - v[1]) / (s[2] - s[1]);
+        let lower_delta = (v[1] - v[0]) / (s[1] - s[0]);
+        let gamma = (upper_delta - lower_delta) / ((s[2] - s[0]) / 2.0);
+
+        // the middle node deviates from the asset price in trees with drift, e.g. Leisen-Reimer
+        let shift = s[1] - asset_price;
+        let change = v[1] - price - delta * shift - gamma * shift.powi(2) / 2.0;
+        LatticeGreeks {
+            delta,
+            gamma,
+            theta: change / (step as f64 * valuation.tree.dt),
+        }
+    }
+}
+
+/// The intrinsic value at expiry, or without volatility the most valuable discounted exercise
+/// value along the forward of the asset at the exercise steps, which is the discounted intrinsic
+/// value of the forward for European options.
+fn deterministic_price(lp: &LatticeParameter, exercise_type: &ExerciseType) -> f64 {
+    let dp = &lp.option_params;
+    let nr_steps = lp.nr_steps.max(2);
+    let dt = dp.time_to_expiration / nr_steps as f64;
+    let escrowed_price = dp.asset_price - lp.dividends_value(0.0);
+    let phi = match exercise_type {
+        ExerciseType::Call => 1.0,
+        ExerciseType::Put => -1.0,
+    };
+    lp.exercise_style
+        .exercise_steps(nr_steps, dt)
+        .iter()
+        .enumerate()
+        .filter(|(_, exercise)| **exercise)
+        .map(|(step, _)| {
+            let t = step as f64 * dt;
+            let forward = escrowed_price * (dp.cost_of_carry() * t).exp() + lp.dividends_value(t);
+            (-dp.rfr * t).exp() * (phi * (forward - dp.strike)).max(0.0)
+        })
+        .fold(0.0, f64::max)
+}
+
+/// The value without volatility is piecewise linear in the asset price, hence has no gamma
+/// off the kinks; the delta and theta are taken by differences.
+fn deterministic_greeks(lp: &LatticeParameter, exercise_type: &ExerciseType) -> LatticeGreeks {
+    let dp = &lp.option_params;
+    let price = |asset_price: f64, time_to_expiration: f64| {
+        let option_params = DerivativeParameter {
+            asset_price,
+            time_to_expiration,
+            ..*dp
+        };
+        let lp = LatticeParameter {
+            option_params,
+            ..lp.clone()
+        };
+        deterministic_price(&lp, exercise_type)
+    };
+    let (s, t) = (dp.asset_price, dp.time_to_expiration);
+    let ds = DETERMINISTIC_BUMP * s;
+    let dt = DETERMINISTIC_BUMP * t.max(1.0);
+    LatticeGreeks {
+        delta: (price(s + ds, t) - price(s - ds, t)) / (2.0 * ds),
+        gamma: 0.0,
+        theta: (price(s, t) - price(s, t + dt)) / dt,
+    }
+}
+
+/// The price by the valuation on the lattice, or deterministically at expiry or without
+/// volatility.
+pub(crate) fn price<L: Lattice + ?Sized>(
+    lp: &LatticeParameter,
+    exercise_type: &ExerciseType,
+) -> f64 {
+    if lp.is_deterministic() {
+        deterministic_price(lp, exercise_type)
+    } else {
+        valuation::<L>(lp, exercise_type).values[0][0]
+    }
+}
+
+/// Discounts the expected option values backwards through the tree, exercising where allowed
+/// and worth it.
+fn valuation<L: Lattice + ?Sized>(
+    lp: &LatticeParameter,
+    exercise_type: &ExerciseType,
+) -> Valuation {
+    let dp = &lp.option_params;
+    let escrowed = DerivativeParameter {
+        asset_price: dp.asset_price - lp.dividends_value(0.0),
+        ..*dp
+    };
+    let tree = L::tree(&escrowed, lp.nr_steps.max(2));
+    let exercise_steps = lp.exercise_style.exercise_steps(tree.nr_steps, tree.dt);
+    let discount_factor = (-dp.rfr * tree.dt).exp();
+    let phi = match exercise_type {
+        ExerciseType::Call => 1.0,
+        ExerciseType::Put => -1.0,
+    };
+
+    let asset_prices = |step: usize| -> Vec<f64> {
+        let dividends_value = lp.dividends_value(step as f64 * tree.dt);
+        let lowest_price = escrowed.asset_price * tree.lowest_move.powi(step as i32);
+        (0..tree.nr_nodes(step))
+            .map(|j| lowest_price * tree.node_ratio.powi(j as i32) + dividends_value)
+            .collect()
+    };
+    let exercise_values = |step: usize| -> Vec<f64> {
+        asset_prices(step)
+            .iter()
+            .map(|s| (phi * (s - dp.strike)).max(0.0))
+            .collect()
+    };
+
+    let mut values = exercise_values(tree.nr_steps);
+    let mut first_values = Vec::new();
+    if tree.nr_steps <= 2 {
+        first_values.push(values.clone());
+    }
+    for step in (0..tree.nr_steps).rev() {
+        values = values
+            .windows(tree.probabilities.len())
+            .map(|next| {
+                let expected: f64 = next
+                    .iter()
+                    .zip(&tree.probabilities)
+                    .map(|(v, p)| v * p)
+                    .sum();
+                discount_factor * expected
+            })
+            .collect();
+        if exercise_steps[step] {
+            for (value, exercise_value) in values.iter_mut().zip(exercise_values(step)) {
+                *value = value.max(exercise_value);
+            }
+        }
+        if step <= 2 {
+            first_values.push(values.clone());
+        }
+    }
+    first_values.reverse();
+
+    Valuation {
+        asset_prices: (0..=2).map(asset_prices).collect(),
+        values: first_values,
+        tree,
+    }
+}
+
+/// Implements [OptionPrice] by the price on the lattice.
+macro_rules! impl_lattice_price {
+    ($lattice:ty) => {
+        impl crate::analytic::black_scholes::OptionPrice for $lattice {
+            type Params = crate::lattice::LatticeParameter;
+
+            fn call(lp: &crate::lattice::LatticeParameter) -> f64 {
+                crate::lattice::price::<Self>(lp, &crate::common::models::ExerciseType::Call)
+            }
+
+            fn put(lp: &crate::lattice::LatticeParameter) -> f64 {
+                crate::lattice::price::<Self>(lp, &crate::common::models::ExerciseType::Put)
+            }
+        }
+    };
+}
+pub(crate) use impl_lattice_price;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::black_scholes::{BlackScholesMerton, OptionGreeks, OptionPrice};
+    use crate::lattice::binomial::{CoxRossRubinstein, LeisenReimer};
+    use crate::lattice::trinomial::BoyleTrinomial;
+    use assert_approx_eq::assert_approx_eq;
+
+    /// The American put of Longstaff and Schwartz with S = 36, K = 40, T = 1, r = 0.06 and
+    /// sigma = 0.2, valued by a binomial tree with 3000 steps.
+    const BINOMIAL_AMERICAN_PUT: f64 = 4.4867;
+
+    /// The same put exercisable at the ten dates 0.1, 0.2, ..., 1.0, valued by a binomial tree.
+    const BINOMIAL_BERMUDAN_PUT: f64 = 4.4426;
+
+    #[test]
+    fn exercise_styles() {
+        let dp = DerivativeParameter::new(36.0, 40.0, 1.0, 0.06, 0.2);
+        let lp = LatticeParameter::new(dp, 1000);
+        let european = CoxRossRubinstein::put(&lp);
+        let american =
+            CoxRossRubinstein::put(&lp.clone().with_exercise_style(ExerciseStyle::American));
+        let bermudan = CoxRossRubinstein::put(&lp.with_exercise_style(ExerciseStyle::Bermudan(
+            (1..10).map(|k| k as f64 / 10.0).collect(),
+        )));
+        assert_approx_eq!(european, BlackScholesMerton::put(&dp), 2e-3);
+        assert_approx_eq!(american, BINOMIAL_AMERICAN_PUT, 2e-3);
+        assert_approx_eq!(bermudan, BINOMIAL_BERMUDAN_PUT, 2e-3);
+    }
+
+    #[test]
+    fn discrete_dividends() {
+        let dp = DerivativeParameter::new(100.0, 100.0, 1.0, 0.05, 0.3);
+        let dividend = Dividend {
+            time: 0.5,
+            amount: 4.0,
+        };
+        let lp = LatticeParameter::new(dp, 1001).with_dividends(vec![dividend]);
+
+        // the European option on the asset less the present value of the dividend
+        let escrowed = DerivativeParameter {
+            asset_price: 100.0 - 4.0 * (-0.05_f64 * 0.5).exp(),
+            ..dp
+        };
+        assert_approx_eq!(
+            LeisenReimer::call(&lp),
+            BlackScholesMerton::call(&escrowed),
+            1e-6
+        );
+        assert_approx_eq!(
+            LeisenReimer::put(&lp),
+            BlackScholesMerton::put(&escrowed),
+            1e-6
+        );
+
+        // exercising just before the dividend is worth it, where a binomial tree with 3000 steps
+        // gives 11.9883
+        let american = lp.with_exercise_style(ExerciseStyle::American);
+        assert_approx_eq!(CoxRossRubinstein::call(&american), 11.9883, 5e-3);
+        assert_approx_eq!(BoyleTrinomial::call(&american), 11.9883, 5e-3);
+        assert!(CoxRossRubinstein::call(&american) > BlackScholesMerton::call(&escrowed) + 0.05);
+
+        // dividends after the expiration are ignored
+        let late_dividend = LatticeParameter::new(dp, 101).with_dividends(vec![Dividend {
+            time: 1.5,
+            amount: 4.0,
+        }]);
+        assert_approx_eq!(
+            LeisenReimer::call(&late_dividend),
+            BlackScholesMerton::call(&dp),
+            1e-4
+        );
+    }
+
+    #[test]
+    fn greeks() {
+        let dp = DerivativeParameter::new(100.0, 95.0, 0.75, 0.04, 0.25).with_dividend_yield(0.01);
+        let lp = LatticeParameter::new(dp, 1001);
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            for greeks in [
+                LeisenReimer::greeks(&lp, &exercise_type),
+                BoyleTrinomial::greeks(&lp, &exercise_type),
+            ] {
+                assert_approx_eq!(
+                    greeks.delta,
+                    BlackScholesMerton::delta(&dp, &exercise_type),
+                    1e-3
+                );
+                assert_approx_eq!(
+                    greeks.gamma,
+                    BlackScholesMerton::gamma(&dp, &exercise_type),
+                    1e-4
+                );
+                assert_approx_eq!(
+                    greeks.theta,
+                    BlackScholesMerton::theta(&dp, &exercise_type),
+                    1e-2
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn two_steps() {
+        let dp = DerivativeParameter::new(100.0, 95.0, 0.75, 0.04, 0.25);
+        for nr_steps in [0, 1, 2] {
+            let lp = LatticeParameter::new(dp, nr_steps);
+            for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+                for greeks in [
+                    CoxRossRubinstein::greeks(&lp, &exercise_type),
+                    BoyleTrinomial::greeks(&lp, &exercise_type),
+                ] {
+                    assert!(greeks.delta.is_finite());
+                    assert!(greeks.gamma.is_finite());
+                    assert!(greeks.theta.is_finite());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn expiry_and_zero_volatility() {
+        fn prices(lp: &LatticeParameter, exercise_type: &ExerciseType) -> [f64; 3] {
+            [
+                CoxRossRubinstein::price(lp, exercise_type),
+                LeisenReimer::price(lp, exercise_type),
+                BoyleTrinomial::price(lp, exercise_type),
+            ]
+        }
+
+        // the intrinsic value at expiry
+        let dp = DerivativeParameter::new(100.0, 95.0, 0.0, 0.05, 0.25);
+        let lp = LatticeParameter::new(dp, 100);
+        assert_eq!(prices(&lp, &ExerciseType::Call), [5.0; 3]);
+        assert_eq!(prices(&lp, &ExerciseType::Put), [0.0; 3]);
+        let greeks = CoxRossRubinstein::greeks(&lp, &ExerciseType::Call);
+        assert_approx_eq!(greeks.delta, 1.0, 1e-8);
+        assert_eq!(greeks.gamma, 0.0);
+        // only the interest on the strike changes the value
+        assert_approx_eq!(greeks.theta, -0.05 * 95.0, 1e-3);
+
+        // the discounted intrinsic value of the forward without volatility
+        let dp = DerivativeParameter::new(100.0, 102.0, 1.0, 0.05, 0.0).with_dividend_yield(0.02);
+        let lp = LatticeParameter::new(dp, 100);
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            for price in prices(&lp, &exercise_type) {
+                assert_approx_eq!(price, BlackScholesMerton::price(&dp, &exercise_type), 1e-12);
+            }
+        }
+        let greeks = LeisenReimer::greeks(&lp, &ExerciseType::Call);
+        assert_approx_eq!(
+            greeks.delta,
+            BlackScholesMerton::delta(&dp, &ExerciseType::Call),
+            1e-6
+        );
+
+        // the American put without volatility is exercised at once if the asset grows
+        let dp = DerivativeParameter::new(90.0, 100.0, 1.0, 0.05, 0.0);
+        let lp = LatticeParameter::new(dp, 100).with_exercise_style(ExerciseStyle::American);
+        assert_eq!(prices(&lp, &ExerciseType::Put), [10.0; 3]);
+    }
+}
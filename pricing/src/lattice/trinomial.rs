@@ -0,0 +1,80 @@
+use crate::common::models::DerivativeParameter;
+use crate::lattice::{impl_lattice_price, Lattice, Tree};
+
+/// The trinomial tree moving up by $u = e^{\sigma \sqrt{2 \Delta t}}$, down by $d = 1 / u$ or
+/// staying, whose probabilities match the mean and the variance over two half steps.
+/// See Boyle, Option Valuation Using a Three-Jump Process (1986), and Haug, The Complete Guide to
+/// Option Pricing Formulas, chapter 7.2.
+pub struct BoyleTrinomial;
+
+impl Lattice for BoyleTrinomial {
+    fn tree(dp: &DerivativeParameter, nr_steps: usize) -> Tree {
+        let dt = dp.time_to_expiration / nr_steps as f64;
+        let half_up = (dp.vola * (dt / 2.0).sqrt()).exp();
+        let half_carry = (dp.cost_of_carry() * dt / 2.0).exp();
+        let p_up = ((half_carry - 1.0 / half_up) / (half_up - 1.0 / half_up)).powi(2);
+        let p_down = ((half_up - half_carry) / (half_up - 1.0 / half_up)).powi(2);
+        Tree {
+            nr_steps,
+            dt,
+            lowest_move: 1.0 / half_up.powi(2),
+            node_ratio: half_up.powi(2),
+            probabilities: vec![p_down, 1.0 - p_up - p_down, p_up],
+        }
+    }
+}
+
+impl_lattice_price!(BoyleTrinomial);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::black_scholes::{BlackScholesMerton, OptionPrice};
+    use crate::lattice::binomial::CoxRossRubinstein;
+    use crate::lattice::{ExerciseStyle, LatticeParameter};
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn european_options() {
+        let dp = DerivativeParameter::new(100.0, 110.0, 0.5, 0.05, 0.25).with_dividend_yield(0.02);
+        let lp = LatticeParameter::new(dp, 500);
+        assert_approx_eq!(
+            BoyleTrinomial::call(&lp),
+            BlackScholesMerton::call(&dp),
+            5e-3
+        );
+        assert_approx_eq!(BoyleTrinomial::put(&lp), BlackScholesMerton::put(&dp), 5e-3);
+    }
+
+    #[test]
+    fn probabilities() {
+        let dp = DerivativeParameter::new(100.0, 100.0, 1.0, 0.08, 0.3);
+        let tree = BoyleTrinomial::tree(&dp, 10);
+        assert_approx_eq!(tree.probabilities.iter().sum::<f64>(), 1.0, 1e-15);
+        assert!(tree.probabilities.iter().all(|p| *p > 0.0));
+        // the expected growth of the asset price over one step is the cost of carry
+        let growth: f64 = tree
+            .probabilities
+            .iter()
+            .enumerate()
+            .map(|(j, p)| p * tree.lowest_move * tree.node_ratio.powi(j as i32))
+            .sum();
+        assert_approx_eq!(growth, (0.08_f64 * 0.1).exp(), 1e-14);
+    }
+
+    #[test]
+    fn american_put() {
+        // a trinomial step resembles two binomial steps
+        let dp = DerivativeParameter::new(36.0, 40.0, 1.0, 0.06, 0.2);
+        let lp = LatticeParameter::new(dp, 500).with_exercise_style(ExerciseStyle::American);
+        let binomial = LatticeParameter {
+            nr_steps: 1000,
+            ..lp.clone()
+        };
+        assert_approx_eq!(
+            BoyleTrinomial::put(&lp),
+            CoxRossRubinstein::put(&binomial),
+            1e-3
+        );
+    }
+}
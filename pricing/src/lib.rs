@@ -1,5 +1,6 @@
 pub mod analytic;
 pub mod common;
+pub mod error;
+pub mod lattice;
+pub mod pde;
 pub mod simulation;
-
-extern crate ndarray;
@@ -0,0 +1,438 @@
+//! Finite difference solvers of the pricing PDEs, which march the option values on a grid of
+//! asset prices backwards from the payoff at the expiration to today. The prices are
+//! deterministic, and the Greeks follow from the values at the neighbouring nodes.
+//! See Tavella and Randall, Pricing Financial Instruments: The Finite Difference Method (2000),
+//! and Duffy, Finite Difference Methods in Financial Engineering (2006).
+pub mod one_factor;
+pub mod two_factor;
+
+use crate::common::models::DerivativeParameter;
+use crate::error::PricingError;
+
+/// The number of standard deviations of the log price the default grids span on either side.
+const NR_STD_DEVS: f64 = 5.0;
+/// The smallest half width of the default grids in the log price.
+const MIN_LOG_WIDTH: f64 = 0.1;
+
+/// The nodes of the asset prices, in increasing order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Grid {
+    nodes: Vec<f64>,
+}
+
+impl Grid {
+    /// Any increasing nodes, e.g. with the strike or a barrier on a node.
+    pub fn new(nodes: Vec<f64>) -> Self {
+        Self { nodes }
+    }
+
+    /// Nodes equally spaced in the logarithm of the asset price, which resolve the log-normal
+    /// distribution evenly.
+    pub fn log_spot(lower: f64, upper: f64, nr_nodes: usize) -> Result<Self, PricingError> {
+        PricingError::check_parameter("nr_nodes", nr_nodes as f64, nr_nodes >= 3)?;
+        PricingError::check_parameter("lower", lower, lower > 0.0)?;
+        PricingError::check_parameter("upper", upper, upper > lower && upper.is_finite())?;
+        let (log_lower, log_upper) = (lower.ln(), upper.ln());
+        let step = (log_upper - log_lower) / (nr_nodes - 1) as f64;
+        Ok(Self::new(
+            (0..nr_nodes)
+                .map(|i| (log_lower + i as f64 * step).exp())
+                .collect(),
+        ))
+    }
+
+    /// An odd number of nodes equally spaced in the log price around the asset price, covering
+    /// five standard deviations of the log price at the expiration and the strike.
+    pub fn log_spot_around(
+        dp: &DerivativeParameter,
+        nr_nodes: usize,
+    ) -> Result<Self, PricingError> {
+        PricingError::check_parameter("asset_price", dp.asset_price, dp.asset_price > 0.0)?;
+        PricingError::check_parameter("strike", dp.strike, dp.strike > 0.0)?;
+        let std_dev = dp.vola * dp.time_to_expiration.sqrt();
+        let drift = (dp.cost_of_carry() - dp.vola.powi(2) / 2.0) * dp.time_to_expiration;
+        let log_width = (NR_STD_DEVS * std_dev + drift.abs())
+            .max(2.0 * (dp.strike / dp.asset_price).ln().abs())
+            .max(MIN_LOG_WIDTH);
+        Self::log_spot(
+            dp.asset_price * (-log_width).exp(),
+            dp.asset_price * log_width.exp(),
+            nr_nodes.max(3) | 1,
+        )
+    }
+
+    /// Nodes concentrated around the center, e.g. the strike, by the sinh transformation
+    /// $S = c + \alpha \sinh(u)$ of equally spaced $u$, where a smaller width $\alpha$
+    /// concentrates more. See Tavella and Randall, chapter 5.3.
+    pub fn sinh(
+        lower: f64,
+        upper: f64,
+        center: f64,
+        width: f64,
+        nr_nodes: usize,
+    ) -> Result<Self, PricingError> {
+        PricingError::check_parameter("nr_nodes", nr_nodes as f64, nr_nodes >= 3)?;
+        PricingError::check_parameter("width", width, width > 0.0)?;
+        PricingError::check_parameter("upper", upper, upper > lower && upper.is_finite())?;
+        let u_lower = ((lower - center) / width).asinh();
+        let u_upper = ((upper - center) / width).asinh();
+        let step = (u_upper - u_lower) / (nr_nodes - 1) as f64;
+        let mut nodes: Vec<f64> = (0..nr_nodes)
+            .map(|i| center + width * (u_lower + i as f64 * step).sinh())
+            .collect();
+        // the bounds are hit exactly, e.g. a barrier
+        nodes[0] = lower;
+        nodes[nr_nodes - 1] = upper;
+        Ok(Self::new(nodes))
+    }
+
+    pub fn nodes(&self) -> &[f64] {
+        &self.nodes
+    }
+
+    /// Rejects less than three nodes or nodes which do not increase.
+    pub(crate) fn validate(&self) -> Result<(), PricingError> {
+        let nr_nodes = self.nodes.len();
+        PricingError::check_parameter("nr_nodes", nr_nodes as f64, nr_nodes >= 3)?;
+        for pair in self.nodes.windows(2) {
+            PricingError::check_parameter("grid node", pair[1], pair[1] > pair[0])?;
+        }
+        Ok(())
+    }
+}
+
+/// The weights of the lower, the same and the upper node in the central differences of the
+/// first and the second derivative at the inner node of the non-uniform nodes.
+pub(crate) fn central_weights(nodes: &[f64], i: usize) -> [[f64; 3]; 2] {
+    let (h_lower, h_upper) = (nodes[i] - nodes[i - 1], nodes[i + 1] - nodes[i]);
+    let h_sum = h_lower + h_upper;
+    [
+        [
+            -h_upper / (h_lower * h_sum),
+            (h_upper - h_lower) / (h_lower * h_upper),
+            h_lower / (h_upper * h_sum),
+        ],
+        [
+            2.0 / (h_lower * h_sum),
+            -2.0 / (h_lower * h_upper),
+            2.0 / (h_upper * h_sum),
+        ],
+    ]
+}
+
+/// The first of the three nodes nearest to x and their weights in the Lagrange polynomial, which
+/// interpolates the value and the first and the second derivative at x.
+pub(crate) fn lagrange_weights(nodes: &[f64], x: f64) -> (usize, [[f64; 3]; 3]) {
+    let nearest = nodes.partition_point(|node| *node < x);
+    let nearest =
+        if nearest > 0 && (nearest == nodes.len() || x - nodes[nearest - 1] < nodes[nearest] - x) {
+            nearest - 1
+        } else {
+            nearest
+        };
+    let first = nearest.clamp(1, nodes.len() - 2) - 1;
+    let x_nodes = [nodes[first], nodes[first + 1], nodes[first + 2]];
+    let mut weights = [[0.0; 3]; 3];
+    for j in 0..3 {
+        let (k, l) = ((j + 1) % 3, (j + 2) % 3);
+        let denominator = (x_nodes[j] - x_nodes[k]) * (x_nodes[j] - x_nodes[l]);
+        weights[0][j] = (x - x_nodes[k]) * (x - x_nodes[l]) / denominator;
+        weights[1][j] = (2.0 * x - x_nodes[k] - x_nodes[l]) / denominator;
+        weights[2][j] = 2.0 / denominator;
+    }
+    (first, weights)
+}
+
+/// The theta scheme of the time steps, which weighs the spatial operator at the new time by
+/// theta and at the old time by one minus theta.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Scheme {
+    /// theta = 0, first order and stable only for time steps below the squared node spacing over
+    /// the variance
+    Explicit,
+    /// theta = 1, first order and unconditionally stable
+    Implicit,
+    /// theta = 1/2, second order, where the first steps are replaced by two implicit half steps
+    /// each to damp the oscillations of the kinked payoff.
+    /// See Rannacher, Finite Element Solution of Diffusion Problems with Irregular Data (1984).
+    CrankNicolson { rannacher_steps: usize },
+}
+
+impl Scheme {
+    /// The sizes and thetas of the steps covering the time step of the size dt, counted from
+    /// the expiration.
+    pub(crate) fn sub_steps(&self, time_step: usize, dt: f64) -> Vec<(f64, f64)> {
+        match self {
+            Scheme::Explicit => vec![(dt, 0.0)],
+            Scheme::Implicit => vec![(dt, 1.0)],
+            Scheme::CrankNicolson { rannacher_steps } if time_step < *rannacher_steps => {
+                vec![(dt / 2.0, 1.0); 2]
+            }
+            Scheme::CrankNicolson { .. } => vec![(dt, 0.5)],
+        }
+    }
+}
+
+/// How the values are kept above the exercise values where the holder may exercise.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EarlyExercise {
+    /// projected successive over-relaxation with the relaxation parameter in (0, 2), which
+    /// iterates until the values change by less than the tolerance.
+    /// See Cryer, The Solution of a Quadratic Programming Problem Using Systematic Overrelaxation
+    /// (1971).
+    ProjectedSor {
+        relaxation: f64,
+        tolerance: f64,
+        max_iterations: usize,
+    },
+    /// the direct solution projected during the back substitution, which needs a single exercise
+    /// boundary such as the one of calls and puts.
+    /// See Brennan and Schwartz, The Valuation of American Put Options (1977).
+    BrennanSchwartz,
+}
+
+/// The tridiagonal matrix of the lower, main and upper diagonals, where the first entry of the
+/// lower and the last entry of the upper diagonal are ignored.
+#[derive(Clone, Debug)]
+pub(crate) struct Tridiagonal {
+    pub lower: Vec<f64>,
+    pub diag: Vec<f64>,
+    pub upper: Vec<f64>,
+}
+
+impl Tridiagonal {
+    /// The matrix of the rows in the reverse order.
+    fn reversed(&self) -> Self {
+        Self {
+            lower: self.upper.iter().rev().copied().collect(),
+            diag: self.diag.iter().rev().copied().collect(),
+            upper: self.lower.iter().rev().copied().collect(),
+        }
+    }
+
+    /// Solves the system by the Thomas algorithm.
+    pub fn solve(&self, rhs: &[f64]) -> Vec<f64> {
+        self.solve_projected(rhs, None)
+    }
+
+    /// The Thomas algorithm, which projects the solution onto the obstacle during the back
+    /// substitution from the last row.
+    fn solve_projected(&self, rhs: &[f64], obstacle: Option<&[f64]>) -> Vec<f64> {
+        let n = self.diag.len();
+        let mut upper = vec![0.0; n];
+        let mut solution = vec![0.0; n];
+        upper[0] = self.upper[0] / self.diag[0];
+        solution[0] = rhs[0] / self.diag[0];
+        for i in 1..n {
+            let pivot = self.diag[i] - self.lower[i] * upper[i - 1];
+            upper[i] = self.upper[i] / pivot;
+            solution[i] = (rhs[i] - self.lower[i] * solution[i - 1]) / pivot;
+        }
+        for i in (0..n).rev() {
+            if i + 1 < n {
+                solution[i] -= upper[i] * solution[i + 1];
+            }
+            if let Some(obstacle) = obstacle {
+                solution[i] = solution[i].max(obstacle[i]);
+            }
+        }
+        solution
+    }
+
+    /// The Brennan-Schwartz solution of the system above the obstacle, which is exact if the
+    /// obstacle is binding on the nodes below (exercise at low asset prices, e.g. puts) or above
+    /// (e.g. calls) a single node.
+    pub fn solve_brennan_schwartz(
+        &self,
+        rhs: &[f64],
+        obstacle: &[f64],
+        exercise_below: bool,
+    ) -> Vec<f64> {
+        if exercise_below {
+            // the back substitution has to end at the high asset prices
+            let rhs: Vec<f64> = rhs.iter().rev().copied().collect();
+            let obstacle: Vec<f64> = obstacle.iter().rev().copied().collect();
+            let mut solution = self.reversed().solve_projected(&rhs, Some(&obstacle));
+            solution.reverse();
+            solution
+        } else {
+            self.solve_projected(rhs, Some(obstacle))
+        }
+    }
+
+    /// The projected successive over-relaxation started at the initial guess.
+    pub fn solve_projected_sor(
+        &self,
+        rhs: &[f64],
+        obstacle: &[f64],
+        initial: Vec<f64>,
+        (relaxation, tolerance, max_iterations): (f64, f64, usize),
+    ) -> Result<Vec<f64>, PricingError> {
+        let n = self.diag.len();
+        let mut solution = initial;
+        for _ in 0..max_iterations {
+            let mut max_change: f64 = 0.0;
+            for i in 0..n {
+                let mut residual = rhs[i] - self.diag[i] * solution[i];
+                if i > 0 {
+                    residual -= self.lower[i] * solution[i - 1];
+                }
+                if i + 1 < n {
+                    residual -= self.upper[i] * solution[i + 1];
+                }
+                let value = (solution[i] + relaxation * residual / self.diag[i]).max(obstacle[i]);
+                max_change = max_change.max((value - solution[i]).abs());
+                solution[i] = value;
+            }
+            if max_change < tolerance {
+                return Ok(solution);
+            }
+        }
+        Err(PricingError::NoConvergence {
+            iterations: max_iterations,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    fn laplacian(n: usize) -> Tridiagonal {
+        Tridiagonal {
+            lower: vec![-1.0; n],
+            diag: vec![2.5; n],
+            upper: vec![-1.0; n],
+        }
+    }
+
+    fn multiply(matrix: &Tridiagonal, x: &[f64]) -> Vec<f64> {
+        let n = x.len();
+        (0..n)
+            .map(|i| {
+                let mut value = matrix.diag[i] * x[i];
+                if i > 0 {
+                    value += matrix.lower[i] * x[i - 1];
+                }
+                if i + 1 < n {
+                    value += matrix.upper[i] * x[i + 1];
+                }
+                value
+            })
+            .collect()
+    }
+
+    #[test]
+    fn grids() {
+        let grid = Grid::log_spot(50.0, 200.0, 3).unwrap();
+        assert_approx_eq!(grid.nodes()[1], 100.0, 1e-12);
+
+        let grid = Grid::sinh(0.0, 300.0, 100.0, 10.0, 101).unwrap();
+        assert_eq!((grid.nodes()[0], grid.nodes()[100]), (0.0, 300.0));
+        assert!(grid.validate().is_ok());
+        // the spacing is smallest at the center
+        let spacings: Vec<f64> = grid.nodes().windows(2).map(|w| w[1] - w[0]).collect();
+        let (finest, _) = spacings
+            .iter()
+            .enumerate()
+            .min_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap();
+        assert!((grid.nodes()[finest] - 100.0).abs() < 2.0 * spacings[finest]);
+        assert!(spacings[0].min(spacings[99]) > 5.0 * spacings[finest]);
+
+        assert_eq!(
+            Grid::new(vec![1.0, 2.0, 2.0]).validate(),
+            Err(PricingError::InvalidParameter {
+                name: "grid node",
+                value: 2.0
+            })
+        );
+        assert!(Grid::new(vec![1.0, 2.0]).validate().is_err());
+
+        // too few nodes or a degenerate range are rejected before building
+        for nr_nodes in [0, 1, 2] {
+            assert_eq!(
+                Grid::log_spot(50.0, 200.0, nr_nodes),
+                Err(PricingError::InvalidParameter {
+                    name: "nr_nodes",
+                    value: nr_nodes as f64
+                })
+            );
+            assert!(Grid::sinh(0.0, 300.0, 100.0, 10.0, nr_nodes).is_err());
+        }
+        assert!(Grid::log_spot(0.0, 200.0, 11).is_err());
+        assert!(Grid::sinh(0.0, 300.0, 100.0, 0.0, 11).is_err());
+        let dp = DerivativeParameter::new(100.0, 0.0, 1.0, 0.05, 0.2);
+        assert_eq!(
+            Grid::log_spot_around(&dp, 101),
+            Err(PricingError::InvalidParameter {
+                name: "strike",
+                value: 0.0
+            })
+        );
+    }
+
+    #[test]
+    fn finite_differences() {
+        // exact for quadratic functions on non-uniform nodes
+        let nodes = [1.0, 1.5, 3.0, 3.2];
+        let f = |x: f64| 2.0 * x * x - x + 3.0;
+        let [first, second] = central_weights(&nodes, 2);
+        let values = [f(nodes[1]), f(nodes[2]), f(nodes[3])];
+        let apply = |weights: [f64; 3]| (0..3).map(|j| weights[j] * values[j]).sum::<f64>();
+        assert_approx_eq!(apply(first), 4.0 * 3.0 - 1.0, 1e-12);
+        assert_approx_eq!(apply(second), 4.0, 1e-12);
+
+        let (first_node, weights) = lagrange_weights(&nodes, 1.4);
+        assert_eq!(first_node, 0);
+        let interpolate = |derivative: usize| -> f64 {
+            (0..3)
+                .map(|j| weights[derivative][j] * f(nodes[first_node + j]))
+                .sum()
+        };
+        assert_approx_eq!(interpolate(0), f(1.4), 1e-12);
+        assert_approx_eq!(interpolate(1), 4.0 * 1.4 - 1.0, 1e-12);
+        assert_approx_eq!(interpolate(2), 4.0, 1e-12);
+        assert_eq!(lagrange_weights(&nodes, 3.1).0, 1);
+    }
+
+    #[test]
+    fn rannacher_steps() {
+        let scheme = Scheme::CrankNicolson { rannacher_steps: 2 };
+        let steps: Vec<Vec<(f64, f64)>> = (0..10).map(|k| scheme.sub_steps(k, 0.1)).collect();
+        assert_eq!(steps[1], vec![(0.05, 1.0), (0.05, 1.0)]);
+        assert_eq!(steps[2], vec![(0.1, 0.5)]);
+        assert_eq!(steps.iter().flatten().count(), 12);
+    }
+
+    #[test]
+    fn tridiagonal_solvers() {
+        let matrix = laplacian(20);
+        let rhs: Vec<f64> = (0..20).map(|i| (i as f64 - 10.0) / 10.0).collect();
+        let solution = matrix.solve(&rhs);
+        for (value, expected) in multiply(&matrix, &solution).iter().zip(&rhs) {
+            assert_approx_eq!(value, expected, 1e-12);
+        }
+
+        // the linear complementarity problem, whose obstacle binds at the low nodes
+        let obstacle: Vec<f64> = (0..20).map(|i| 1.0 - i as f64 / 10.0).collect();
+        let brennan_schwartz = matrix.solve_brennan_schwartz(&rhs, &obstacle, true);
+        let psor = matrix
+            .solve_projected_sor(&rhs, &obstacle, obstacle.clone(), (1.5, 1e-14, 1000))
+            .unwrap();
+        let residuals = multiply(&matrix, &psor);
+        for i in 0..20 {
+            assert_approx_eq!(brennan_schwartz[i], psor[i], 1e-10);
+            assert!(psor[i] >= obstacle[i]);
+            // either the obstacle binds or the equation holds
+            assert!(psor[i] - obstacle[i] < 1e-10 || (residuals[i] - rhs[i]).abs() < 1e-10);
+        }
+        assert!(psor[0] == obstacle[0] && psor[19] > obstacle[19]);
+
+        assert_eq!(
+            matrix.solve_projected_sor(&rhs, &obstacle, obstacle.clone(), (1.5, 1e-14, 2)),
+            Err(PricingError::NoConvergence { iterations: 2 })
+        );
+    }
+}
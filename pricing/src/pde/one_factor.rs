@@ -0,0 +1,679 @@
+//! The Black-Scholes PDE
+//! $\partial V / \partial \tau = \frac{1}{2} \sigma^2 S^2 V_{SS} + b S V_S - r V$
+//! in the time to expiration $\tau$, discretized by central differences on the nodes of the grid
+//! and by the theta scheme in time.
+use crate::common::models::{DerivativeParameter, ExerciseType};
+use crate::error::PricingError;
+use crate::lattice::ExerciseStyle;
+use crate::pde::{central_weights, lagrange_weights, EarlyExercise, Grid, Scheme, Tridiagonal};
+
+/// The condition at the lowest or the highest node of the grid.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Boundary {
+    /// the option value, e.g. zero far out of the money or the rebate at a barrier
+    Dirichlet(f64),
+    /// the first derivative dV/dS, e.g. one for a call far in the money
+    Neumann(f64),
+}
+
+/// A barrier which extinguishes the option when the asset price reaches it, paying the rebate
+/// at that time. The grid is cut at the barrier, which has to lie within the grid.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum KnockOut {
+    Down { barrier: f64, rebate: f64 },
+    Up { barrier: f64, rebate: f64 },
+}
+
+impl KnockOut {
+    pub fn rebate(&self) -> f64 {
+        match self {
+            KnockOut::Down { rebate, .. } | KnockOut::Up { rebate, .. } => *rebate,
+        }
+    }
+
+    /// The nodes within the barrier, which becomes the lowest or the highest node.
+    pub(crate) fn cut(&self, nodes: &[f64]) -> Result<Vec<f64>, PricingError> {
+        let (lowest, highest) = (nodes[0], nodes[nodes.len() - 1]);
+        Ok(match *self {
+            KnockOut::Down { barrier, .. } => {
+                PricingError::check_parameter(
+                    "barrier",
+                    barrier,
+                    barrier > lowest && barrier < highest,
+                )?;
+                let mut cut_nodes = vec![barrier];
+                cut_nodes.extend(nodes.iter().filter(|s| **s > barrier));
+                cut_nodes
+            }
+            KnockOut::Up { barrier, .. } => {
+                PricingError::check_parameter(
+                    "barrier",
+                    barrier,
+                    barrier > lowest && barrier < highest,
+                )?;
+                let mut cut_nodes: Vec<f64> =
+                    nodes.iter().copied().filter(|s| *s < barrier).collect();
+                cut_nodes.push(barrier);
+                cut_nodes
+            }
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PdeParameter {
+    pub option_params: DerivativeParameter,
+    pub grid: Grid,
+    /// the number of time steps of the size of the time to expiration over it, at least one
+    pub nr_time_steps: usize,
+    pub scheme: Scheme,
+    pub exercise_style: ExerciseStyle,
+    pub early_exercise: EarlyExercise,
+    pub knock_out: Option<KnockOut>,
+    /// the conditions at the lowest and the highest node, by default zero out of the money and
+    /// the slope of the discounted forward over the remaining time to expiration in the money
+    pub boundaries: Option<(Boundary, Boundary)>,
+}
+
+impl PdeParameter {
+    /// European exercise by Crank-Nicolson with two Rannacher steps on an odd number of nodes,
+    /// equally spaced in the log price around the asset price over five standard deviations and
+    /// the strike.
+    pub fn new(
+        option_params: DerivativeParameter,
+        nr_nodes: usize,
+        nr_time_steps: usize,
+    ) -> Result<Self, PricingError> {
+        let grid = Grid::log_spot_around(&option_params, nr_nodes)?;
+        Ok(Self {
+            option_params,
+            grid,
+            nr_time_steps,
+            scheme: Scheme::CrankNicolson { rannacher_steps: 2 },
+            exercise_style: ExerciseStyle::European,
+            early_exercise: EarlyExercise::BrennanSchwartz,
+            knock_out: None,
+            boundaries: None,
+        })
+    }
+
+    pub fn with_grid(self, grid: Grid) -> Self {
+        Self { grid, ..self }
+    }
+
+    pub fn with_scheme(self, scheme: Scheme) -> Self {
+        Self { scheme, ..self }
+    }
+
+    pub fn with_exercise_style(self, exercise_style: ExerciseStyle) -> Self {
+        Self {
+            exercise_style,
+            ..self
+        }
+    }
+
+    pub fn with_early_exercise(self, early_exercise: EarlyExercise) -> Self {
+        Self {
+            early_exercise,
+            ..self
+        }
+    }
+
+    pub fn with_knock_out(self, knock_out: KnockOut) -> Self {
+        Self {
+            knock_out: Some(knock_out),
+            ..self
+        }
+    }
+
+    pub fn with_boundaries(self, lower: Boundary, upper: Boundary) -> Self {
+        Self {
+            boundaries: Some((lower, upper)),
+            ..self
+        }
+    }
+
+    /// The nodes cut at the barrier.
+    fn nodes(&self) -> Result<Vec<f64>, PricingError> {
+        let nodes = self.grid.nodes();
+        match self.knock_out {
+            None => Ok(nodes.to_vec()),
+            Some(knock_out) => knock_out.cut(nodes),
+        }
+    }
+
+    /// The conditions at the lowest and the highest node with the time to expiration tau left.
+    fn boundaries(&self, exercise_type: &ExerciseType, tau: f64) -> (Boundary, Boundary) {
+        let dp = &self.option_params;
+        let (lower, upper) = self.boundaries.unwrap_or_else(|| {
+            let slope = match self.exercise_style {
+                ExerciseStyle::European => ((dp.cost_of_carry() - dp.rfr) * tau).exp(),
+                _ => 1.0,
+            };
+            match exercise_type {
+                ExerciseType::Call => (Boundary::Dirichlet(0.0), Boundary::Neumann(slope)),
+                ExerciseType::Put => (Boundary::Neumann(-slope), Boundary::Dirichlet(0.0)),
+            }
+        });
+        match self.knock_out {
+            None => (lower, upper),
+            Some(KnockOut::Down { rebate, .. }) => (Boundary::Dirichlet(rebate), upper),
+            Some(KnockOut::Up { rebate, .. }) => (lower, Boundary::Dirichlet(rebate)),
+        }
+    }
+}
+
+/// The option values and their time derivatives on the nodes today, which give the price and
+/// the Greeks at any asset price within the grid by quadratic interpolation.
+#[derive(Clone, Debug)]
+pub struct PdeSolution {
+    pub asset_prices: Vec<f64>,
+    pub values: Vec<f64>,
+    thetas: Vec<f64>,
+}
+
+impl PdeSolution {
+    fn interpolate(&self, values: &[f64], asset_price: f64, derivative: usize) -> f64 {
+        let (first, weights) = lagrange_weights(&self.asset_prices, asset_price);
+        (0..3)
+            .map(|j| weights[derivative][j] * values[first + j])
+            .sum()
+    }
+
+    pub fn value(&self, asset_price: f64) -> f64 {
+        self.interpolate(&self.values, asset_price, 0)
+    }
+
+    /// dV/dS
+    pub fn delta(&self, asset_price: f64) -> f64 {
+        self.interpolate(&self.values, asset_price, 1)
+    }
+
+    /// d^2V/dS^2
+    pub fn gamma(&self, asset_price: f64) -> f64 {
+        self.interpolate(&self.values, asset_price, 2)
+    }
+
+    /// dV/dt from the values one time step later, or from the PDE at the expiration
+    pub fn theta(&self, asset_price: f64) -> f64 {
+        self.interpolate(&self.thetas, asset_price, 0)
+    }
+}
+
+/// The finite difference solver of the Black-Scholes PDE for European, American, Bermudan and
+/// knock-out options. See Tavella and Randall, chapters 3 and 4, and Wilmott, Paul Wilmott on
+/// Quantitative Finance, chapter 78.
+pub struct BlackScholesPde;
+
+impl BlackScholesPde {
+    pub fn solve(
+        pp: &PdeParameter,
+        exercise_type: &ExerciseType,
+    ) -> Result<PdeSolution, PricingError> {
+        let dp = &pp.option_params;
+        dp.validate()?;
+        pp.grid.validate()?;
+        PricingError::check_parameter(
+            "nr_time_steps",
+            pp.nr_time_steps as f64,
+            pp.nr_time_steps > 0,
+        )?;
+        if let EarlyExercise::ProjectedSor { relaxation, .. } = pp.early_exercise {
+            PricingError::check_parameter(
+                "relaxation",
+                relaxation,
+                relaxation > 0.0 && relaxation < 2.0,
+            )?;
+        }
+        let nodes = pp.nodes()?;
+        let n = nodes.len();
+        PricingError::check_parameter(
+            "asset_price",
+            dp.asset_price,
+            dp.asset_price >= nodes[0] && dp.asset_price <= nodes[n - 1],
+        )?;
+
+        let phi = match exercise_type {
+            ExerciseType::Call => 1.0,
+            ExerciseType::Put => -1.0,
+        };
+        let mut values: Vec<f64> = nodes
+            .iter()
+            .map(|s| (phi * (s - dp.strike)).max(0.0))
+            .collect();
+        // the values at nodes with a Dirichlet condition are not exercised
+        let mut obstacle = values.clone();
+        let (lower, upper) = pp.boundaries(exercise_type, 0.0);
+        for (boundary, i) in [(lower, 0), (upper, n - 1)] {
+            if let Boundary::Dirichlet(value) = boundary {
+                values[i] = value;
+                obstacle[i] = f64::NEG_INFINITY;
+            }
+        }
+
+        let operator = Self::operator(dp, &nodes);
+        let dt = dp.time_to_expiration / pp.nr_time_steps as f64;
+        let exercise_steps = pp.exercise_style.exercise_steps(pp.nr_time_steps, dt);
+        let mut later_values = values.clone();
+        let mut tau = 0.0;
+        for time_step in 0..pp.nr_time_steps {
+            if time_step + 1 == pp.nr_time_steps {
+                later_values = values.clone();
+            }
+            // the exercise steps count from today
+            let exercise = exercise_steps[pp.nr_time_steps - 1 - time_step];
+            for step in pp.scheme.sub_steps(time_step, dt) {
+                tau += step.0;
+                let boundaries = pp.boundaries(exercise_type, tau);
+                let (system, rhs) = Self::theta_step(&operator, &nodes, &values, step, boundaries);
+                values = match (exercise, pp.early_exercise) {
+                    (false, _) => system.solve(&rhs),
+                    (true, EarlyExercise::BrennanSchwartz) => {
+                        system.solve_brennan_schwartz(&rhs, &obstacle, phi < 0.0)
+                    }
+                    (
+                        true,
+                        EarlyExercise::ProjectedSor {
+                            relaxation,
+                            tolerance,
+                            max_iterations,
+                        },
+                    ) => {
+                        let initial = values
+                            .iter()
+                            .zip(&obstacle)
+                            .map(|(v, o)| v.max(*o))
+                            .collect();
+                        system.solve_projected_sor(
+                            &rhs,
+                            &obstacle,
+                            initial,
+                            (relaxation, tolerance, max_iterations),
+                        )?
+                    }
+                };
+            }
+        }
+
+        let thetas = if dt > 0.0 {
+            later_values
+                .iter()
+                .zip(&values)
+                .map(|(later, value)| (later - value) / dt)
+                .collect()
+        } else {
+            Self::expiration_thetas(&operator, &values, (lower, upper))
+        };
+        Ok(PdeSolution {
+            asset_prices: nodes,
+            values,
+            thetas,
+        })
+    }
+
+    pub fn price(pp: &PdeParameter, exercise_type: &ExerciseType) -> Result<f64, PricingError> {
+        Ok(Self::solve(pp, exercise_type)?.value(pp.option_params.asset_price))
+    }
+
+    /// The coefficients of the values at the lower, the same and the upper node in the spatial
+    /// operator at the inner nodes, by central differences on the non-uniform nodes.
+    fn operator(dp: &DerivativeParameter, nodes: &[f64]) -> Tridiagonal {
+        let n = nodes.len();
+        let mut operator = Tridiagonal {
+            lower: vec![0.0; n],
+            diag: vec![0.0; n],
+            upper: vec![0.0; n],
+        };
+        for i in 1..n - 1 {
+            let [first, second] = central_weights(nodes, i);
+            let diffusion = dp.vola.powi(2) * nodes[i].powi(2) / 2.0;
+            let convection = dp.cost_of_carry() * nodes[i];
+            let row: Vec<f64> = (0..3)
+                .map(|j| diffusion * second[j] + convection * first[j])
+                .collect();
+            operator.lower[i] = row[0];
+            operator.diag[i] = row[1] - dp.rfr;
+            operator.upper[i] = row[2];
+        }
+        operator
+    }
+
+    /// The time derivatives dV/dt = -LV of the payoff by the PDE, which are zero at a node with a
+    /// Dirichlet condition and those of the next node at a node with a Neumann condition.
+    fn expiration_thetas(
+        operator: &Tridiagonal,
+        values: &[f64],
+        (lower, upper): (Boundary, Boundary),
+    ) -> Vec<f64> {
+        let n = values.len();
+        let mut thetas: Vec<f64> = (0..n)
+            .map(|i| {
+                if i == 0 || i == n - 1 {
+                    return 0.0;
+                }
+                -(operator.lower[i] * values[i - 1]
+                    + operator.diag[i] * values[i]
+                    + operator.upper[i] * values[i + 1])
+            })
+            .collect();
+        if let Boundary::Neumann(_) = lower {
+            thetas[0] = thetas[1];
+        }
+        if let Boundary::Neumann(_) = upper {
+            thetas[n - 1] = thetas[n - 2];
+        }
+        thetas
+    }
+
+    /// The system of the theta step from the values, whose first and last rows impose the
+    /// boundary conditions.
+    fn theta_step(
+        operator: &Tridiagonal,
+        nodes: &[f64],
+        values: &[f64],
+        (dt, theta): (f64, f64),
+        (lower, upper): (Boundary, Boundary),
+    ) -> (Tridiagonal, Vec<f64>) {
+        let n = nodes.len();
+        let mut system = Tridiagonal {
+            lower: operator.lower.iter().map(|l| -theta * dt * l).collect(),
+            diag: operator.diag.iter().map(|d| 1.0 - theta * dt * d).collect(),
+            upper: operator.upper.iter().map(|u| -theta * dt * u).collect(),
+        };
+        let mut rhs: Vec<f64> = (0..n)
+            .map(|i| {
+                if i == 0 || i == n - 1 {
+                    return 0.0;
+                }
+                let change = operator.lower[i] * values[i - 1]
+                    + operator.diag[i] * values[i]
+                    + operator.upper[i] * values[i + 1];
+                values[i] + (1.0 - theta) * dt * change
+            })
+            .collect();
+
+        // the first row relates the lowest to the second node, the last row the highest to the
+        // next to highest node
+        system.diag[0] = 1.0;
+        system.diag[n - 1] = 1.0;
+        match lower {
+            Boundary::Dirichlet(value) => {
+                system.upper[0] = 0.0;
+                rhs[0] = value;
+            }
+            Boundary::Neumann(slope) => {
+                system.upper[0] = -1.0;
+                rhs[0] = -slope * (nodes[1] - nodes[0]);
+            }
+        }
+        match upper {
+            Boundary::Dirichlet(value) => {
+                system.lower[n - 1] = 0.0;
+                rhs[n - 1] = value;
+            }
+            Boundary::Neumann(slope) => {
+                system.lower[n - 1] = -1.0;
+                rhs[n - 1] = slope * (nodes[n - 1] - nodes[n - 2]);
+            }
+        }
+        (system, rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::barrier::{BarrierParameter, BarrierType, ReinerRubinstein};
+    use crate::analytic::black_scholes::{BlackScholesMerton, OptionGreeks, OptionPrice};
+    use assert_approx_eq::assert_approx_eq;
+
+    /// The American put of Longstaff and Schwartz with S = 36, K = 40, T = 1, r = 0.06 and
+    /// sigma = 0.2, valued by a binomial tree with 3000 steps.
+    const BINOMIAL_AMERICAN_PUT: f64 = 4.4867;
+
+    /// The same put exercisable at the ten dates 0.1, 0.2, ..., 1.0, valued by a binomial tree.
+    const BINOMIAL_BERMUDAN_PUT: f64 = 4.4426;
+
+    fn projected_sor() -> EarlyExercise {
+        EarlyExercise::ProjectedSor {
+            relaxation: 1.2,
+            tolerance: 1e-10,
+            max_iterations: 1000,
+        }
+    }
+
+    #[test]
+    fn european_options() {
+        let dp = DerivativeParameter::new(100.0, 110.0, 0.5, 0.05, 0.25).with_dividend_yield(0.02);
+        let pp = PdeParameter::new(dp, 401, 200).unwrap();
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            let reference = BlackScholesMerton::price(&dp, &exercise_type);
+            for (scheme, nr_time_steps, tolerance) in [
+                (Scheme::CrankNicolson { rannacher_steps: 2 }, 200, 1e-3),
+                (Scheme::Implicit, 2000, 2e-3),
+                // stable for time steps below the squared log spacing over the variance
+                (Scheme::Explicit, 2000, 1e-3),
+            ] {
+                let pp = PdeParameter {
+                    nr_time_steps,
+                    ..pp.clone().with_scheme(scheme)
+                };
+                let price = BlackScholesPde::price(&pp, &exercise_type).unwrap();
+                assert_approx_eq!(price, reference, tolerance);
+            }
+        }
+    }
+
+    #[test]
+    fn american_options() {
+        let dp = DerivativeParameter::new(36.0, 40.0, 1.0, 0.06, 0.2);
+        let pp = PdeParameter::new(dp, 401, 400)
+            .unwrap()
+            .with_exercise_style(ExerciseStyle::American);
+        let brennan_schwartz = BlackScholesPde::price(&pp, &ExerciseType::Put).unwrap();
+        let psor = BlackScholesPde::price(
+            &pp.clone().with_early_exercise(projected_sor()),
+            &ExerciseType::Put,
+        )
+        .unwrap();
+        assert_approx_eq!(brennan_schwartz, BINOMIAL_AMERICAN_PUT, 2e-3);
+        assert_approx_eq!(psor, brennan_schwartz, 1e-8);
+
+        // the American call on an asset without dividends is never exercised early
+        assert_approx_eq!(
+            BlackScholesPde::price(&pp, &ExerciseType::Call).unwrap(),
+            BlackScholesMerton::call(&dp),
+            1e-3
+        );
+
+        let bermudan = pp.with_exercise_style(ExerciseStyle::Bermudan(
+            (1..10).map(|k| k as f64 / 10.0).collect(),
+        ));
+        assert_approx_eq!(
+            BlackScholesPde::price(&bermudan, &ExerciseType::Put).unwrap(),
+            BINOMIAL_BERMUDAN_PUT,
+            2e-3
+        );
+    }
+
+    #[test]
+    fn knock_out_options() {
+        // Haug, The Complete Guide to Option Pricing Formulas, table 4-13
+        let dp = DerivativeParameter::new(100.0, 100.0, 0.5, 0.08, 0.25).with_dividend_yield(0.04);
+        for (barrier_type, knock_out, exercise_type) in [
+            (
+                BarrierType::DownAndOut,
+                KnockOut::Down {
+                    barrier: 95.0,
+                    rebate: 3.0,
+                },
+                ExerciseType::Call,
+            ),
+            (
+                BarrierType::UpAndOut,
+                KnockOut::Up {
+                    barrier: 105.0,
+                    rebate: 3.0,
+                },
+                ExerciseType::Put,
+            ),
+        ] {
+            let (KnockOut::Down { barrier, rebate } | KnockOut::Up { barrier, rebate }) = knock_out;
+            let bp = BarrierParameter::new(dp, barrier_type, barrier, rebate);
+            let pp = PdeParameter::new(dp, 801, 200)
+                .unwrap()
+                .with_knock_out(knock_out);
+            let solution = BlackScholesPde::solve(&pp, &exercise_type).unwrap();
+            let barrier_node = solution
+                .asset_prices
+                .iter()
+                .position(|s| *s == barrier)
+                .unwrap();
+            assert_eq!(solution.values[barrier_node], rebate);
+            assert_approx_eq!(
+                solution.value(100.0),
+                ReinerRubinstein::price(&bp, &exercise_type),
+                5e-4
+            );
+        }
+
+        let pp = PdeParameter::new(dp, 101, 10)
+            .unwrap()
+            .with_knock_out(KnockOut::Up {
+                barrier: 1000.0,
+                rebate: 0.0,
+            });
+        assert_eq!(
+            BlackScholesPde::price(&pp, &ExerciseType::Call).unwrap_err(),
+            PricingError::InvalidParameter {
+                name: "barrier",
+                value: 1000.0
+            }
+        );
+    }
+
+    #[test]
+    fn greeks() {
+        let dp = DerivativeParameter::new(100.0, 95.0, 0.75, 0.04, 0.25).with_dividend_yield(0.01);
+        let pp = PdeParameter::new(dp, 401, 400).unwrap();
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            let solution = BlackScholesPde::solve(&pp, &exercise_type).unwrap();
+            assert_approx_eq!(
+                solution.delta(100.0),
+                BlackScholesMerton::delta(&dp, &exercise_type),
+                1e-3
+            );
+            assert_approx_eq!(
+                solution.gamma(100.0),
+                BlackScholesMerton::gamma(&dp, &exercise_type),
+                1e-4
+            );
+            assert_approx_eq!(
+                solution.theta(100.0),
+                BlackScholesMerton::theta(&dp, &exercise_type),
+                1e-2
+            );
+        }
+    }
+
+    #[test]
+    fn expiration() {
+        // the payoff, whose time derivative is that of the discounted intrinsic value away from
+        // the strike
+        let dp = DerivativeParameter::new(100.0, 95.0, 0.0, 0.04, 0.25).with_dividend_yield(0.01);
+        let pp = PdeParameter::new(dp, 401, 10).unwrap();
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            let solution = BlackScholesPde::solve(&pp, &exercise_type).unwrap();
+            assert_eq!(
+                solution.value(100.0),
+                BlackScholesMerton::price(&dp, &exercise_type)
+            );
+            assert_approx_eq!(
+                solution.theta(100.0),
+                BlackScholesMerton::theta(&dp, &exercise_type),
+                1e-8
+            );
+        }
+    }
+
+    #[test]
+    fn boundary_slope() {
+        // the slope of the discounted forward shrinks with the time to expiration, which matters
+        // on a narrow grid over a long time
+        let dp = DerivativeParameter::new(100.0, 100.0, 5.0, 0.02, 0.3).with_dividend_yield(0.1);
+        let pp = PdeParameter::new(dp, 0, 400)
+            .unwrap()
+            .with_grid(Grid::log_spot(100.0 / 3.0, 300.0, 401).unwrap());
+        for exercise_type in [ExerciseType::Call, ExerciseType::Put] {
+            assert_approx_eq!(
+                BlackScholesPde::price(&pp, &exercise_type).unwrap(),
+                BlackScholesMerton::price(&dp, &exercise_type),
+                2e-3
+            );
+        }
+    }
+
+    #[test]
+    fn rannacher_smoothing() {
+        // with large time steps the kink of the payoff makes Crank-Nicolson oscillate
+        let dp = DerivativeParameter::new(100.0, 100.0, 0.25, 0.05, 0.2);
+        let reference = BlackScholesMerton::gamma(&dp, &ExerciseType::Call);
+        let gamma_error = |rannacher_steps| {
+            let pp = PdeParameter::new(dp, 801, 10)
+                .unwrap()
+                .with_scheme(Scheme::CrankNicolson { rannacher_steps });
+            let solution = BlackScholesPde::solve(&pp, &ExerciseType::Call).unwrap();
+            (solution.gamma(100.0) - reference).abs()
+        };
+        assert!(gamma_error(2) < 1e-3);
+        assert!(gamma_error(0) > 10.0 * gamma_error(2));
+    }
+
+    #[test]
+    fn non_uniform_grids() {
+        // nodes concentrated at the strike need fewer nodes than equally spaced ones
+        let dp = DerivativeParameter::new(100.0, 100.0, 1.0, 0.05, 0.3);
+        let reference = BlackScholesMerton::put(&dp);
+        let error = |grid: Grid| {
+            let pp = PdeParameter::new(dp, 0, 200).unwrap().with_grid(grid);
+            (BlackScholesPde::price(&pp, &ExerciseType::Put).unwrap() - reference).abs()
+        };
+        let sinh = error(Grid::sinh(0.0, 500.0, 100.0, 10.0, 101).unwrap());
+        let uniform = error(Grid::new((0..101).map(|i| i as f64 * 5.0).collect()));
+        assert!(sinh < 5e-3);
+        assert!(uniform > 5.0 * sinh);
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        let dp = DerivativeParameter::new(100.0, 100.0, 1.0, 0.05, 0.3);
+        let pp = PdeParameter::new(dp, 101, 10).unwrap();
+        assert_eq!(
+            BlackScholesPde::price(
+                &pp.clone()
+                    .with_grid(Grid::log_spot(110.0, 200.0, 11).unwrap()),
+                &ExerciseType::Call
+            ),
+            Err(PricingError::InvalidParameter {
+                name: "asset_price",
+                value: 100.0
+            })
+        );
+        assert!(BlackScholesPde::price(
+            &PdeParameter {
+                nr_time_steps: 0,
+                ..pp.clone()
+            },
+            &ExerciseType::Call
+        )
+        .is_err());
+        let pp = pp
+            .with_exercise_style(ExerciseStyle::American)
+            .with_early_exercise(EarlyExercise::ProjectedSor {
+                relaxation: 2.5,
+                tolerance: 1e-8,
+                max_iterations: 100,
+            });
+        assert!(BlackScholesPde::price(&pp, &ExerciseType::Put).is_err());
+    }
+}
@@ -0,0 +1,717 @@
+//! The two-factor PDE
+//! $\partial V / \partial \tau = a_{xx} V_{xx} + a_{yy} V_{yy} + a_{xy} V_{xy} + b_x V_x + b_y V_y - r V$
+//! in the time to expiration $\tau$, solved by alternating direction implicit (ADI) schemes. They
+//! treat the mixed derivative explicitly and each factor implicitly by tridiagonal systems along
+//! the lines of the grid. The second derivative across the boundaries of the grid is taken as
+//! zero and the first derivative by one-sided differences, which is exact where the diffusion
+//! vanishes, e.g. at zero asset prices or variances.
+//! See in 't Hout and Foulon, ADI Finite Difference Schemes for Option Pricing in the Heston
+//! Model with Correlation (2010).
+use ndarray::{Array2, Axis, Zip};
+
+use crate::analytic::heston::HestonParameter;
+use crate::analytic::spread::SpreadParameter;
+use crate::common::models::DerivativeParameter;
+use crate::error::PricingError;
+use crate::lattice::ExerciseStyle;
+use crate::pde::one_factor::KnockOut;
+use crate::pde::{central_weights, lagrange_weights, Grid, Tridiagonal};
+
+/// The largest variance of the default grid of the Heston model.
+const HESTON_MAX_VARIANCE: f64 = 5.0;
+
+/// The coefficients of the derivatives in the two-factor PDE at a point.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Coefficients {
+    pub xx: f64,
+    pub yy: f64,
+    pub xy: f64,
+    pub x: f64,
+    pub y: f64,
+}
+
+pub trait TwoFactorModel {
+    fn coefficients(&self, x: f64, y: f64) -> Coefficients;
+
+    /// the discount rate r
+    fn rfr(&self) -> f64;
+
+    fn validate(&self) -> Result<(), PricingError>;
+}
+
+/// The factors are the asset price and its variance.
+impl TwoFactorModel for HestonParameter {
+    fn coefficients(&self, asset_price: f64, variance: f64) -> Coefficients {
+        Coefficients {
+            xx: variance * asset_price.powi(2) / 2.0,
+            yy: self.vol_of_vol.powi(2) * variance / 2.0,
+            xy: self.correlation * self.vol_of_vol * variance * asset_price,
+            x: self.option_params.cost_of_carry() * asset_price,
+            y: self.mean_reversion * (self.long_term_variance - variance),
+        }
+    }
+
+    fn rfr(&self) -> f64 {
+        self.option_params.rfr
+    }
+
+    fn validate(&self) -> Result<(), PricingError> {
+        self.option_params.validate()?;
+        for (name, value) in [
+            ("mean_reversion", self.mean_reversion),
+            ("long_term_variance", self.long_term_variance),
+            ("vol_of_vol", self.vol_of_vol),
+        ] {
+            PricingError::check_parameter(name, value, value.is_finite() && value >= 0.0)?;
+        }
+        PricingError::check_parameter(
+            "correlation",
+            self.correlation,
+            self.correlation.abs() <= 1.0,
+        )
+    }
+}
+
+/// The factors are the prices of both assets.
+impl TwoFactorModel for SpreadParameter {
+    fn coefficients(&self, first: f64, second: f64) -> Coefficients {
+        let dp = &self.option_params;
+        Coefficients {
+            xx: dp.vola.powi(2) * first.powi(2) / 2.0,
+            yy: self.second_vola.powi(2) * second.powi(2) / 2.0,
+            xy: self.correlation * dp.vola * self.second_vola * first * second,
+            x: dp.cost_of_carry() * first,
+            y: (dp.rfr - self.second_dividend_yield) * second,
+        }
+    }
+
+    fn rfr(&self) -> f64 {
+        self.option_params.rfr
+    }
+
+    fn validate(&self) -> Result<(), PricingError> {
+        self.option_params.validate()?;
+        for (name, value) in [
+            ("second_asset_price", self.second_asset_price),
+            ("second_dividend_yield", self.second_dividend_yield),
+        ] {
+            PricingError::check_parameter(name, value, value.is_finite())?;
+        }
+        PricingError::check_parameter(
+            "second_vola",
+            self.second_vola,
+            self.second_vola.is_finite() && self.second_vola >= 0.0,
+        )?;
+        PricingError::check_parameter(
+            "correlation",
+            self.correlation,
+            self.correlation.abs() <= 1.0,
+        )
+    }
+}
+
+/// The ADI schemes, which are unconditionally stable for the theta of each.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AdiScheme {
+    /// first order with the mixed derivative, theta = 1/2.
+    /// See Douglas and Rachford, On the Numerical Solution of Heat Conduction Problems in Two and
+    /// Three Space Variables (1956).
+    Douglas,
+    /// second order by correcting the mixed derivative, theta = 1/2.
+    /// See Craig and Sneyd, An Alternating-Direction Implicit Scheme for Parabolic Equations with
+    /// Mixed Derivatives (1988).
+    CraigSneyd,
+    /// second order by correcting all derivatives, theta = 1/2 + sqrt(3)/6.
+    /// See Hundsdorfer and Verwer, Numerical Solution of Time-Dependent Advection-Diffusion-Reaction
+    /// Equations (2003).
+    HundsdorferVerwer,
+}
+
+impl AdiScheme {
+    fn theta(&self) -> f64 {
+        match self {
+            AdiScheme::Douglas | AdiScheme::CraigSneyd => 0.5,
+            AdiScheme::HundsdorferVerwer => 0.5 + 3.0_f64.sqrt() / 6.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AdiParameter {
+    /// the nodes of the first factor, e.g. the asset price
+    pub x_grid: Grid,
+    /// the nodes of the second factor, e.g. the variance or the second asset price
+    pub y_grid: Grid,
+    pub time_to_expiration: f64,
+    /// the number of time steps of the size of the time to expiration over it, at least one
+    pub nr_time_steps: usize,
+    pub scheme: AdiScheme,
+    /// the number of first time steps replaced by two half steps of the implicit Douglas scheme
+    /// each to damp the kink of the payoff
+    pub damping_steps: usize,
+    /// the holder exercises where the exercise value exceeds the value after the time step
+    pub exercise_style: ExerciseStyle,
+    /// a barrier on the first factor
+    pub knock_out: Option<KnockOut>,
+}
+
+impl AdiParameter {
+    /// European exercise by the Hundsdorfer-Verwer scheme with two damping steps.
+    pub fn new(x_grid: Grid, y_grid: Grid, time_to_expiration: f64, nr_time_steps: usize) -> Self {
+        Self {
+            x_grid,
+            y_grid,
+            time_to_expiration,
+            nr_time_steps,
+            scheme: AdiScheme::HundsdorferVerwer,
+            damping_steps: 2,
+            exercise_style: ExerciseStyle::European,
+            knock_out: None,
+        }
+    }
+
+    /// The asset prices up to eight times the strike concentrated at the strike, and the
+    /// variances up to five concentrated at zero, see in 't Hout and Foulon.
+    pub fn heston(
+        hp: &HestonParameter,
+        (nr_asset_nodes, nr_variance_nodes): (usize, usize),
+        nr_time_steps: usize,
+    ) -> Result<Self, PricingError> {
+        let dp = &hp.option_params;
+        let strike = dp.strike;
+        let x_grid = Grid::sinh(
+            0.0,
+            (8.0 * strike).max(2.0 * dp.asset_price),
+            strike,
+            strike / 5.0,
+            nr_asset_nodes,
+        )?;
+        let y_grid = Grid::sinh(
+            0.0,
+            HESTON_MAX_VARIANCE,
+            0.0,
+            HESTON_MAX_VARIANCE / 500.0,
+            nr_variance_nodes,
+        )?;
+        Ok(Self::new(
+            x_grid,
+            y_grid,
+            dp.time_to_expiration,
+            nr_time_steps,
+        ))
+    }
+
+    /// The prices of both assets equally spaced in the log price around them.
+    pub fn two_asset(
+        sp: &SpreadParameter,
+        nr_nodes: usize,
+        nr_time_steps: usize,
+    ) -> Result<Self, PricingError> {
+        let dp = &sp.option_params;
+        let first = DerivativeParameter {
+            strike: dp.asset_price,
+            ..*dp
+        };
+        let second = DerivativeParameter::new(
+            sp.second_asset_price,
+            sp.second_asset_price,
+            dp.time_to_expiration,
+            dp.rfr,
+            sp.second_vola,
+        )
+        .with_dividend_yield(sp.second_dividend_yield);
+        Ok(Self::new(
+            Grid::log_spot_around(&first, nr_nodes)?,
+            Grid::log_spot_around(&second, nr_nodes)?,
+            dp.time_to_expiration,
+            nr_time_steps,
+        ))
+    }
+
+    pub fn with_scheme(self, scheme: AdiScheme) -> Self {
+        Self { scheme, ..self }
+    }
+
+    pub fn with_damping_steps(self, damping_steps: usize) -> Self {
+        Self {
+            damping_steps,
+            ..self
+        }
+    }
+
+    pub fn with_exercise_style(self, exercise_style: ExerciseStyle) -> Self {
+        Self {
+            exercise_style,
+            ..self
+        }
+    }
+
+    pub fn with_knock_out(self, knock_out: KnockOut) -> Self {
+        Self {
+            knock_out: Some(knock_out),
+            ..self
+        }
+    }
+}
+
+/// The tridiagonal operator along the lines of one axis of the grid, with the coefficients of
+/// the lower, the same and the upper node at each node.
+struct LineOperator {
+    axis: Axis,
+    lower: Array2<f64>,
+    diag: Array2<f64>,
+    upper: Array2<f64>,
+}
+
+impl LineOperator {
+    fn apply(&self, values: &Array2<f64>) -> Array2<f64> {
+        let mut result = Array2::zeros(values.raw_dim());
+        Zip::from(result.lanes_mut(self.axis))
+            .and(values.lanes(self.axis))
+            .and(self.lower.lanes(self.axis))
+            .and(self.diag.lanes(self.axis))
+            .and(self.upper.lanes(self.axis))
+            .for_each(|mut result, values, lower, diag, upper| {
+                let n = values.len();
+                for i in 0..n {
+                    result[i] = diag[i] * values[i];
+                    if i > 0 {
+                        result[i] += lower[i] * values[i - 1];
+                    }
+                    if i + 1 < n {
+                        result[i] += upper[i] * values[i + 1];
+                    }
+                }
+            });
+        result
+    }
+
+    /// Solves $(I - s A) Y = R$ along every line.
+    fn solve(&self, scale: f64, rhs: &Array2<f64>) -> Array2<f64> {
+        let mut result = Array2::zeros(rhs.raw_dim());
+        Zip::from(result.lanes_mut(self.axis))
+            .and(rhs.lanes(self.axis))
+            .and(self.lower.lanes(self.axis))
+            .and(self.diag.lanes(self.axis))
+            .and(self.upper.lanes(self.axis))
+            .for_each(|mut result, rhs, lower, diag, upper| {
+                let system = Tridiagonal {
+                    lower: lower.iter().map(|l| -scale * l).collect(),
+                    diag: diag.iter().map(|d| 1.0 - scale * d).collect(),
+                    upper: upper.iter().map(|u| -scale * u).collect(),
+                };
+                let solution = system.solve(&rhs.to_vec());
+                for (value, solved) in result.iter_mut().zip(solution) {
+                    *value = solved;
+                }
+            });
+        result
+    }
+}
+
+/// The operators of the mixed derivative and of each factor, the latter with half of the
+/// discounting.
+struct Operators {
+    mixed: Array2<f64>,
+    x_weights: Vec<[f64; 3]>,
+    y_weights: Vec<[f64; 3]>,
+    x: LineOperator,
+    y: LineOperator,
+}
+
+impl Operators {
+    /// The operators on the nodes, which are zero at the fixed nodes of a barrier.
+    fn new(
+        model: &impl TwoFactorModel,
+        (x_nodes, y_nodes): (&[f64], &[f64]),
+        fixed: &Array2<bool>,
+    ) -> Self {
+        let (nx, ny) = (x_nodes.len(), y_nodes.len());
+        let first_weights = |nodes: &[f64]| -> Vec<[f64; 3]> {
+            (0..nodes.len())
+                .map(|i| match i {
+                    0 => [0.0; 3],
+                    i if i + 1 == nodes.len() => [0.0; 3],
+                    i => central_weights(nodes, i)[0],
+                })
+                .collect()
+        };
+        let mut operators = Self {
+            mixed: Array2::zeros((nx, ny)),
+            x_weights: first_weights(x_nodes),
+            y_weights: first_weights(y_nodes),
+            x: Self::line_operator(Axis(0), (nx, ny)),
+            y: Self::line_operator(Axis(1), (nx, ny)),
+        };
+        let half_rate = model.rfr() / 2.0;
+        for i in 0..nx {
+            for j in 0..ny {
+                if fixed[[i, j]] {
+                    continue;
+                }
+                let c = model.coefficients(x_nodes[i], y_nodes[j]);
+                if i > 0 && i + 1 < nx && j > 0 && j + 1 < ny {
+                    operators.mixed[[i, j]] = c.xy;
+                }
+                let x_row = Self::row(x_nodes, i, (c.xx, c.x));
+                let y_row = Self::row(y_nodes, j, (c.yy, c.y));
+                operators.x.lower[[i, j]] = x_row[0];
+                operators.x.diag[[i, j]] = x_row[1] - half_rate;
+                operators.x.upper[[i, j]] = x_row[2];
+                operators.y.lower[[i, j]] = y_row[0];
+                operators.y.diag[[i, j]] = y_row[1] - half_rate;
+                operators.y.upper[[i, j]] = y_row[2];
+            }
+        }
+        operators
+    }
+
+    fn line_operator(axis: Axis, shape: (usize, usize)) -> LineOperator {
+        LineOperator {
+            axis,
+            lower: Array2::zeros(shape),
+            diag: Array2::zeros(shape),
+            upper: Array2::zeros(shape),
+        }
+    }
+
+    /// The coefficients of the lower, the same and the upper node in the second and first
+    /// derivative along the nodes, one-sided and without the second derivative at the bounds.
+    fn row(nodes: &[f64], i: usize, (diffusion, convection): (f64, f64)) -> [f64; 3] {
+        let n = nodes.len();
+        if i == 0 {
+            let slope = convection / (nodes[1] - nodes[0]);
+            [0.0, -slope, slope]
+        } else if i + 1 == n {
+            let slope = convection / (nodes[n - 1] - nodes[n - 2]);
+            [-slope, slope, 0.0]
+        } else {
+            let [first, second] = central_weights(nodes, i);
+            [0, 1, 2].map(|k| diffusion * second[k] + convection * first[k])
+        }
+    }
+
+    fn apply_mixed(&self, values: &Array2<f64>) -> Array2<f64> {
+        let mut result = Array2::zeros(values.raw_dim());
+        for ((i, j), coefficient) in self.mixed.indexed_iter() {
+            if *coefficient == 0.0 {
+                continue;
+            }
+            let mut cross_derivative = 0.0;
+            for (k, x_weight) in self.x_weights[i].iter().enumerate() {
+                for (l, y_weight) in self.y_weights[j].iter().enumerate() {
+                    cross_derivative += x_weight * y_weight * values[[i + k - 1, j + l - 1]];
+                }
+            }
+            result[[i, j]] = coefficient * cross_derivative;
+        }
+        result
+    }
+
+    /// The step of the scheme from the values, see in 't Hout and Foulon, section 3.
+    fn step(&self, values: &Array2<f64>, dt: f64, theta: f64, scheme: AdiScheme) -> Array2<f64> {
+        let scale = theta * dt;
+        let (mixed, x, y) = (
+            self.apply_mixed(values),
+            self.x.apply(values),
+            self.y.apply(values),
+        );
+        let predictor = values + &(dt * (&mixed + &x + &y));
+        let first = self.x.solve(scale, &(&predictor - &(scale * &x)));
+        let second = self.y.solve(scale, &(&first - &(scale * &y)));
+        let corrector = match scheme {
+            AdiScheme::Douglas => return second,
+            AdiScheme::CraigSneyd => {
+                let correction = &self.apply_mixed(&second) - &mixed;
+                (predictor + 0.5 * dt * correction, x, y)
+            }
+            AdiScheme::HundsdorferVerwer => {
+                let (second_x, second_y) = (self.x.apply(&second), self.y.apply(&second));
+                let correction =
+                    &self.apply_mixed(&second) + &second_x + &second_y - &mixed - &x - &y;
+                (predictor + 0.5 * dt * correction, second_x, second_y)
+            }
+        };
+        let (predictor, x, y) = corrector;
+        let first = self.x.solve(scale, &(&predictor - &(scale * &x)));
+        self.y.solve(scale, &(&first - &(scale * &y)))
+    }
+}
+
+/// The option values on the nodes today, which give the price and the Greeks at any point
+/// within the grid by quadratic interpolation along both factors.
+#[derive(Clone, Debug)]
+pub struct TwoFactorSolution {
+    pub x_nodes: Vec<f64>,
+    pub y_nodes: Vec<f64>,
+    pub values: Array2<f64>,
+}
+
+impl TwoFactorSolution {
+    /// The derivative of the orders along both factors.
+    fn interpolate(&self, x: f64, y: f64, (x_order, y_order): (usize, usize)) -> f64 {
+        let (x_first, x_weights) = lagrange_weights(&self.x_nodes, x);
+        let (y_first, y_weights) = lagrange_weights(&self.y_nodes, y);
+        let mut value = 0.0;
+        for (k, x_weight) in x_weights[x_order].iter().enumerate() {
+            for (l, y_weight) in y_weights[y_order].iter().enumerate() {
+                value += x_weight * y_weight * self.values[[x_first + k, y_first + l]];
+            }
+        }
+        value
+    }
+
+    pub fn value(&self, x: f64, y: f64) -> f64 {
+        self.interpolate(x, y, (0, 0))
+    }
+
+    /// dV/dx and dV/dy
+    pub fn deltas(&self, x: f64, y: f64) -> [f64; 2] {
+        [
+            self.interpolate(x, y, (1, 0)),
+            self.interpolate(x, y, (0, 1)),
+        ]
+    }
+
+    /// The matrix of the second derivatives.
+    pub fn gammas(&self, x: f64, y: f64) -> [[f64; 2]; 2] {
+        let cross = self.interpolate(x, y, (1, 1));
+        [
+            [self.interpolate(x, y, (2, 0)), cross],
+            [cross, self.interpolate(x, y, (0, 2))],
+        ]
+    }
+}
+
+/// The ADI solver of two-factor PDEs for European, American, Bermudan and knock-out options
+/// with the payoff of both factors.
+pub struct TwoFactorPde;
+
+impl TwoFactorPde {
+    pub fn solve(
+        model: &impl TwoFactorModel,
+        ap: &AdiParameter,
+        payoff: impl Fn(f64, f64) -> f64,
+    ) -> Result<TwoFactorSolution, PricingError> {
+        model.validate()?;
+        ap.x_grid.validate()?;
+        ap.y_grid.validate()?;
+        PricingError::check_parameter(
+            "time_to_expiration",
+            ap.time_to_expiration,
+            ap.time_to_expiration.is_finite() && ap.time_to_expiration >= 0.0,
+        )?;
+        PricingError::check_parameter(
+            "nr_time_steps",
+            ap.nr_time_steps as f64,
+            ap.nr_time_steps > 0,
+        )?;
+        let x_nodes = match ap.knock_out {
+            Some(knock_out) => knock_out.cut(ap.x_grid.nodes())?,
+            None => ap.x_grid.nodes().to_vec(),
+        };
+        let y_nodes = ap.y_grid.nodes().to_vec();
+        let (nx, ny) = (x_nodes.len(), y_nodes.len());
+
+        // the nodes on the barrier keep the rebate
+        let barrier_node = match ap.knock_out {
+            Some(KnockOut::Down { .. }) => Some(0),
+            Some(KnockOut::Up { .. }) => Some(nx - 1),
+            None => None,
+        };
+        let fixed = Array2::from_shape_fn((nx, ny), |(i, _)| Some(i) == barrier_node);
+        let exercise_values = Array2::from_shape_fn((nx, ny), |(i, j)| {
+            if fixed[[i, j]] {
+                f64::NEG_INFINITY
+            } else {
+                payoff(x_nodes[i], y_nodes[j])
+            }
+        });
+        let mut values = Array2::from_shape_fn((nx, ny), |(i, j)| match ap.knock_out {
+            Some(knock_out) if fixed[[i, j]] => knock_out.rebate(),
+            _ => exercise_values[[i, j]],
+        });
+
+        let operators = Operators::new(model, (&x_nodes, &y_nodes), &fixed);
+        let dt = ap.time_to_expiration / ap.nr_time_steps as f64;
+        let exercise_steps = ap.exercise_style.exercise_steps(ap.nr_time_steps, dt);
+        for time_step in 0..ap.nr_time_steps {
+            if time_step < ap.damping_steps {
+                for _ in 0..2 {
+                    values = operators.step(&values, dt / 2.0, 1.0, AdiScheme::Douglas);
+                }
+            } else {
+                values = operators.step(&values, dt, ap.scheme.theta(), ap.scheme);
+            }
+            // the exercise steps count from today
+            if exercise_steps[ap.nr_time_steps - 1 - time_step] {
+                Zip::from(&mut values)
+                    .and(&exercise_values)
+                    .for_each(|value, exercise_value| *value = value.max(*exercise_value));
+            }
+        }
+
+        Ok(TwoFactorSolution {
+            x_nodes,
+            y_nodes,
+            values,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::barrier::{BarrierParameter, BarrierType, ReinerRubinstein};
+    use crate::analytic::black_scholes::{OptionGreeks, OptionPrice};
+    use crate::analytic::heston::Heston;
+    use crate::analytic::spread::Margrabe;
+    use crate::common::models::ExerciseType;
+    use crate::simulation::products::basket_option::MonteCarloEuropeanBasketOption;
+    use assert_approx_eq::assert_approx_eq;
+    use ndarray::{arr1, arr2};
+
+    fn call_payoff(strike: f64) -> impl Fn(f64, f64) -> f64 {
+        move |asset_price, _| (asset_price - strike).max(0.0)
+    }
+
+    #[test]
+    fn heston_european_options() {
+        // Fang and Oosterlee (2008), section 5.2
+        let dp = DerivativeParameter::new(100.0, 100.0, 1.0, 0.0, 0.0175_f64.sqrt());
+        let hp = HestonParameter::new(dp, 1.5768, 0.0398, 0.5751, -0.5711);
+        let reference = Heston::call(&hp);
+        for scheme in [
+            AdiScheme::Douglas,
+            AdiScheme::CraigSneyd,
+            AdiScheme::HundsdorferVerwer,
+        ] {
+            let ap = AdiParameter::heston(&hp, (100, 50), 50)
+                .unwrap()
+                .with_scheme(scheme);
+            let solution = TwoFactorPde::solve(&hp, &ap, call_payoff(100.0)).unwrap();
+            assert_approx_eq!(solution.value(100.0, 0.0175), reference, 2e-2);
+        }
+    }
+
+    #[test]
+    fn heston_greeks() {
+        let dp = DerivativeParameter::new(100.0, 105.0, 0.8, 0.05, 0.2).with_dividend_yield(0.02);
+        let hp = HestonParameter::new(dp, 2.0, 0.05, 0.5, -0.7);
+        let ap = AdiParameter::heston(&hp, (100, 50), 50).unwrap();
+        let solution = TwoFactorPde::solve(&hp, &ap, call_payoff(105.0)).unwrap();
+        let [delta, variance_delta] = solution.deltas(100.0, 0.04);
+        let [[gamma, _], _] = solution.gammas(100.0, 0.04);
+        let exercise_type = ExerciseType::Call;
+        assert_approx_eq!(delta, Heston::delta(&hp, &exercise_type), 2e-3);
+        assert_approx_eq!(gamma, Heston::gamma(&hp, &exercise_type), 2e-3);
+        // the vega refers to the volatility, whose square is the variance
+        assert_approx_eq!(
+            2.0 * 0.2 * variance_delta,
+            Heston::vega(&hp, &exercise_type),
+            5e-2
+        );
+    }
+
+    #[test]
+    fn heston_american_put() {
+        // Clarke and Parrott, Multigrid for American Option Pricing with Stochastic Volatility
+        // (1999), with the variance 0.0625
+        let dp = DerivativeParameter::new(10.0, 10.0, 0.25, 0.1, 0.25);
+        let hp = HestonParameter::new(dp, 5.0, 0.16, 0.9, 0.1);
+        let ap = AdiParameter::heston(&hp, (100, 50), 50)
+            .unwrap()
+            .with_exercise_style(ExerciseStyle::American);
+        let solution = TwoFactorPde::solve(&hp, &ap, |s, _| (10.0 - s).max(0.0)).unwrap();
+        for (asset_price, price) in [8.0, 9.0, 10.0, 11.0, 12.0]
+            .iter()
+            .zip([2.0, 1.107641, 0.520030, 0.213668, 0.082036])
+        {
+            assert_approx_eq!(solution.value(*asset_price, 0.0625), price, 3e-3);
+        }
+    }
+
+    #[test]
+    fn heston_knock_out() {
+        // an almost constant variance reproduces the Black-Scholes barrier option
+        let dp = DerivativeParameter::new(100.0, 100.0, 0.5, 0.08, 0.25).with_dividend_yield(0.04);
+        let hp = HestonParameter::new(dp, 1.0, 0.0625, 1e-4, 0.0);
+        let knock_out = KnockOut::Down {
+            barrier: 95.0,
+            rebate: 3.0,
+        };
+        let ap = AdiParameter::heston(&hp, (200, 50), 100)
+            .unwrap()
+            .with_knock_out(knock_out);
+        let solution = TwoFactorPde::solve(&hp, &ap, call_payoff(100.0)).unwrap();
+        let bp = BarrierParameter::new(dp, BarrierType::DownAndOut, 95.0, 3.0);
+        assert_approx_eq!(
+            solution.value(100.0, 0.0625),
+            ReinerRubinstein::call(&bp),
+            2e-3
+        );
+        assert!(solution.values.row(0).iter().all(|value| *value == 3.0));
+    }
+
+    #[test]
+    fn exchange_option() {
+        let dp = DerivativeParameter::new(100.0, 0.0, 1.0, 0.05, 0.3).with_dividend_yield(0.02);
+        let sp = SpreadParameter::new(dp, 95.0, 0.2, 0.5).with_second_dividend_yield(0.01);
+        let ap = AdiParameter::two_asset(&sp, 101, 50).unwrap();
+        let solution = TwoFactorPde::solve(&sp, &ap, |x, y| (x - y).max(0.0)).unwrap();
+        assert_approx_eq!(solution.value(100.0, 95.0), Margrabe::call(&sp), 5e-3);
+    }
+
+    #[test]
+    fn basket_monte_carlo() {
+        // the covariance of the Cholesky factor has the volatilities 0.2 and 0.25 and the
+        // correlation 0.6
+        let cholesky_factor = arr2(&[[0.2, 0.0], [0.15, 0.25 * 0.8]]);
+        let mc_option: MonteCarloEuropeanBasketOption<rand_hc::Hc128Rng> =
+            MonteCarloEuropeanBasketOption::new(
+                arr1(&[0.4, 0.6]),
+                arr1(&[100.0, 90.0]),
+                arr1(&[0.03, 0.03]),
+                cholesky_factor,
+                95.0,
+                1.0,
+            )
+            .and_then(|option| option.with_simulation(20_000, 20, 42))
+            .unwrap();
+        let dp = DerivativeParameter::new(100.0, 95.0, 1.0, 0.03, 0.2);
+        let sp = SpreadParameter::new(dp, 90.0, 0.25, 0.6);
+        let ap = AdiParameter::two_asset(&sp, 101, 50).unwrap();
+        let solution =
+            TwoFactorPde::solve(&sp, &ap, |x, y| (0.4 * x + 0.6 * y - 95.0).max(0.0)).unwrap();
+        // within about two standard errors of the simulation
+        assert_approx_eq!(solution.value(100.0, 90.0), mc_option.call().unwrap(), 0.15);
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        let dp = DerivativeParameter::new(100.0, 100.0, 1.0, 0.05, 0.2);
+        let hp = HestonParameter::new(dp, 1.0, 0.04, 0.3, -1.5);
+        let ap = AdiParameter::heston(&hp, (20, 10), 5).unwrap();
+        assert_eq!(
+            TwoFactorPde::solve(&hp, &ap, call_payoff(100.0)).unwrap_err(),
+            PricingError::InvalidParameter {
+                name: "correlation",
+                value: -1.5
+            }
+        );
+
+        let hp = HestonParameter::new(dp, 1.0, 0.04, 0.3, -0.5);
+        let knock_out = KnockOut::Up {
+            barrier: 1000.0,
+            rebate: 0.0,
+        };
+        assert!(
+            TwoFactorPde::solve(&hp, &ap.with_knock_out(knock_out), call_payoff(100.0)).is_err()
+        );
+        let ap = AdiParameter {
+            nr_time_steps: 0,
+            ..AdiParameter::heston(&hp, (20, 10), 5).unwrap()
+        };
+        assert!(TwoFactorPde::solve(&hp, &ap, call_payoff(100.0)).is_err());
+    }
+}
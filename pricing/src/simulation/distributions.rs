@@ -1,3 +1,4 @@
+use crate::error::PricingError;
 use crate::simulation::monte_carlo::PathGenerator;
 
 use ndarray::{arr1, Array1, Array2};
@@ -43,24 +44,17 @@ pub struct MultivariateNormalDistribution {
 
 /// https://en.wikipedia.org/wiki/Multivariate_normal_distribution
 impl MultivariateNormalDistribution {
-    pub fn new(mu: Array1<f64>, cholesky_factor: Array2<f64>) -> Self {
-        let mu_shape = mu.shape();
-        let matrix_shape = cholesky_factor.shape();
-
-        assert_eq!(matrix_shape, &[mu_shape[0], mu_shape[0]]);
+    pub fn new(mu: Array1<f64>, cholesky_factor: Array2<f64>) -> Result<Self, PricingError> {
+        let dim = mu.len();
+        PricingError::check_shape("cholesky_factor", cholesky_factor.shape(), &[dim, dim])?;
 
         // TODO: add a check that cholesky_factor is triangular;
         // or provide only a constructor using the correlation matrix (see https://docs.rs/ndarray-linalg/0.14.1/ndarray_linalg/cholesky/index.html)
-        // for i in 0..matrix_shape[0] {
-        //     for j in 0..matrix_shape[1] {
-        //         assert!(cholesky_factor[[[i, j]] == 0.0);
-        //     }
-        // }
 
-        Self {
+        Ok(Self {
             mu,
             cholesky_factor,
-        }
+        })
     }
 
     pub fn dim(&self) -> usize {
@@ -114,10 +108,7 @@ impl PathGenerator<Vec<Array1<f64>>> for MultivariateNormalDistribution {
         &self,
         rn_generator: &mut SeedRng,
         nr_samples: usize,
-    ) -> Vec<Array1<f64>>
-    where
-        SeedRng: rand::SeedableRng + rand::RngCore,
-    {
+    ) -> Vec<Array1<f64>> {
         let dim = self.dim();
         let standard_normals: Vec<f64> = StandardNormal.sample_path(rn_generator, nr_samples * dim);
 
@@ -156,12 +147,13 @@ mod tests {
 
         // 'forgets' the random part
         let cholesky_factor = arr2(&[[0.0, 0.0, 0.0], [0.0, 0.0, 0.0], [0.0, 0.0, 0.0]]);
-        let mv_normal = MultivariateNormalDistribution::new(mu.clone(), cholesky_factor);
+        let mv_normal = MultivariateNormalDistribution::new(mu.clone(), cholesky_factor).unwrap();
         let sample = mv_normal.sample(&mut rn_generator);
         assert_eq!(sample, mu);
 
         let cholesky_factor = arr2(&[[1.0, 0.5, 0.1], [0.0, 0.6, 0.7], [0.0, 0.0, 0.8]]);
-        let mv_normal = MultivariateNormalDistribution::new(mu.to_owned(), cholesky_factor);
+        let mv_normal =
+            MultivariateNormalDistribution::new(mu.to_owned(), cholesky_factor).unwrap();
         let sample = mv_normal.sample(&mut rn_generator);
         assert_eq!(
             sample,
@@ -175,7 +167,7 @@ mod tests {
 
         let mu = arr1(&[0.1, 0.2, 0.3]);
         let cholesky_factor = arr2(&[[1.0, 0.5, 0.1], [0.0, 0.6, 0.7], [0.0, 0.0, 0.8]]);
-        let mv_normal = MultivariateNormalDistribution::new(mu, cholesky_factor);
+        let mv_normal = MultivariateNormalDistribution::new(mu, cholesky_factor).unwrap();
         let samples: Array2<_> = mv_normal.sample_path(&mut rn_generator, 100_000);
 
         assert_eq!(samples.shape(), &[3, 100_000]);
@@ -101,6 +101,33 @@ where
     }
 }
 
+/// The sample mean of a Monte Carlo simulation with its standard error.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MonteCarloEstimate {
+    pub price: f64,
+    pub standard_error: f64,
+}
+
+impl MonteCarloEstimate {
+    pub fn from_samples(samples: &[f64]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let n = samples.len() as f64;
+        let price = samples.iter().sum::<f64>() / n;
+        let sum_of_squares = samples.iter().map(|x| (x - price).powi(2)).sum::<f64>();
+        let variance = if samples.len() > 1 {
+            sum_of_squares / (n - 1.0)
+        } else {
+            0.0
+        };
+        Some(Self {
+            price,
+            standard_error: (variance / n).sqrt(),
+        })
+    }
+}
+
 pub struct PathEvaluator<'a, Path> {
     paths: &'a [Path],
 }
@@ -229,4 +256,13 @@ mod tests {
         let avg = path_eval.evaluate_average(|path| path.last().cloned());
         assert_eq!(avg.unwrap(), (2.0 + 4.0) / 3.0);
     }
+
+    #[test]
+    fn estimate() {
+        let estimate = MonteCarloEstimate::from_samples(&[1.0, 2.0, 3.0, 6.0]).unwrap();
+        assert_eq!(estimate.price, 3.0);
+        // the sample variance is 14 / 3
+        assert_approx_eq!(estimate.standard_error, (14.0_f64 / 12.0).sqrt(), 1e-15);
+        assert_eq!(MonteCarloEstimate::from_samples(&[]), None);
+    }
 }
@@ -1 +1,612 @@
-//see https://github.com/xcycharles/derivatives/blob/15be6db5ed20bfac1b0883be277b3f45afa2cdf8/LSM_american_option.py#L14
+//! The least-squares Monte Carlo method of Longstaff and Schwartz for American options: the value
+//! of holding on is regressed on functions of the asset prices backwards in time, which gives an
+//! exercise policy. See Longstaff and Schwartz, Valuing American Options by Simulation: A Simple
+//! Least-Squares Approach (2001), and
+//! https://github.com/xcycharles/derivatives/blob/15be6db5ed20bfac1b0883be277b3f45afa2cdf8/LSM_american_option.py#L14
+use std::marker::PhantomData;
+
+use ndarray::Array2;
+
+use crate::common::models::DerivativeParameter;
+use crate::simulation::monte_carlo::{MonteCarloEstimate, MonteCarloPathSimulator};
+use crate::simulation::sde::gbm::GeometricBrownianMotion;
+
+/// The asset prices of a simulated path at the exercise dates after today, the last date being
+/// the expiration.
+pub trait ExercisePath {
+    fn nr_dates(&self) -> usize;
+    fn state(&self, date: usize) -> Vec<f64>;
+}
+
+/// The paths of [GeometricBrownianMotion] as sampled by [MonteCarloPathSimulator], which start
+/// after today. Paths starting with today's price, e.g. from
+/// [GeometricBrownianMotion::generate_path], are wrapped in [WithInitialValue].
+impl ExercisePath for Vec<f64> {
+    fn nr_dates(&self) -> usize {
+        self.len()
+    }
+
+    fn state(&self, date: usize) -> Vec<f64> {
+        vec![self[date]]
+    }
+}
+
+/// A path of a single asset which starts with today's price, e.g. from
+/// [crate::simulation::monte_carlo::Dynamics::transform].
+#[derive(Clone, Debug)]
+pub struct WithInitialValue<Path>(pub Path);
+
+impl ExercisePath for WithInitialValue<Vec<f64>> {
+    fn nr_dates(&self) -> usize {
+        self.0.len().saturating_sub(1)
+    }
+
+    fn state(&self, date: usize) -> Vec<f64> {
+        vec![self.0[date + 1]]
+    }
+}
+
+/// The paths of [crate::simulation::sde::multivariate_gbm::MultivariateGeometricBrownianMotion]
+/// hold the assets in rows and start with today's prices.
+impl ExercisePath for Array2<f64> {
+    fn nr_dates(&self) -> usize {
+        self.ncols().saturating_sub(1)
+    }
+
+    fn state(&self, date: usize) -> Vec<f64> {
+        self.column(date + 1).to_vec()
+    }
+}
+
+/// The functions of the asset prices which the value of holding on is regressed on.
+pub trait RegressionBasis {
+    fn evaluate(&self, state: &[f64]) -> Vec<f64>;
+}
+
+/// The monomials up to the degree of the asset prices divided by the scale, e.g. the strike, and
+/// from degree two the products of distinct asset prices.
+#[derive(Clone, Copy, Debug)]
+pub struct Polynomial {
+    degree: usize,
+    scale: f64,
+}
+
+impl Polynomial {
+    pub fn new(degree: usize, scale: f64) -> Self {
+        Self { degree, scale }
+    }
+}
+
+impl RegressionBasis for Polynomial {
+    fn evaluate(&self, state: &[f64]) -> Vec<f64> {
+        let x: Vec<f64> = state.iter().map(|s| s / self.scale).collect();
+        let mut values = vec![1.0];
+        for xi in &x {
+            values.extend((1..=self.degree).map(|k| xi.powi(k as i32)));
+        }
+        if self.degree >= 2 {
+            for (i, xi) in x.iter().enumerate() {
+                values.extend(x[i + 1..].iter().map(|xj| xi * xj));
+            }
+        }
+        values
+    }
+}
+
+/// The weighted Laguerre polynomials $e^{-x/2} L_k(x)$ up to the degree of the asset prices
+/// divided by the scale, and the constant, as proposed by Longstaff and Schwartz.
+#[derive(Clone, Copy, Debug)]
+pub struct Laguerre {
+    degree: usize,
+    scale: f64,
+}
+
+impl Laguerre {
+    pub fn new(degree: usize, scale: f64) -> Self {
+        Self { degree, scale }
+    }
+}
+
+impl RegressionBasis for Laguerre {
+    fn evaluate(&self, state: &[f64]) -> Vec<f64> {
+        let mut values = vec![1.0];
+        for s in state {
+            let x = s / self.scale;
+            let weight = (-x / 2.0).exp();
+            // the recurrence (k + 1) L_{k+1} = (2k + 1 - x) L_k - k L_{k-1}
+            let (mut previous, mut current) = (0.0, 1.0);
+            for k in 0..=self.degree {
+                values.push(weight * current);
+                let k = k as f64;
+                let next = ((2.0 * k + 1.0 - x) * current - k * previous) / (k + 1.0);
+                (previous, current) = (current, next);
+            }
+        }
+        values
+    }
+}
+
+/// The least-squares coefficients from the normal equations. The elimination drops the basis
+/// functions which depend linearly on the previous ones by setting their coefficients to zero,
+/// e.g. for perfectly correlated assets.
+fn least_squares(rows: &[Vec<f64>], targets: &[f64]) -> Vec<f64> {
+    let n = rows.first().map_or(0, Vec::len);
+    let mut matrix = vec![vec![0.0; n]; n];
+    let mut rhs = vec![0.0; n];
+    for (row, target) in rows.iter().zip(targets) {
+        for i in 0..n {
+            rhs[i] += row[i] * target;
+            for j in 0..n {
+                matrix[i][j] += row[i] * row[j];
+            }
+        }
+    }
+
+    // the elimination needs no pivoting as the matrix is positive semi-definite, and each pivot
+    // is the squared distance of the basis function from the span of the previous ones
+    let sizes: Vec<f64> = (0..n).map(|i| matrix[i][i]).collect();
+    let independent: Vec<bool> = (0..n)
+        .map(|k| {
+            let pivot = matrix[k][k];
+            if pivot <= 1e-10 * sizes[k] || pivot <= 0.0 {
+                return false;
+            }
+            let pivot_row = matrix[k].clone();
+            for i in k + 1..n {
+                let factor = matrix[i][k] / pivot;
+                for (entry, pivot_entry) in matrix[i][k..].iter_mut().zip(&pivot_row[k..]) {
+                    *entry -= factor * pivot_entry;
+                }
+                rhs[i] -= factor * rhs[k];
+            }
+            true
+        })
+        .collect();
+
+    let mut coefficients = vec![0.0; n];
+    for k in (0..n).rev() {
+        if independent[k] {
+            let known: f64 = (k + 1..n).map(|j| matrix[k][j] * coefficients[j]).sum();
+            coefficients[k] = (rhs[k] - known) / matrix[k][k];
+        }
+    }
+    coefficients
+}
+
+/// Decides whether to exercise at one of the exercise dates of an [ExercisePath], which should
+/// include exercising in the money at the expiration.
+pub trait ExerciseStrategy {
+    fn exercise(&self, date: usize, state: &[f64], exercise_value: f64) -> bool;
+}
+
+/// The value of exercising by the strategy on the path, discounted to the start of the path,
+/// whose dates are the dates of the strategy from the first date on.
+pub(crate) fn exercised_value<Path: ExercisePath>(
+    strategy: &impl ExerciseStrategy,
+    path: &Path,
+    first_date: usize,
+    step_discount_factor: f64,
+    payoff: impl Fn(&[f64]) -> f64,
+) -> f64 {
+    (0..path.nr_dates())
+        .find_map(|idx| {
+            let state = path.state(idx);
+            let exercise_value = payoff(&state);
+            strategy
+                .exercise(first_date + idx, &state, exercise_value)
+                .then(|| exercise_value * step_discount_factor.powi(idx as i32 + 1))
+        })
+        .unwrap_or(0.0)
+}
+
+/// The exercise decisions found by the regressions, one per exercise date.
+#[derive(Clone, Debug)]
+pub struct ExercisePolicy<Basis> {
+    basis: Basis,
+    /// unknown for the expiration and for dates without paths in the money
+    coefficients: Vec<Option<Vec<f64>>>,
+}
+
+impl<Basis: RegressionBasis> ExercisePolicy<Basis> {
+    pub fn nr_dates(&self) -> usize {
+        self.coefficients.len()
+    }
+
+    /// The regressed value of holding on at the date, discounted to the date.
+    pub fn continuation_value(&self, date: usize, state: &[f64]) -> Option<f64> {
+        self.coefficients[date].as_ref().map(|coefficients| {
+            let basis = self.basis.evaluate(state);
+            basis.iter().zip(coefficients).map(|(b, c)| b * c).sum()
+        })
+    }
+}
+
+impl<Basis: RegressionBasis> ExerciseStrategy for ExercisePolicy<Basis> {
+    /// Exercises in the money at expiration, and before if exercising is worth at least the
+    /// regressed value of holding on.
+    fn exercise(&self, date: usize, state: &[f64], exercise_value: f64) -> bool {
+        if exercise_value <= 0.0 {
+            return false;
+        }
+        if date + 1 == self.nr_dates() {
+            return true;
+        }
+        self.continuation_value(date, state)
+            .is_some_and(|continuation_value| exercise_value >= continuation_value)
+    }
+}
+
+/// Prices American and Bermudan options on paths with equidistant exercise dates.
+pub struct LongstaffSchwartz<Basis> {
+    basis: Basis,
+    /// the discount factor over one period between exercise dates
+    step_discount_factor: f64,
+}
+
+impl<Basis> LongstaffSchwartz<Basis>
+where
+    Basis: RegressionBasis + Clone,
+{
+    pub fn new(basis: Basis, rfr: f64, dt: f64) -> Self {
+        Self {
+            basis,
+            step_discount_factor: (-rfr * dt).exp(),
+        }
+    }
+
+    /// Regresses the discounted cash flows of the paths in the money on the basis, backwards from
+    /// the expiration, and exercises where the exercise value exceeds the regressed value.
+    pub fn fit<Path: ExercisePath>(
+        &self,
+        paths: &[Path],
+        payoff: impl Fn(&[f64]) -> f64,
+    ) -> ExercisePolicy<Basis> {
+        let nr_dates = paths.first().map_or(0, ExercisePath::nr_dates);
+        let mut coefficients = vec![None; nr_dates];
+        if nr_dates == 0 {
+            return ExercisePolicy {
+                basis: self.basis.clone(),
+                coefficients,
+            };
+        }
+
+        // the cash flows of the paths, discounted to the current date
+        let mut cash_flows: Vec<f64> = paths
+            .iter()
+            .map(|path| payoff(&path.state(nr_dates - 1)))
+            .collect();
+        for date in (0..nr_dates - 1).rev() {
+            cash_flows
+                .iter_mut()
+                .for_each(|cash_flow| *cash_flow *= self.step_discount_factor);
+
+            let mut in_the_money = Vec::new();
+            let mut rows = Vec::new();
+            for (idx, path) in paths.iter().enumerate() {
+                let state = path.state(date);
+                let exercise_value = payoff(&state);
+                if exercise_value > 0.0 {
+                    in_the_money.push((idx, exercise_value));
+                    rows.push(self.basis.evaluate(&state));
+                }
+            }
+            if rows.len() <= rows.first().map_or(0, Vec::len) {
+                // too few paths in the money for the regression, hence the policy holds on
+                continue;
+            }
+
+            let targets: Vec<f64> = in_the_money
+                .iter()
+                .map(|(idx, _)| cash_flows[*idx])
+                .collect();
+            let beta = least_squares(&rows, &targets);
+            for (row, (idx, exercise_value)) in rows.iter().zip(in_the_money) {
+                let continuation_value: f64 = row.iter().zip(&beta).map(|(b, c)| b * c).sum();
+                if exercise_value >= continuation_value {
+                    cash_flows[idx] = exercise_value;
+                }
+            }
+            coefficients[date] = Some(beta);
+        }
+
+        ExercisePolicy {
+            basis: self.basis.clone(),
+            coefficients,
+        }
+    }
+
+    /// The value of exercising by the policy on the paths, which is biased low as no policy beats
+    /// the optimal one, given paths independent of the ones the policy was fit on.
+    pub fn lower_bound<Path: ExercisePath>(
+        &self,
+        policy: &impl ExerciseStrategy,
+        paths: &[Path],
+        payoff: impl Fn(&[f64]) -> f64,
+    ) -> Option<MonteCarloEstimate> {
+        let samples: Vec<f64> = paths
+            .iter()
+            .map(|path| exercised_value(policy, path, 0, self.step_discount_factor, &payoff))
+            .collect();
+        MonteCarloEstimate::from_samples(&samples)
+    }
+
+    /// Fits the policy on the first half of the paths and prices with it on the second half.
+    pub fn price<Path: ExercisePath>(
+        &self,
+        paths: &[Path],
+        payoff: impl Fn(&[f64]) -> f64,
+    ) -> Option<MonteCarloEstimate> {
+        let (training_paths, pricing_paths) = paths.split_at(paths.len() / 2);
+        let policy = self.fit(training_paths, &payoff);
+        self.lower_bound(&policy, pricing_paths, payoff)
+    }
+}
+
+/// American options on a single asset, exercisable at the end of each time step.
+pub struct MonteCarloAmericanOption<SeedRng, Basis>
+where
+    SeedRng: rand::SeedableRng + rand::RngCore,
+{
+    pub option_params: DerivativeParameter,
+    pub basis: Basis,
+    pub seed_nr: u64,
+    pub nr_paths: usize,
+    pub nr_steps: usize,
+    _phantom_rng: PhantomData<SeedRng>,
+}
+
+impl<SeedRng, Basis> MonteCarloAmericanOption<SeedRng, Basis>
+where
+    SeedRng: rand::SeedableRng + rand::RngCore,
+    Basis: RegressionBasis + Clone,
+{
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        asset_price: f64,
+        strike: f64,
+        time_to_expiration: f64,
+        rfr: f64,
+        vola: f64,
+        dividend_yield: f64,
+        basis: Basis,
+        nr_paths: usize,
+        nr_steps: usize,
+        seed_nr: u64,
+    ) -> Self {
+        let option_params =
+            DerivativeParameter::new(asset_price, strike, time_to_expiration, rfr, vola)
+                .with_dividend_yield(dividend_yield);
+        Self {
+            option_params,
+            basis,
+            nr_paths,
+            nr_steps,
+            seed_nr,
+            _phantom_rng: PhantomData::<SeedRng>,
+        }
+    }
+
+    pub fn dt(&self) -> f64 {
+        self.option_params.time_to_expiration / self.nr_steps as f64
+    }
+
+    /// The Longstaff-Schwartz estimate, or the exercise value if exercising today is worth more.
+    pub fn estimate(&self, payoff: impl Fn(&[f64]) -> f64) -> Option<MonteCarloEstimate> {
+        let dp = &self.option_params;
+        // under the risk neutral measure we have mu = r - q
+        let stock_gbm =
+            GeometricBrownianMotion::new(dp.asset_price, dp.cost_of_carry(), dp.vola, self.dt());
+        let mc_simulator: MonteCarloPathSimulator<_, SeedRng, Vec<f64>> =
+            MonteCarloPathSimulator::new(stock_gbm, Some(self.seed_nr));
+        let paths = mc_simulator.simulate_paths(self.nr_paths, self.nr_steps);
+
+        let lsm = LongstaffSchwartz::new(self.basis.clone(), dp.rfr, self.dt());
+        let estimate = lsm.price(&paths, &payoff)?;
+        let exercise_value = payoff(&[dp.asset_price]);
+        if exercise_value > estimate.price {
+            return Some(MonteCarloEstimate {
+                price: exercise_value,
+                standard_error: 0.0,
+            });
+        }
+        Some(estimate)
+    }
+
+    pub fn call(&self) -> Option<MonteCarloEstimate> {
+        let strike = self.option_params.strike;
+        self.estimate(|state| (state[0] - strike).max(0.0))
+    }
+
+    pub fn put(&self) -> Option<MonteCarloEstimate> {
+        let strike = self.option_params.strike;
+        self.estimate(|state| (strike - state[0]).max(0.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::american::BjerksundStensland;
+    use crate::analytic::black_scholes::{BlackScholesMerton, OptionPrice};
+    use crate::simulation::sde::multivariate_gbm::MultivariateGeometricBrownianMotion;
+    use assert_approx_eq::assert_approx_eq;
+    use ndarray::{arr1, arr2};
+
+    /// Longstaff and Schwartz, table 1: the finite difference value of the American put with 50
+    /// exercise dates per year
+    const FINITE_DIFFERENCE_AMERICAN_PUT: f64 = 4.478;
+
+    #[test]
+    fn american_put() {
+        let mc_option: MonteCarloAmericanOption<rand_hc::Hc128Rng, _> =
+            MonteCarloAmericanOption::new(
+                36.0,
+                40.0,
+                1.0,
+                0.06,
+                0.2,
+                0.0,
+                Laguerre::new(2, 40.0),
+                20_000,
+                50,
+                42,
+            );
+        let put = mc_option.put().unwrap();
+        assert!(put.standard_error < 0.04);
+        assert_approx_eq!(
+            put.price,
+            FINITE_DIFFERENCE_AMERICAN_PUT,
+            3.0 * put.standard_error
+        );
+
+        // the early exercise premium
+        let european_put = BlackScholesMerton::put(&mc_option.option_params);
+        assert!(put.price > european_put + 0.5);
+    }
+
+    #[test]
+    fn regression_bases() {
+        for (strike, vola) in [(36.0, 0.4), (44.0, 0.2)] {
+            let laguerre: MonteCarloAmericanOption<rand_hc::Hc128Rng, _> =
+                MonteCarloAmericanOption::new(
+                    40.0,
+                    strike,
+                    1.0,
+                    0.06,
+                    vola,
+                    0.0,
+                    Laguerre::new(3, strike),
+                    10_000,
+                    50,
+                    7,
+                );
+            let polynomial: MonteCarloAmericanOption<rand_hc::Hc128Rng, _> =
+                MonteCarloAmericanOption::new(
+                    40.0,
+                    strike,
+                    1.0,
+                    0.06,
+                    vola,
+                    0.0,
+                    Polynomial::new(3, strike),
+                    10_000,
+                    50,
+                    7,
+                );
+            assert_approx_eq!(
+                laguerre.put().unwrap().price,
+                polynomial.put().unwrap().price,
+                0.03
+            );
+        }
+    }
+
+    #[test]
+    fn american_call_without_dividends() {
+        let mc_option: MonteCarloAmericanOption<rand_hc::Hc128Rng, _> =
+            MonteCarloAmericanOption::new(
+                40.0,
+                40.0,
+                1.0,
+                0.06,
+                0.2,
+                0.0,
+                Polynomial::new(2, 40.0),
+                20_000,
+                50,
+                42,
+            );
+        let call = mc_option.call().unwrap();
+        // never exercised early, hence the European call
+        let european_call = BlackScholesMerton::call(&mc_option.option_params);
+        assert_approx_eq!(call.price, european_call, 3.0 * call.standard_error);
+    }
+
+    #[test]
+    fn american_call_with_dividends() {
+        let mc_option: MonteCarloAmericanOption<rand_hc::Hc128Rng, _> =
+            MonteCarloAmericanOption::new(
+                40.0,
+                36.0,
+                1.0,
+                0.03,
+                0.2,
+                0.08,
+                Laguerre::new(2, 36.0),
+                20_000,
+                50,
+                42,
+            );
+        let call = mc_option.call().unwrap();
+        let american_call = BjerksundStensland::call(&mc_option.option_params);
+        assert_approx_eq!(call.price, american_call, 3.0 * call.standard_error + 0.02);
+
+        // the early exercise premium
+        let european_call = BlackScholesMerton::call(&mc_option.option_params);
+        assert!(call.price > european_call + 0.1);
+    }
+
+    #[test]
+    fn paths_with_initial_value() {
+        let dt = 1.0 / 50.0;
+        let stock_gbm = GeometricBrownianMotion::new(36.0, 0.06, 0.2, dt);
+        let mc_simulator: MonteCarloPathSimulator<_, rand_hc::Hc128Rng, Vec<f64>> =
+            MonteCarloPathSimulator::new(stock_gbm, Some(42));
+        let paths = mc_simulator.simulate_paths(2_000, 50);
+        let with_initial_value: Vec<_> = paths
+            .iter()
+            .map(|path| WithInitialValue([&[36.0], &path[..]].concat()))
+            .collect();
+        assert_eq!(with_initial_value[0].nr_dates(), 50);
+        assert_eq!(with_initial_value[0].state(49), paths[0].state(49));
+
+        let lsm = LongstaffSchwartz::new(Laguerre::new(2, 40.0), 0.06, dt);
+        let payoff = |state: &[f64]| (40.0 - state[0]).max(0.0);
+        assert_eq!(
+            lsm.price(&with_initial_value, payoff),
+            lsm.price(&paths, payoff)
+        );
+    }
+
+    #[test]
+    fn american_basket_put() {
+        // two perfectly correlated copies of the asset of the American put
+        let cholesky_factor = arr2(&[[0.2, 0.0], [0.2, 0.0]]);
+        let mv_gbm = MultivariateGeometricBrownianMotion::new(
+            arr1(&[36.0, 36.0]),
+            arr1(&[0.06, 0.06]),
+            cholesky_factor,
+            1.0 / 50.0,
+        )
+        .unwrap();
+        let mc_simulator: MonteCarloPathSimulator<_, rand_hc::Hc128Rng, Array2<f64>> =
+            MonteCarloPathSimulator::new(mv_gbm, Some(42));
+        let paths = mc_simulator.simulate_paths(20_000, 50);
+
+        // the regression drops the basis functions of the second asset as they are redundant
+        let lsm = LongstaffSchwartz::new(Polynomial::new(2, 40.0), 0.06, 1.0 / 50.0);
+        let put = lsm
+            .price(&paths, |state| {
+                (40.0 - 0.5 * (state[0] + state[1])).max(0.0)
+            })
+            .unwrap();
+        assert_approx_eq!(
+            put.price,
+            FINITE_DIFFERENCE_AMERICAN_PUT,
+            3.0 * put.standard_error
+        );
+    }
+
+    #[test]
+    fn linearly_dependent_basis() {
+        // y = 1 + 2x on the basis (1, x, 2x)
+        let rows: Vec<Vec<f64>> = (0..5)
+            .map(|i| vec![1.0, i as f64, 2.0 * i as f64])
+            .collect();
+        let targets: Vec<f64> = (0..5).map(|i| 1.0 + 2.0 * i as f64).collect();
+        let coefficients = least_squares(&rows, &targets);
+        assert_approx_eq!(coefficients[0], 1.0, 1e-12);
+        assert_approx_eq!(coefficients[1], 2.0, 1e-12);
+        assert_eq!(coefficients[2], 0.0);
+    }
+}
@@ -0,0 +1,250 @@
+//! The duality upper bound of Andersen and Broadie for American options: any martingale $M$
+//! with $M_0 = 0$ bounds the price by $E[\max_k (D_k h_k - M_k)]$ for the discounted exercise
+//! values $D_k h_k$, and the martingale of the values of an exercise strategy, estimated by nested
+//! simulations, gives a tight bound for a good strategy.
+//! See Andersen and Broadie, Primal-Dual Simulation Algorithm for Pricing Multidimensional
+//! American Options (2004), and Glasserman, Monte Carlo Methods in Financial Engineering,
+//! chapter 8.7.
+use std::marker::PhantomData;
+
+use crate::simulation::monte_carlo::{MonteCarloEstimate, MonteCarloPathSimulator, PathGenerator};
+use crate::simulation::products::american_option::{
+    exercised_value, ExercisePath, ExerciseStrategy,
+};
+
+/// The lower bound of exercising by a strategy and the upper bound of its dual, whose
+/// difference tells how far the strategy is from the optimal one.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PriceInterval {
+    pub lower_bound: MonteCarloEstimate,
+    pub upper_bound: MonteCarloEstimate,
+}
+
+pub struct AndersenBroadie<SeedRng>
+where
+    SeedRng: rand::SeedableRng + rand::RngCore,
+{
+    /// the discount factor over one period between exercise dates
+    step_discount_factor: f64,
+    /// the number of paths to estimate the duality gap on
+    nr_outer_paths: usize,
+    /// the number of paths of each nested simulation
+    nr_inner_paths: usize,
+    seed_nr: u64,
+    _phantom_rng: PhantomData<SeedRng>,
+}
+
+impl<SeedRng> AndersenBroadie<SeedRng>
+where
+    SeedRng: rand::SeedableRng + rand::RngCore,
+{
+    pub fn new(
+        rfr: f64,
+        dt: f64,
+        nr_outer_paths: usize,
+        nr_inner_paths: usize,
+        seed_nr: u64,
+    ) -> Self {
+        Self {
+            step_discount_factor: (-rfr * dt).exp(),
+            nr_outer_paths,
+            nr_inner_paths,
+            seed_nr,
+            _phantom_rng: PhantomData::<SeedRng>,
+        }
+    }
+
+    /// The value of following the strategy after the date of the state, discounted to that date
+    /// and estimated by inner paths started at the state.
+    fn continuation_value<Path: ExercisePath>(
+        &self,
+        strategy: &impl ExerciseStrategy,
+        path_generator: impl PathGenerator<Path>,
+        (first_date, nr_dates): (usize, usize),
+        payoff: impl Fn(&[f64]) -> f64,
+        seed_nr: u64,
+    ) -> f64 {
+        let mc_simulator: MonteCarloPathSimulator<_, SeedRng, Path> =
+            MonteCarloPathSimulator::new(path_generator, Some(seed_nr));
+        let paths = mc_simulator.simulate_paths(self.nr_inner_paths, nr_dates - first_date);
+        let total: f64 = paths
+            .iter()
+            .map(|path| {
+                exercised_value(
+                    strategy,
+                    path,
+                    first_date,
+                    self.step_discount_factor,
+                    &payoff,
+                )
+            })
+            .sum();
+        total / self.nr_inner_paths as f64
+    }
+
+    /// The price interval of the strategy on the paths, which must not be the ones the strategy
+    /// was fit on. The lower bound is estimated on all paths and the duality gap, which needs the
+    /// nested simulations at the dates the option is in the money, on the first outer paths. The path generator creates the dynamics
+    /// started at a state with the time step of the paths, and the initial state holds the prices
+    /// today.
+    pub fn price_interval<Path, PathGen>(
+        &self,
+        strategy: &impl ExerciseStrategy,
+        paths: &[Path],
+        initial_state: &[f64],
+        path_generator: impl Fn(&[f64]) -> PathGen,
+        payoff: impl Fn(&[f64]) -> f64,
+    ) -> Option<PriceInterval>
+    where
+        Path: ExercisePath,
+        PathGen: PathGenerator<Path>,
+    {
+        let discount_factor = |date: usize| self.step_discount_factor.powi(date as i32 + 1);
+        let lower_samples: Vec<f64> = paths
+            .iter()
+            .map(|path| exercised_value(strategy, path, 0, self.step_discount_factor, &payoff))
+            .collect();
+        let lower_bound = MonteCarloEstimate::from_samples(&lower_samples)?;
+
+        let mut seed_nr = self.seed_nr;
+        let gap_samples: Vec<f64> = paths
+            .iter()
+            .take(self.nr_outer_paths)
+            .map(|path| {
+                let nr_dates = path.nr_dates();
+                // the martingale M_{k+1} = M_k + L_{k+1} - E_k[L_{k+1}] of the discounted values L_k
+                // of following the strategy from date k on, shifted by E_0[L_1] to start with L_1,
+                // telescopes to M_k = L_k + sum_{i < k} (L_i - C_i) for the continuation values
+                // C_i = E_i[L_{i+1}], where L_i = C_i unless the strategy exercises at date i
+                let mut exercised_excess = 0.0;
+                let mut gap = payoff(initial_state) - lower_bound.price;
+                for date in 0..nr_dates {
+                    let state = path.state(date);
+                    let payoff_value = payoff(&state);
+                    let exercise = strategy.exercise(date, &state, payoff_value);
+                    let is_last = date + 1 == nr_dates;
+                    // out of the money the option is not exercised before the expiration, hence
+                    // the maximum is taken over the remaining dates, which need no inner paths
+                    if payoff_value <= 0.0 && !exercise && !is_last {
+                        continue;
+                    }
+                    let exercise_value = discount_factor(date) * payoff_value;
+                    let continuation_value = if is_last {
+                        0.0
+                    } else {
+                        seed_nr = seed_nr.wrapping_add(1);
+                        let value = self.continuation_value(
+                            strategy,
+                            path_generator(&state),
+                            (date + 1, nr_dates),
+                            &payoff,
+                            seed_nr,
+                        );
+                        discount_factor(date) * value
+                    };
+                    let value = if exercise {
+                        exercise_value
+                    } else {
+                        continuation_value
+                    };
+                    gap = f64::max(gap, exercise_value - value - exercised_excess);
+                    exercised_excess += value - continuation_value;
+                }
+                gap
+            })
+            .collect();
+        let duality_gap = MonteCarloEstimate::from_samples(&gap_samples)?;
+
+        // the duality gap hardly varies with the lower bound, hence the errors are added as if
+        // the estimates were independent
+        let upper_bound = MonteCarloEstimate {
+            price: lower_bound.price + duality_gap.price,
+            standard_error: lower_bound.standard_error.hypot(duality_gap.standard_error),
+        };
+        Some(PriceInterval {
+            lower_bound,
+            upper_bound,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::products::american_option::{Laguerre, LongstaffSchwartz};
+    use crate::simulation::sde::gbm::GeometricBrownianMotion;
+
+    /// The put of Longstaff and Schwartz with S = 36, K = 40, T = 1, r = 0.06 and sigma = 0.2,
+    /// exercisable at the ten dates 0.1, 0.2, ..., 1.0, valued by a binomial tree.
+    const BINOMIAL_BERMUDAN_PUT: f64 = 4.4426;
+    const DT: f64 = 0.1;
+
+    fn put_payoff(state: &[f64]) -> f64 {
+        (40.0 - state[0]).max(0.0)
+    }
+
+    fn stock_gbm(state: &[f64]) -> GeometricBrownianMotion {
+        GeometricBrownianMotion::new(state[0], 0.06, 0.2, DT)
+    }
+
+    fn simulate_paths(nr_paths: usize, seed_nr: u64) -> Vec<Vec<f64>> {
+        let mc_simulator: MonteCarloPathSimulator<_, rand_hc::Hc128Rng, Vec<f64>> =
+            MonteCarloPathSimulator::new(stock_gbm(&[36.0]), Some(seed_nr));
+        mc_simulator.simulate_paths(nr_paths, 10)
+    }
+
+    /// Exercises at the expiration only.
+    struct European {
+        nr_dates: usize,
+    }
+
+    impl ExerciseStrategy for European {
+        fn exercise(&self, date: usize, _state: &[f64], exercise_value: f64) -> bool {
+            date + 1 == self.nr_dates && exercise_value > 0.0
+        }
+    }
+
+    #[test]
+    fn longstaff_schwartz_policy() {
+        let lsm = LongstaffSchwartz::new(Laguerre::new(2, 40.0), 0.06, DT);
+        let policy = lsm.fit(&simulate_paths(20_000, 1), put_payoff);
+
+        let andersen_broadie: AndersenBroadie<rand_hc::Hc128Rng> =
+            AndersenBroadie::new(0.06, DT, 500, 200, 3);
+        let interval = andersen_broadie
+            .price_interval(
+                &policy,
+                &simulate_paths(20_000, 2),
+                &[36.0],
+                stock_gbm,
+                put_payoff,
+            )
+            .unwrap();
+        let (lower_bound, upper_bound) = (interval.lower_bound, interval.upper_bound);
+
+        // the duality gap of a good policy is small, even if the noise of the nested simulations
+        // widens it
+        assert!(upper_bound.price - lower_bound.price < 0.1);
+        assert!(lower_bound.price - 2.0 * lower_bound.standard_error < BINOMIAL_BERMUDAN_PUT);
+        assert!(upper_bound.price + 2.0 * upper_bound.standard_error > BINOMIAL_BERMUDAN_PUT);
+    }
+
+    #[test]
+    fn suboptimal_policy() {
+        // holding on to the put leaves the early exercise premium, which the upper bound reveals
+        let andersen_broadie: AndersenBroadie<rand_hc::Hc128Rng> =
+            AndersenBroadie::new(0.06, DT, 500, 200, 3);
+        let interval = andersen_broadie
+            .price_interval(
+                &European { nr_dates: 10 },
+                &simulate_paths(20_000, 2),
+                &[36.0],
+                stock_gbm,
+                put_payoff,
+            )
+            .unwrap();
+        assert!(interval.lower_bound.price < BINOMIAL_BERMUDAN_PUT - 0.4);
+        assert!(interval.upper_bound.price > BINOMIAL_BERMUDAN_PUT);
+        assert!(interval.upper_bound.price - interval.lower_bound.price > 0.5);
+    }
+}
@@ -20,17 +20,11 @@ where
     SeedRng: rand::SeedableRng + rand::RngCore,
 {
     pub fn new(
-        asset_price: f64,
-        strike: f64,
-        time_to_expiration: f64,
-        rfr: f64,
-        vola: f64,
+        option_params: DerivativeParameter,
         nr_paths: usize,
         nr_steps: usize,
         seed_nr: u64,
     ) -> Self {
-        let option_params =
-            DerivativeParameter::new(asset_price, strike, time_to_expiration, rfr, vola);
         Self {
             option_params,
             nr_paths,
@@ -40,6 +34,13 @@ where
         }
     }
 
+    pub fn with_dividend_yield(self, dividend_yield: f64) -> Self {
+        Self {
+            option_params: self.option_params.with_dividend_yield(dividend_yield),
+            ..self
+        }
+    }
+
     pub fn dt(&self) -> f64 {
         self.option_params.time_to_expiration / self.nr_steps as f64
     }
@@ -83,8 +84,8 @@ where
     R: rand::SeedableRng + rand::RngCore,
 {
     fn from(mceo: &MonteCarloEuropeanOption<R>) -> Self {
-        // under the risk neutral measure we have mu = r
-        let drift = mceo.option_params.rfr;
+        // under the risk neutral measure we have mu = r - q
+        let drift = mceo.option_params.cost_of_carry();
         GeometricBrownianMotion::new(
             mceo.option_params.asset_price,
             drift,
@@ -97,6 +98,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::analytic::black_scholes::{BlackScholesMerton, OptionPrice};
     use assert_approx_eq::assert_approx_eq;
 
     /// NOTE: the tolerance will depend on the number of samples paths and other params like steps and the volatility
@@ -105,8 +107,12 @@ mod tests {
 
     #[test]
     fn european_call() {
-        let mc_option: MonteCarloEuropeanOption<rand_hc::Hc128Rng> =
-            MonteCarloEuropeanOption::new(300.0, 310.0, 1.0, 0.03, 0.25, 20_000, 1000, 1);
+        let mc_option: MonteCarloEuropeanOption<rand_hc::Hc128Rng> = MonteCarloEuropeanOption::new(
+            DerivativeParameter::new(300.0, 310.0, 1.0, 0.03, 0.25),
+            20_000,
+            1000,
+            1,
+        );
         let call_price = mc_option.call().unwrap();
         assert_eq!(call_price, 29.76722498945371);
         assert_approx_eq!(call_price, 29.47, TOLERANCE);
@@ -114,18 +120,41 @@ mod tests {
 
     #[test]
     fn european_put() {
-        let mc_option: MonteCarloEuropeanOption<rand_hc::Hc128Rng> =
-            MonteCarloEuropeanOption::new(300.0, 290.0, 1.0, 0.03, 0.12, 100_000, 100, 42);
+        let mc_option: MonteCarloEuropeanOption<rand_hc::Hc128Rng> = MonteCarloEuropeanOption::new(
+            DerivativeParameter::new(300.0, 290.0, 1.0, 0.03, 0.12),
+            100_000,
+            100,
+            42,
+        );
         let put_price = mc_option.put().unwrap();
         assert_eq!(put_price, 6.4775539881225335);
         assert_approx_eq!(put_price, 6.547, TOLERANCE);
     }
 
+    #[test]
+    fn european_call_dividend_yield() {
+        let mc_option: MonteCarloEuropeanOption<rand_hc::Hc128Rng> = MonteCarloEuropeanOption::new(
+            DerivativeParameter::new(100.0, 95.0, 0.5, 0.10, 0.20),
+            50_000,
+            100,
+            42,
+        )
+        .with_dividend_yield(0.05);
+        let call_price = mc_option.call().unwrap();
+
+        let analytic_price = BlackScholesMerton::call(&mc_option.option_params);
+        assert_approx_eq!(call_price, analytic_price, TOLERANCE);
+    }
+
     /// Reference: https://predictivehacks.com/pricing-of-european-options-with-monte-carlo/
     #[test]
     fn european_put_as_of_reference() {
-        let mc_option: MonteCarloEuropeanOption<rand_hc::Hc128Rng> =
-            MonteCarloEuropeanOption::new(102.0, 100.0, 0.5, 0.02, 0.2, 1_000_000, 100, 42);
+        let mc_option: MonteCarloEuropeanOption<rand_hc::Hc128Rng> = MonteCarloEuropeanOption::new(
+            DerivativeParameter::new(102.0, 100.0, 0.5, 0.02, 0.2),
+            1_000_000,
+            100,
+            42,
+        );
         let put_price = mc_option.put().unwrap();
         assert_eq!(put_price, 4.2836072940653445); // black scholes ref: 4.293135
         assert_approx_eq!(put_price, 4.294683, TOLERANCE); // monte carlo ref: 4.294683
@@ -134,8 +163,12 @@ mod tests {
     /// Reference: https://predictivehacks.com/pricing-of-european-options-with-monte-carlo/
     #[test]
     fn european_call_as_of_reference() {
-        let mc_option: MonteCarloEuropeanOption<rand_hc::Hc128Rng> =
-            MonteCarloEuropeanOption::new(102.0, 100.0, 0.5, 0.02, 0.2, 1_000_000, 100, 111111);
+        let mc_option: MonteCarloEuropeanOption<rand_hc::Hc128Rng> = MonteCarloEuropeanOption::new(
+            DerivativeParameter::new(102.0, 100.0, 0.5, 0.02, 0.2),
+            1_000_000,
+            100,
+            111111,
+        );
         let call_price = mc_option.call().unwrap();
         assert_eq!(call_price, 7.297463800819357); // black scholes ref: 7.288151
         assert_approx_eq!(call_price, 7.290738, TOLERANCE); // monte carlo ref: 7.290738
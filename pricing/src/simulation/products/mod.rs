@@ -1,2 +1,5 @@
+pub mod american_option;
+pub mod andersen_broadie;
+pub mod asian_option;
 pub mod basket_option;
 pub mod european_option;
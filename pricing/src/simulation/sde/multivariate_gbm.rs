@@ -4,6 +4,7 @@ use ndarray_rand::RandomExt;
 use rand::Rng;
 use rand_distr::{Distribution, StandardNormal};
 
+use crate::error::PricingError;
 use crate::simulation::monte_carlo::PathGenerator;
 
 pub struct MultivariateGeometricBrownianMotion {
@@ -22,24 +23,22 @@ impl MultivariateGeometricBrownianMotion {
         drifts: Array1<f64>,
         cholesky_factor: Array2<f64>,
         dt: f64,
-    ) -> Self {
-        let iv_shape = initial_values.shape();
-        let drifts_shape = drifts.shape();
-        let matrix_shape = cholesky_factor.shape();
-
-        assert_eq!(iv_shape, drifts_shape);
-        assert_eq!(matrix_shape, &[drifts_shape[0], drifts_shape[0]]);
+    ) -> Result<Self, PricingError> {
+        let dim = initial_values.len();
+        PricingError::check_shape("drifts", drifts.shape(), &[dim])?;
+        PricingError::check_shape("cholesky_factor", cholesky_factor.shape(), &[dim, dim])?;
+        PricingError::check_parameter("dt", dt, dt.is_finite() && dt >= 0.0)?;
 
         // TODO: add a check that cholesky_factor is triangular; oR provide only a constructor using the correlation matrix
         // https://docs.rs/ndarray-linalg/0.9.0/ndarray_linalg/cholesky/index.html
         // use ndarray_linalg::cholesky::*;
 
-        Self {
+        Ok(Self {
             initial_values,
             drifts,
             cholesky_factor,
             dt,
-        }
+        })
     }
 
     fn dim(&self) -> usize {
@@ -149,13 +148,33 @@ mod tests {
         let dt = 4.0;
 
         let mv_gbm =
-            MultivariateGeometricBrownianMotion::new(initial_values, drifts, cholesky_factor, dt);
+            MultivariateGeometricBrownianMotion::new(initial_values, drifts, cholesky_factor, dt)
+                .unwrap();
 
         let rand_normals = arr1(&[0.1, -0.1, 0.05]);
         let sample = mv_gbm.step(&mv_gbm.initial_values, &rand_normals);
         assert_eq!(sample, arr1(&[1.51, 3.5, 6.84]));
     }
 
+    #[test]
+    fn dimension_mismatch() {
+        let cholesky_factor = arr2(&[[1.0, 0.5], [0.0, 0.6]]);
+        let mv_gbm = MultivariateGeometricBrownianMotion::new(
+            arr1(&[1.0, 2.0]),
+            arr1(&[0.1, 0.2, 0.3]),
+            cholesky_factor,
+            0.1,
+        );
+        assert_eq!(
+            mv_gbm.err(),
+            Some(PricingError::DimensionMismatch {
+                name: "drifts",
+                shape: vec![3],
+                expected: vec![2],
+            })
+        );
+    }
+
     #[test]
     fn basket_stock_price_simulation() {
         let nr_paths = 5_000;
@@ -167,7 +186,8 @@ mod tests {
         let dt = 1.0 / 100.0;
 
         let mv_gbm =
-            MultivariateGeometricBrownianMotion::new(initial_values, drifts, cholesky_factor, dt);
+            MultivariateGeometricBrownianMotion::new(initial_values, drifts, cholesky_factor, dt)
+                .unwrap();
 
         let mc_simulator: MonteCarloPathSimulator<_, rand_hc::Hc128Rng, Array2<f64>> =
             MonteCarloPathSimulator::new(mv_gbm, Some(42));